@@ -0,0 +1,47 @@
+//! parse a large `txlist` response with serde_json and simd-json
+#[macro_use]
+extern crate criterion;
+
+use criterion::Criterion;
+use ethers_core::types::H256;
+use ethers_etherscan::{
+    account::NormalTransaction,
+    fixtures::{list_response, with_fields, NORMAL_TRANSACTION},
+    Response,
+};
+
+/// A page of 10000 transactions, the most `txlist` returns at once
+fn txlist_page() -> String {
+    let rows: Vec<_> = (0..10_000u64)
+        .map(|i| {
+            let fields = serde_json::json!({
+                "blockNumber": (17_000_000 + i / 4).to_string(),
+                "hash": format!("{:?}", H256::from_low_u64_be(i)),
+                "transactionIndex": (i % 4).to_string(),
+            });
+            with_fields(NORMAL_TRANSACTION, fields).to_string()
+        })
+        .collect();
+    let rows: Vec<_> = rows.iter().map(String::as_str).collect();
+    list_response(&rows)
+}
+
+fn parse_list_benchmark(c: &mut Criterion) {
+    let body = txlist_page();
+
+    let mut group = c.benchmark_group("parse txlist");
+    group.bench_function("serde_json", |b| {
+        b.iter(|| {
+            serde_json::from_str::<Response<Vec<NormalTransaction>>>(&body).unwrap();
+        });
+    });
+    group.bench_function("simd-json", |b| {
+        b.iter(|| {
+            let mut bytes = body.as_bytes().to_vec();
+            simd_json::serde::from_slice::<Response<Vec<NormalTransaction>>>(&mut bytes).unwrap();
+        });
+    });
+}
+
+criterion_group!(benches, parse_list_benchmark);
+criterion_main!(benches);
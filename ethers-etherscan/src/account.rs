@@ -1,15 +1,174 @@
-use crate::{Client, EtherscanError, Query, Response, Result};
+use crate::{
+    analytics::TxSummary,
+    check_ok, check_string_ok,
+    currency::NativeCurrency,
+    utils::{
+        deserialize_numeric_or_none, deserialize_numeric_or_zero, deserialize_u64_or_none,
+        deserialize_u64_or_zero,
+    },
+    Client, EtherscanError, ProxyResponse, Query, Response, Result, MAX_RESULT_WINDOW,
+};
 use ethers_core::{
-    abi::Address,
+    abi::{Abi, Address, Function, Token},
     types::{serde_helpers::*, BlockNumber, Bytes, H256, H32, U256},
+    utils::to_checksum,
 };
-use serde::{Deserialize, Serialize};
+use futures_util::{stream, Stream, TryStreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::{Display, Error, Formatter},
 };
 
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+
+/// The number of decimals of the native currency
+#[cfg(feature = "decimal")]
+const NATIVE_DECIMALS: u32 = 18;
+
+/// Converts an integer amount of the smallest unit into a [Decimal] scaled by `decimals`.
+///
+/// Returns `None` if the amount can't be represented exactly.
+#[cfg(feature = "decimal")]
+fn to_decimal(amount: U256, decimals: u32) -> Option<Decimal> {
+    let mantissa = i128::try_from(u128::try_from(amount).ok()?).ok()?;
+    Decimal::try_from_i128_with_scale(mantissa, decimals).ok()
+}
+
+/// The columns written by [Client::export_transactions_csv]
+#[cfg(feature = "csv")]
+const CSV_HEADER: [&str; 12] = [
+    "hash",
+    "block_number",
+    "timestamp",
+    "from",
+    "to",
+    "value",
+    "gas",
+    "gas_price",
+    "gas_used",
+    "is_error",
+    "contract_address",
+    "function_name",
+];
+
+/// Writes a transaction as a CSV row, with the timestamp as RFC 3339 and the value in ether.
+#[cfg(feature = "csv")]
+fn write_csv_row<W: std::io::Write>(
+    writer: &mut csv::Writer<W>,
+    tx: &NormalTransaction,
+) -> Result<()> {
+    let timestamp = tx
+        .time_stamp
+        .parse()
+        .ok()
+        .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+        .map(|time| time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        .unwrap_or_else(|| tx.time_stamp.clone());
+    let address = |address: &Address| format!("{address:#x}");
+    let from = match &tx.from {
+        GenesisOption::Some(from) => address(from),
+        GenesisOption::Genesis => "GENESIS".to_string(),
+        GenesisOption::None => String::new(),
+    };
+    let optional = |value: Option<String>| value.unwrap_or_default();
+
+    writer.write_record([
+        optional(tx.hash.value().map(|hash| format!("{hash:?}"))),
+        optional(tx.block_number.as_number().map(|block| block.to_string())),
+        timestamp,
+        from,
+        optional(tx.to.as_ref().map(address)),
+        ethers_core::utils::format_units(tx.value, "ether").expect("ether is a valid unit"),
+        tx.gas.to_string(),
+        optional(tx.gas_price.map(|price| price.to_string())),
+        tx.gas_used.to_string(),
+        tx.is_error.clone(),
+        optional(tx.contract_address.as_ref().map(address)),
+        optional(tx.function_name.clone()),
+    ])?;
+    Ok(())
+}
+
+/// Converts transactions into an arrow [RecordBatch](arrow::record_batch::RecordBatch) with one
+/// row per transaction, for loading them into DataFusion or Polars.
+///
+/// The columns are those of [Client::export_transactions_csv], with `block_number` as `Int64`, the
+/// `timestamp` in seconds since the epoch in UTC, the `value` in wei as `Decimal128(38, 0)` and the
+/// gas fields as `UInt64`. The columns of the `Option` and [GenesisOption] fields are nullable,
+/// with the `GENESIS` placeholders of genesis allocations as nulls, and so are values that don't
+/// fit their column.
+#[cfg(feature = "arrow")]
+pub fn transactions_to_record_batch(txs: &[NormalTransaction]) -> arrow::record_batch::RecordBatch {
+    use arrow::{
+        array::{
+            ArrayRef, BooleanArray, Decimal128Array, Int64Array, StringArray, TimestampSecondArray,
+            UInt64Array,
+        },
+        datatypes::{DataType, Field, Schema, TimeUnit},
+        record_batch::RecordBatch,
+    };
+    use std::sync::Arc;
+
+    let addresses = |address: fn(&NormalTransaction) -> Option<&Address>| {
+        let column: StringArray =
+            txs.iter().map(|tx| address(tx).map(|address| format!("{address:#x}"))).collect();
+        Arc::new(column) as ArrayRef
+    };
+    let gas = |gas: fn(&NormalTransaction) -> Option<U256>| {
+        let column: UInt64Array =
+            txs.iter().map(|tx| gas(tx).and_then(|gas| u64::try_from(gas).ok())).collect();
+        Arc::new(column) as ArrayRef
+    };
+
+    let hashes: StringArray =
+        txs.iter().map(|tx| tx.hash.value().map(|hash| format!("{hash:?}"))).collect();
+    let block_numbers: Int64Array = txs
+        .iter()
+        .map(|tx| tx.block_number.as_number().and_then(|block| i64::try_from(block.as_u64()).ok()))
+        .collect();
+    let timestamps: TimestampSecondArray =
+        txs.iter().map(|tx| tx.time_stamp.parse::<i64>().ok()).collect();
+    let values: Decimal128Array = txs
+        .iter()
+        .map(|tx| u128::try_from(tx.value).ok().and_then(|value| i128::try_from(value).ok()))
+        .collect();
+    let is_error: BooleanArray = txs.iter().map(|tx| Some(tx.is_error == "1")).collect();
+    let function_names: StringArray = txs.iter().map(|tx| tx.function_name.as_deref()).collect();
+
+    let schema = Schema::new(vec![
+        Field::new("hash", DataType::Utf8, true),
+        Field::new("block_number", DataType::Int64, true),
+        Field::new("timestamp", DataType::Timestamp(TimeUnit::Second, Some("UTC".into())), true),
+        Field::new("from", DataType::Utf8, true),
+        Field::new("to", DataType::Utf8, true),
+        Field::new("value", DataType::Decimal128(38, 0), true),
+        Field::new("gas", DataType::UInt64, true),
+        Field::new("gas_price", DataType::UInt64, true),
+        Field::new("gas_used", DataType::UInt64, true),
+        Field::new("is_error", DataType::Boolean, false),
+        Field::new("contract_address", DataType::Utf8, true),
+        Field::new("function_name", DataType::Utf8, true),
+    ]);
+    let columns = vec![
+        Arc::new(hashes) as ArrayRef,
+        Arc::new(block_numbers),
+        Arc::new(timestamps.with_timezone("UTC")),
+        addresses(|tx| tx.from.value()),
+        addresses(|tx| tx.to.as_ref()),
+        Arc::new(values.with_precision_and_scale(38, 0).expect("valid decimal precision")),
+        gas(|tx| Some(tx.gas)),
+        gas(|tx| tx.gas_price),
+        gas(|tx| Some(tx.gas_used)),
+        Arc::new(is_error),
+        addresses(|tx| tx.contract_address.as_ref()),
+        Arc::new(function_names),
+    ];
+    RecordBatch::try_new(Arc::new(schema), columns).expect("columns match the schema")
+}
+
 /// The raw response from the balance-related API endpoints
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AccountBalance {
@@ -17,6 +176,58 @@ pub struct AccountBalance {
     pub balance: String,
 }
 
+impl AccountBalance {
+    /// Formats the balance in whole units of `currency`, e.g. `1.5 MATIC` with the currency of
+    /// [Client::native_currency].
+    ///
+    /// Returns `None` if the balance is not a decimal number.
+    pub fn format(&self, currency: &NativeCurrency) -> Option<String> {
+        Some(currency.format(U256::from_dec_str(&self.balance).ok()?))
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl AccountBalance {
+    /// Returns the balance in ether.
+    ///
+    /// Returns `None` if the balance can't be represented exactly as a [Decimal].
+    pub fn balance_decimal(&self) -> Option<Decimal> {
+        to_decimal(U256::from_dec_str(&self.balance).ok()?, NATIVE_DECIMALS)
+    }
+}
+
+/// An overview of an address, see [Client::get_address_info]
+#[derive(Clone, Debug)]
+pub struct AddressInfo {
+    /// The balance in wei at the latest block
+    pub balance: String,
+    /// The number of transactions sent by the address, i.e. its nonce
+    pub transaction_count: U256,
+    /// The first transaction of the address, see [Client::get_first_transaction]
+    pub first_transaction: Option<NormalTransaction>,
+    /// The most recent transaction of the address, see [Client::get_last_transaction]
+    pub last_transaction: Option<NormalTransaction>,
+    /// Whether code is deployed at the address
+    pub is_contract: bool,
+}
+
+/// Serializes a value into the bare string the explorer would return for it, i.e. without the
+/// quotes that wrap strings in JSON.
+fn to_bare_string<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    Ok(match serde_json::to_value(value)? {
+        serde_json::Value::String(s) => s,
+        other => other.to_string(),
+    })
+}
+
+/// Deserializes a value from the bare string the explorer returned for it.
+///
+/// The string is passed on as is rather than quoted and parsed again, so quotes and backslashes
+/// in it can't change its meaning.
+fn from_bare_string<T: DeserializeOwned>(s: Cow<'_, str>) -> serde_json::Result<T> {
+    serde_json::from_value(serde_json::Value::String(s.into_owned()))
+}
+
 mod genesis_string {
     use super::*;
     use serde::{
@@ -36,9 +247,7 @@ mod genesis_string {
         let json = match value {
             GenesisOption::None => Cow::from(""),
             GenesisOption::Genesis => Cow::from("GENESIS"),
-            GenesisOption::Some(value) => {
-                serde_json::to_string(value).map_err(S::Error::custom)?.into()
-            }
+            GenesisOption::Some(value) => to_bare_string(value).map_err(S::Error::custom)?.into(),
         };
         serializer.serialize_str(&json)
     }
@@ -52,9 +261,7 @@ mod genesis_string {
     {
         let json = Cow::<'de, str>::deserialize(deserializer)?;
         if !json.is_empty() && !json.starts_with("GENESIS") {
-            serde_json::from_str(&format!("\"{}\"", &json))
-                .map(GenesisOption::Some)
-                .map_err(D::Error::custom)
+            from_bare_string(json).map(GenesisOption::Some).map_err(D::Error::custom)
         } else if json.starts_with("GENESIS") {
             Ok(GenesisOption::Genesis)
         } else {
@@ -78,7 +285,7 @@ mod json_string {
     {
         let json = match value {
             Option::None => Cow::from(""),
-            Option::Some(value) => serde_json::to_string(value).map_err(S::Error::custom)?.into(),
+            Option::Some(value) => to_bare_string(value).map_err(S::Error::custom)?.into(),
         };
         serializer.serialize_str(&json)
     }
@@ -92,9 +299,7 @@ mod json_string {
         if json.is_empty() {
             Ok(Option::None)
         } else {
-            serde_json::from_str(&format!("\"{}\"", &json))
-                .map(Option::Some)
-                .map_err(D::Error::custom)
+            from_bare_string(json).map(Option::Some).map_err(D::Error::custom)
         }
     }
 }
@@ -114,7 +319,7 @@ mod hex_string {
     {
         let json = match value {
             Option::None => Cow::from("0x"),
-            Option::Some(value) => serde_json::to_string(value).map_err(S::Error::custom)?.into(),
+            Option::Some(value) => to_bare_string(value).map_err(S::Error::custom)?.into(),
         };
         serializer.serialize_str(&json)
     }
@@ -128,9 +333,7 @@ mod hex_string {
         if json.is_empty() || json == "0x" {
             Ok(Option::None)
         } else {
-            serde_json::from_str(&format!("\"{}\"", &json))
-                .map(Option::Some)
-                .map_err(D::Error::custom)
+            from_bare_string(json).map(Option::Some).map_err(D::Error::custom)
         }
     }
 }
@@ -182,28 +385,28 @@ pub struct NormalTransaction {
     pub nonce: Option<U256>,
     #[serde(with = "json_string")]
     pub block_hash: Option<U256>,
-    #[serde(deserialize_with = "deserialize_stringified_u64_opt")]
+    #[serde(deserialize_with = "deserialize_u64_or_none")]
     pub transaction_index: Option<u64>,
     #[serde(with = "genesis_string")]
     pub from: GenesisOption<Address>,
     #[serde(with = "json_string")]
     pub to: Option<Address>,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub value: U256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub gas: U256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric_opt")]
+    #[serde(deserialize_with = "deserialize_numeric_or_none")]
     pub gas_price: Option<U256>,
     #[serde(rename = "txreceipt_status")]
     pub tx_receipt_status: String,
     pub input: Bytes,
     #[serde(with = "json_string")]
     pub contract_address: Option<Address>,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub gas_used: U256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub cumulative_gas_used: U256,
-    #[serde(deserialize_with = "deserialize_stringified_u64")]
+    #[serde(deserialize_with = "deserialize_u64_or_zero")]
     pub confirmations: u64,
     #[serde(with = "hex_string")]
     pub method_id: Option<H32>,
@@ -211,6 +414,83 @@ pub struct NormalTransaction {
     pub function_name: Option<String>,
 }
 
+/// A [NormalTransaction] borrowing its text fields from the response body, see
+/// [Client::get_transactions_with]
+///
+/// The input is kept as hex text instead of being decoded.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalTransactionRef<'a> {
+    #[serde(borrow)]
+    pub is_error: Cow<'a, str>,
+    #[serde(deserialize_with = "deserialize_stringified_block_number")]
+    pub block_number: BlockNumber,
+    #[serde(borrow)]
+    pub time_stamp: Cow<'a, str>,
+    #[serde(with = "genesis_string")]
+    pub hash: GenesisOption<H256>,
+    #[serde(with = "json_string")]
+    pub nonce: Option<U256>,
+    #[serde(with = "json_string")]
+    pub block_hash: Option<U256>,
+    #[serde(deserialize_with = "deserialize_u64_or_none")]
+    pub transaction_index: Option<u64>,
+    #[serde(with = "genesis_string")]
+    pub from: GenesisOption<Address>,
+    #[serde(with = "json_string")]
+    pub to: Option<Address>,
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
+    pub value: U256,
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
+    pub gas: U256,
+    #[serde(deserialize_with = "deserialize_numeric_or_none")]
+    pub gas_price: Option<U256>,
+    #[serde(rename = "txreceipt_status", borrow)]
+    pub tx_receipt_status: Cow<'a, str>,
+    #[serde(borrow)]
+    pub input: Cow<'a, str>,
+    #[serde(with = "json_string")]
+    pub contract_address: Option<Address>,
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
+    pub gas_used: U256,
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
+    pub cumulative_gas_used: U256,
+    #[serde(deserialize_with = "deserialize_u64_or_zero")]
+    pub confirmations: u64,
+    #[serde(with = "hex_string")]
+    pub method_id: Option<H32>,
+    /// Empty if the function is unknown
+    #[serde(borrow)]
+    pub function_name: Cow<'a, str>,
+}
+
+impl NormalTransaction {
+    /// Returns the sender, `None` for the rows of the genesis allocations.
+    pub fn from_address(&self) -> Option<Address> {
+        self.from.value().copied()
+    }
+
+    /// Returns the transaction hash, `None` for the rows of the genesis allocations.
+    pub fn hash(&self) -> Option<H256> {
+        self.hash.value().copied()
+    }
+
+    /// Returns whether this row is a genesis allocation rather than a transaction.
+    pub fn is_genesis(&self) -> bool {
+        self.hash.is_genesis() || self.from.is_genesis()
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl NormalTransaction {
+    /// Returns the transferred value in ether.
+    ///
+    /// Returns `None` if the value can't be represented exactly as a [Decimal].
+    pub fn value_decimal(&self) -> Option<Decimal> {
+        to_decimal(self.value, NATIVE_DECIMALS)
+    }
+}
+
 /// The raw response from the internal transaction list API endpoint
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -222,7 +502,7 @@ pub struct InternalTransaction {
     pub from: Address,
     #[serde(with = "genesis_string")]
     pub to: GenesisOption<Address>,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub value: U256,
     #[serde(with = "genesis_string")]
     pub contract_address: GenesisOption<Address>,
@@ -230,15 +510,283 @@ pub struct InternalTransaction {
     pub input: GenesisOption<Bytes>,
     #[serde(rename = "type")]
     pub result_type: String,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub gas: U256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub gas_used: U256,
     pub trace_id: String,
     pub is_error: String,
     pub err_code: String,
 }
 
+/// The kind of an [InternalTransaction], parsed from its `type`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CallType {
+    Call,
+    DelegateCall,
+    StaticCall,
+    CallCode,
+    Create,
+    Create2,
+    /// `suicide`, a contract destroying itself and sending its balance
+    SelfDestruct,
+    /// A type this version doesn't know, as returned by the explorer
+    Other(String),
+}
+
+impl From<&str> for CallType {
+    fn from(s: &str) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "call" => CallType::Call,
+            "delegatecall" => CallType::DelegateCall,
+            "staticcall" => CallType::StaticCall,
+            "callcode" => CallType::CallCode,
+            "create" => CallType::Create,
+            "create2" => CallType::Create2,
+            "suicide" | "selfdestruct" => CallType::SelfDestruct,
+            _ => CallType::Other(s.to_string()),
+        }
+    }
+}
+
+impl InternalTransaction {
+    /// Returns the kind of the call, parsed from [Self::result_type].
+    pub fn call_type(&self) -> CallType {
+        self.result_type.as_str().into()
+    }
+
+    /// Returns the recipient, `None` for contract creations and genesis rows.
+    pub fn to_address(&self) -> Option<Address> {
+        self.to.value().copied()
+    }
+
+    /// Returns the created contract, `None` unless this is a contract creation.
+    pub fn contract_address(&self) -> Option<Address> {
+        self.contract_address.value().copied()
+    }
+
+    /// Returns the call data, `None` if there is none or for genesis rows.
+    pub fn input(&self) -> Option<&Bytes> {
+        self.input.value()
+    }
+
+    /// Returns whether this row belongs to the genesis allocations.
+    pub fn is_genesis(&self) -> bool {
+        self.to.is_genesis() || self.contract_address.is_genesis() || self.input.is_genesis()
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl InternalTransaction {
+    /// Returns the transferred value in ether.
+    ///
+    /// Returns `None` if the value can't be represented exactly as a [Decimal].
+    pub fn value_decimal(&self) -> Option<Decimal> {
+        to_decimal(self.value, NATIVE_DECIMALS)
+    }
+}
+
+/// A transaction with its internal transactions, see [Client::get_transactions_with_internals]
+#[derive(Clone, Debug)]
+pub struct EnrichedTransaction {
+    pub tx: NormalTransaction,
+    /// Ordered by trace id
+    pub internals: Vec<InternalTransaction>,
+}
+
+/// A call in the tree of internal transactions of a single transaction.
+///
+/// See [build_trace_tree].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TraceNode {
+    /// The trace id of the call, e.g. `0_1`. Empty for the root node.
+    pub trace_id: String,
+    pub from: Address,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub gas: U256,
+    /// The calls made by this call, in trace id order
+    pub children: Vec<TraceNode>,
+}
+
+impl From<&InternalTransaction> for TraceNode {
+    fn from(tx: &InternalTransaction) -> Self {
+        Self {
+            trace_id: tx.trace_id.clone(),
+            from: tx.from,
+            to: tx.to.value().copied(),
+            value: tx.value,
+            gas: tx.gas,
+            children: vec![],
+        }
+    }
+}
+
+/// Reconstructs the call tree from the internal transactions of a single transaction.
+///
+/// The calls are nested according to their `trace_id` paths (e.g. `0_1` is a child of `0`), both
+/// `_` and `.` separators are accepted. The returned root node has no call data of its own, its
+/// children are the top level calls. Calls whose parent is missing are attached to the root,
+/// among the top level calls in trace id order. Calls with an unparsable or duplicate trace id
+/// are attached to the root after all of them.
+pub fn build_trace_tree(txs: &[InternalTransaction]) -> TraceNode {
+    let mut root = TraceNode::default();
+    let mut nodes = BTreeMap::new();
+    for tx in txs {
+        match trace_path(&tx.trace_id) {
+            Some(path) if !nodes.contains_key(&path) => {
+                nodes.insert(path, TraceNode::from(tx));
+            }
+            _ => root.children.push(TraceNode::from(tx)),
+        }
+    }
+
+    // children sort after their parents, so walking backwards moves every subtree into place
+    // before its parent is moved
+    let paths: Vec<_> = nodes.keys().rev().cloned().collect();
+    for path in paths {
+        let node = nodes.remove(&path).expect("path was collected from the map");
+        let parent = nodes.get_mut(&path[..path.len() - 1]).unwrap_or(&mut root);
+        parent.children.insert(0, node);
+    }
+
+    root
+}
+
+/// Parses a trace id like `0_1` into its numeric path segments.
+fn trace_path(trace_id: &str) -> Option<Vec<u64>> {
+    trace_id.split(['_', '.']).map(|segment| segment.parse().ok()).collect()
+}
+
+/// Which list endpoint a [LedgerEntry] comes from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LedgerSource {
+    /// A transaction from `txlist`
+    Normal,
+    /// A message call from `txlistinternal`
+    Internal,
+}
+
+/// An ether movement of an address, either a transaction or an internal transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LedgerEntry {
+    pub source: LedgerSource,
+    pub block_number: BlockNumber,
+    pub time_stamp: String,
+    /// The hash of the transaction, `None` for genesis allocations
+    pub hash: Option<H256>,
+    /// The index of the transaction in its block, for internal transactions the index of the
+    /// parent transaction if it's part of the ledger
+    pub transaction_index: Option<u64>,
+    /// The trace id of an internal transaction
+    pub trace_id: Option<String>,
+    /// The sender, `None` for genesis allocations
+    pub from: Option<Address>,
+    /// The recipient, `None` for contract creations
+    pub to: Option<Address>,
+    pub value: U256,
+    /// Whether the transaction or call failed, in which case `value` was not transferred
+    pub is_error: bool,
+}
+
+impl From<NormalTransaction> for LedgerEntry {
+    fn from(tx: NormalTransaction) -> Self {
+        Self {
+            source: LedgerSource::Normal,
+            block_number: tx.block_number,
+            time_stamp: tx.time_stamp,
+            hash: tx.hash.into(),
+            transaction_index: tx.transaction_index,
+            trace_id: None,
+            from: tx.from.into(),
+            to: tx.to,
+            value: tx.value,
+            is_error: tx.is_error == "1",
+        }
+    }
+}
+
+impl From<InternalTransaction> for LedgerEntry {
+    fn from(tx: InternalTransaction) -> Self {
+        Self {
+            source: LedgerSource::Internal,
+            block_number: tx.block_number,
+            time_stamp: tx.time_stamp,
+            hash: Some(tx.hash),
+            transaction_index: None,
+            trace_id: Some(tx.trace_id),
+            from: Some(tx.from),
+            to: tx.to.into(),
+            value: tx.value,
+            is_error: tx.is_error == "1",
+        }
+    }
+}
+
+/// Merges transactions and internal transactions into one ledger.
+///
+/// Entries are ordered by block number, then by transaction index, with internal transactions
+/// following their parent transaction in trace id order. Internal transactions whose parent is
+/// not part of `txs` come after the transactions of their block.
+fn merge_ledger(
+    txs: Vec<NormalTransaction>,
+    internal: Vec<InternalTransaction>,
+) -> Vec<LedgerEntry> {
+    let indices: HashMap<H256, u64> =
+        txs.iter().filter_map(|tx| Some((*tx.hash.value()?, tx.transaction_index?))).collect();
+
+    let mut entries: Vec<LedgerEntry> = txs.into_iter().map(Into::into).collect();
+    entries.extend(internal.into_iter().map(|tx| {
+        let mut entry = LedgerEntry::from(tx);
+        entry.transaction_index = entry.hash.and_then(|hash| indices.get(&hash).copied());
+        entry
+    }));
+    entries.sort_by_cached_key(|entry| {
+        (
+            entry.block_number.as_number(),
+            entry.transaction_index.unwrap_or(u64::MAX),
+            entry.trace_id.as_deref().map(trace_path),
+        )
+    });
+    entries
+}
+
+/// Returns the contracts created by `deployer`, paired with their creation transaction hash,
+/// ordered by block.
+///
+/// Direct deployments are transactions without a recipient, factory deployments are successful
+/// internal `create` or `create2` calls of `deployer`.
+fn deployed_contracts(
+    deployer: &Address,
+    txs: Vec<NormalTransaction>,
+    internal: Vec<InternalTransaction>,
+) -> Vec<(Address, H256)> {
+    let direct = txs.into_iter().filter_map(|tx| {
+        if tx.to.is_some() || tx.is_error == "1" || tx.from.value() != Some(deployer) {
+            return None
+        }
+        Some((tx.block_number.as_number(), tx.contract_address?, *tx.hash.value()?))
+    });
+    let factory = internal.into_iter().filter_map(|tx| {
+        if !matches!(tx.call_type(), CallType::Create | CallType::Create2) ||
+            tx.is_error == "1" ||
+            tx.from != *deployer
+        {
+            return None
+        }
+        Some((tx.block_number.as_number(), *tx.contract_address.value()?, tx.hash))
+    });
+
+    let mut deployments: Vec<_> = direct.chain(factory).collect();
+    deployments.sort_by_key(|(block, _, _)| *block);
+    let mut seen = HashSet::new();
+    deployments
+        .into_iter()
+        .filter(|(_, contract, _)| seen.insert(*contract))
+        .map(|(_, contract, hash)| (contract, hash))
+        .collect()
+}
+
 /// The raw response from the ERC20 transfer list API endpoint
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -247,33 +795,85 @@ pub struct ERC20TokenTransferEvent {
     pub block_number: BlockNumber,
     pub time_stamp: String,
     pub hash: H256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub nonce: U256,
     pub block_hash: H256,
     pub from: Address,
     pub contract_address: Address,
     pub to: Option<Address>,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub value: U256,
     pub token_name: String,
     pub token_symbol: String,
     pub token_decimal: String,
-    #[serde(deserialize_with = "deserialize_stringified_u64")]
+    #[serde(deserialize_with = "deserialize_u64_or_zero")]
     pub transaction_index: u64,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub gas: U256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric_opt")]
+    #[serde(deserialize_with = "deserialize_numeric_or_none")]
     pub gas_price: Option<U256>,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub gas_used: U256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub cumulative_gas_used: U256,
     /// deprecated
     pub input: String,
-    #[serde(deserialize_with = "deserialize_stringified_u64")]
+    #[serde(deserialize_with = "deserialize_u64_or_zero")]
+    pub confirmations: u64,
+}
+
+/// An [ERC20TokenTransferEvent] borrowing its text fields from the response body, see
+/// [Client::get_erc20_token_transfer_events_with]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ERC20TokenTransferEventRef<'a> {
+    #[serde(deserialize_with = "deserialize_stringified_block_number")]
+    pub block_number: BlockNumber,
+    #[serde(borrow)]
+    pub time_stamp: Cow<'a, str>,
+    pub hash: H256,
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
+    pub nonce: U256,
+    pub block_hash: H256,
+    pub from: Address,
+    pub contract_address: Address,
+    pub to: Option<Address>,
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
+    pub value: U256,
+    #[serde(borrow)]
+    pub token_name: Cow<'a, str>,
+    #[serde(borrow)]
+    pub token_symbol: Cow<'a, str>,
+    #[serde(borrow)]
+    pub token_decimal: Cow<'a, str>,
+    #[serde(deserialize_with = "deserialize_u64_or_zero")]
+    pub transaction_index: u64,
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
+    pub gas: U256,
+    #[serde(deserialize_with = "deserialize_numeric_or_none")]
+    pub gas_price: Option<U256>,
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
+    pub gas_used: U256,
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
+    pub cumulative_gas_used: U256,
+    /// deprecated
+    #[serde(borrow)]
+    pub input: Cow<'a, str>,
+    #[serde(deserialize_with = "deserialize_u64_or_zero")]
     pub confirmations: u64,
 }
 
+#[cfg(feature = "decimal")]
+impl ERC20TokenTransferEvent {
+    /// Returns the transferred amount in whole tokens, scaled by the token's decimals.
+    ///
+    /// Returns `None` if the token decimals are invalid or the amount can't be represented exactly
+    /// as a [Decimal].
+    pub fn value_decimal(&self) -> Option<Decimal> {
+        to_decimal(self.value, self.token_decimal.parse().ok()?)
+    }
+}
+
 /// The raw response from the ERC721 transfer list API endpoint
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -282,7 +882,7 @@ pub struct ERC721TokenTransferEvent {
     pub block_number: BlockNumber,
     pub time_stamp: String,
     pub hash: H256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub nonce: U256,
     pub block_hash: H256,
     pub from: Address,
@@ -293,19 +893,19 @@ pub struct ERC721TokenTransferEvent {
     pub token_name: String,
     pub token_symbol: String,
     pub token_decimal: String,
-    #[serde(deserialize_with = "deserialize_stringified_u64")]
+    #[serde(deserialize_with = "deserialize_u64_or_zero")]
     pub transaction_index: u64,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub gas: U256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric_opt")]
+    #[serde(deserialize_with = "deserialize_numeric_or_none")]
     pub gas_price: Option<U256>,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub gas_used: U256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub cumulative_gas_used: U256,
     /// deprecated
     pub input: String,
-    #[serde(deserialize_with = "deserialize_stringified_u64")]
+    #[serde(deserialize_with = "deserialize_u64_or_zero")]
     pub confirmations: u64,
 }
 
@@ -317,7 +917,7 @@ pub struct ERC1155TokenTransferEvent {
     pub block_number: BlockNumber,
     pub time_stamp: String,
     pub hash: H256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub nonce: U256,
     pub block_hash: H256,
     pub from: Address,
@@ -328,19 +928,19 @@ pub struct ERC1155TokenTransferEvent {
     pub token_value: String,
     pub token_name: String,
     pub token_symbol: String,
-    #[serde(deserialize_with = "deserialize_stringified_u64")]
+    #[serde(deserialize_with = "deserialize_u64_or_zero")]
     pub transaction_index: u64,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub gas: U256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric_opt")]
+    #[serde(deserialize_with = "deserialize_numeric_or_none")]
     pub gas_price: Option<U256>,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub gas_used: U256,
-    #[serde(deserialize_with = "deserialize_stringified_numeric")]
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
     pub cumulative_gas_used: U256,
     /// deprecated
     pub input: String,
-    #[serde(deserialize_with = "deserialize_stringified_u64")]
+    #[serde(deserialize_with = "deserialize_u64_or_zero")]
     pub confirmations: u64,
 }
 
@@ -355,7 +955,7 @@ pub struct MinedBlock {
 }
 
 /// The pre-defined block parameter for balance API endpoints
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum Tag {
     Earliest,
     Pending,
@@ -390,39 +990,261 @@ impl Display for Sort {
 }
 
 /// Common optional arguments for the transaction or event list API endpoints
+///
+/// Results are paginated: `page` is the page number and `offset` is the number of results per
+/// page, which can also be set with [TxListParams::page_size]. Etherscan only serves the first
+/// 10000 results, queries with `page * offset` above that fail with
+/// [EtherscanError::ResultWindowTooLarge] before they are sent.
+///
+/// The default params query the full history, up to [BlockNumber::Latest]. Ranges passed to
+/// [TxListParams::new] as numbers end where they say, so an end block like `99999999` caps the
+/// results on chains that are past it.
 #[derive(Clone, Copy, Debug)]
 pub struct TxListParams {
-    start_block: u64,
-    end_block: u64,
+    start_block: BlockNumber,
+    end_block: BlockNumber,
     page: u64,
     offset: u64,
+    page_size: Option<u64>,
     sort: Sort,
+    sorted: bool,
+    min_value: Option<U256>,
+    min_confirmations: Option<u64>,
 }
 
 impl TxListParams {
     pub fn new(start_block: u64, end_block: u64, page: u64, offset: u64, sort: Sort) -> Self {
-        Self { start_block, end_block, page, offset, sort }
+        Self {
+            start_block: start_block.into(),
+            end_block: end_block.into(),
+            page,
+            offset,
+            page_size: None,
+            sort,
+            sorted: false,
+            min_value: None,
+            min_confirmations: None,
+        }
+    }
+
+    /// Sets the first block of the queried range.
+    ///
+    /// The list endpoints only understand block numbers, [BlockNumber::Earliest] and
+    /// [BlockNumber::Latest].
+    #[must_use]
+    pub fn start_block(mut self, block: impl Into<BlockNumber>) -> Self {
+        self.start_block = block.into();
+        self
+    }
+
+    /// Sets the last block of the queried range, e.g. [BlockNumber::Latest] to query up to the
+    /// current head.
+    ///
+    /// The list endpoints only understand block numbers, [BlockNumber::Earliest] and
+    /// [BlockNumber::Latest].
+    #[must_use]
+    pub fn end_block(mut self, block: impl Into<BlockNumber>) -> Self {
+        self.end_block = block.into();
+        self
+    }
+
+    /// Sets the number of results per page, sent as the `offset` param.
+    ///
+    /// Takes precedence over the `offset` passed to [TxListParams::new].
+    #[must_use]
+    pub fn page_size(mut self, page_size: u64) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Sorts the transactions returned by [Client::get_transactions] by block number and
+    /// transaction index, in the direction of the `sort` param.
+    ///
+    /// Etherscan orders by block, but the order within a block is not guaranteed. Sorting needs
+    /// the full result, so it's ignored by [Client::get_transactions_stream].
+    #[must_use]
+    pub fn sorted(mut self, sorted: bool) -> Self {
+        self.sorted = sorted;
+        self
+    }
+
+    /// Drops transactions of [Client::get_transactions] and [Client::get_transactions_stream]
+    /// transferring less than `min_value` wei, e.g. to skip zero-value spam.
+    ///
+    /// Etherscan can't filter by value, so this is applied after each page is fetched: pages may
+    /// hold fewer than `offset` transactions and the request quota is the same as without it.
+    #[must_use]
+    pub fn min_value(mut self, min_value: U256) -> Self {
+        self.min_value = Some(min_value);
+        self
+    }
+
+    /// Drops transactions of [Client::get_transactions] and [Client::get_transactions_stream]
+    /// with fewer than `min_confirmations` confirmations, which is off by default.
+    ///
+    /// Transactions in recent blocks can still be reorged out of the chain, so this trades
+    /// latency for safety: with a high minimum, recent transactions only show up in later
+    /// calls. Chains with fast finality need few confirmations, if any. Like
+    /// [TxListParams::min_value], this is applied to each page after it's fetched, using the
+    /// confirmations the explorer reported when serving it.
+    #[must_use]
+    pub fn min_confirmations(mut self, min_confirmations: u64) -> Self {
+        self.min_confirmations = Some(min_confirmations);
+        self
+    }
+
+    /// Returns whether a transaction passes the `min_value` and `min_confirmations` filters.
+    fn keeps(&self, value: U256, confirmations: u64) -> bool {
+        self.min_value.map_or(true, |min_value| value >= min_value) &&
+            self.min_confirmations.map_or(true, |min| confirmations >= min)
+    }
+
+    /// Returns whether the block range is narrowed from the full history at either end.
+    fn has_block_range(&self) -> bool {
+        let start = matches!(self.start_block, BlockNumber::Number(block) if block > 0.into());
+        let end = matches!(self.end_block, BlockNumber::Number(block) if block < UNBOUNDED_END_BLOCK.into());
+        start || end
+    }
+
+    /// Returns the number of results per page.
+    fn effective_page_size(&self) -> u64 {
+        self.page_size.unwrap_or(self.offset)
     }
 }
 
+/// The end block that Etherscan's examples use for the full history. Ranges ending at or above it
+/// are treated as unbounded.
+const UNBOUNDED_END_BLOCK: u64 = 99999999;
+
+/// The maximum number of addresses of a `balancemulti` request
+const MAX_BALANCE_ADDRESSES: usize = 20;
+
 impl Default for TxListParams {
     fn default() -> Self {
-        Self { start_block: 0, end_block: 99999999, page: 0, offset: 10000, sort: Sort::Asc }
+        Self {
+            start_block: 0.into(),
+            end_block: BlockNumber::Latest,
+            page: 0,
+            offset: 10000,
+            page_size: None,
+            sort: Sort::Asc,
+            sorted: false,
+            min_value: None,
+            min_confirmations: None,
+        }
     }
 }
 
 impl From<TxListParams> for HashMap<&'static str, String> {
     fn from(tx_params: TxListParams) -> Self {
         let mut params = HashMap::new();
-        params.insert("startBlock", tx_params.start_block.to_string());
-        params.insert("endBlock", tx_params.end_block.to_string());
+        params.insert("startBlock", format_block(tx_params.start_block));
+        params.insert("endBlock", format_block(tx_params.end_block));
         params.insert("page", tx_params.page.to_string());
-        params.insert("offset", tx_params.offset.to_string());
+        params.insert("offset", tx_params.effective_page_size().to_string());
         params.insert("sort", tx_params.sort.to_string());
         params
     }
 }
 
+impl Client {
+    /// Formats an address for use in a query, as a `0x` prefixed hex string that is lowercase
+    /// unless [ClientBuilder::with_checksummed_addresses](crate::ClientBuilder::with_checksummed_addresses)
+    /// is set.
+    pub(crate) fn format_address(&self, address: &Address) -> String {
+        if self.checksummed_addresses {
+            to_checksum(address, None)
+        } else {
+            format!("{address:#x}")
+        }
+    }
+}
+
+/// Maps the untyped failures of the balance endpoints to [EtherscanError::BalanceFailed].
+fn balance_failed(err: EtherscanError) -> EtherscanError {
+    match err {
+        EtherscanError::Message(_) => EtherscanError::BalanceFailed,
+        err => err,
+    }
+}
+
+/// Formats a block for use in a query: numbers as decimals and tags by name.
+fn format_block(block: BlockNumber) -> String {
+    match block {
+        BlockNumber::Number(number) => number.to_string(),
+        BlockNumber::Earliest => "0".to_string(),
+        tag => tag.to_string(),
+    }
+}
+
+/// Sorts transactions by block number and transaction index.
+fn sort_transactions(txs: &mut [NormalTransaction], sort: Sort) {
+    txs.sort_by_key(|tx| (tx.block_number.as_number(), tx.transaction_index));
+    if let Sort::Desc = sort {
+        txs.reverse();
+    }
+}
+
+/// The state of paging through the transactions of an address.
+///
+/// Instead of increasing the page number, which Etherscan limits to 10000 results in total, the
+/// block range is narrowed to start (or end, for [Sort::Desc]) at the last block of the previous
+/// page. That block is queried again, so its already yielded transactions are skipped.
+struct TxPageCursor {
+    address: Address,
+    params: TxListParams,
+    /// Hashes of the already yielded transactions of the cursor block
+    seen: HashSet<H256>,
+    done: bool,
+}
+
+impl TxPageCursor {
+    fn new(address: Address, params: TxListParams) -> Self {
+        Self {
+            address,
+            params: TxListParams { page: 1, ..params },
+            seen: HashSet::new(),
+            done: false,
+        }
+    }
+
+    /// Moves the cursor past the given page and returns its not yet yielded transactions.
+    fn advance(&mut self, txs: Vec<NormalTransaction>) -> Vec<NormalTransaction> {
+        let full = !txs.is_empty() && txs.len() as u64 >= self.params.effective_page_size();
+        let last_block = txs.last().and_then(|tx| tx.block_number.as_number());
+        let txs: Vec<_> = txs
+            .into_iter()
+            .filter(|tx| tx.hash.value().map_or(true, |hash| !self.seen.contains(hash)))
+            .collect();
+
+        let last_block = match last_block {
+            Some(last_block) if full => last_block,
+            _ => {
+                self.done = true;
+                return txs
+            }
+        };
+        let cursor = match self.params.sort {
+            Sort::Asc => &mut self.params.start_block,
+            Sort::Desc => &mut self.params.end_block,
+        };
+        if cursor.as_number() == Some(last_block) {
+            // the whole page is in the cursor block
+            self.params.page += 1;
+        } else {
+            *cursor = last_block.into();
+            self.params.page = 1;
+            self.seen.clear();
+        }
+        self.seen.extend(
+            txs.iter()
+                .filter(|tx| tx.block_number.as_number() == Some(last_block))
+                .filter_map(|tx| tx.hash.value().copied()),
+        );
+        txs
+    }
+}
+
 /// Options for querying internal transactions
 #[derive(Clone, Debug)]
 pub enum InternalTxQueryOption {
@@ -437,38 +1259,119 @@ pub enum TokenQueryOption {
     ByAddress(Address),
     ByContract(Address),
     ByAddressAndContract(Address, Address),
+    /// Transfers of an address between two blocks, inclusive. The range overrides the one in
+    /// [TxListParams].
+    ByAddressAndRange(Address, u64, u64),
+    /// Transfers of a token contract between two blocks, inclusive. The range overrides the one
+    /// in [TxListParams].
+    ByContractAndRange(Address, u64, u64),
 }
 
 impl TokenQueryOption {
+    /// Checks that a query with these params can't accidentally scan a token's full history.
+    ///
+    /// [TokenQueryOption::ByContract] queries without an address match every transfer of the
+    /// token, so they need a block range narrower than the default. Queries by address are
+    /// always accepted. This is not checked by the token transfer methods, call it before
+    /// sending a query built from user input.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [EtherscanError::UnboundedTokenQuery] for a contract-wide query over the full
+    /// block range.
+    pub fn validate(&self, params: &TxListParams) -> Result<()> {
+        match *self {
+            TokenQueryOption::ByContract(contract) if !params.has_block_range() => {
+                Err(EtherscanError::UnboundedTokenQuery(contract))
+            }
+            TokenQueryOption::ByContractAndRange(contract, start, end)
+                if start == 0 && end >= UNBOUNDED_END_BLOCK =>
+            {
+                Err(EtherscanError::UnboundedTokenQuery(contract))
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn into_params(self, list_params: TxListParams) -> HashMap<&'static str, String> {
+        self.into_params_with(list_params, |address| format!("{address:#x}"))
+    }
+
+    /// Like [TokenQueryOption::into_params], formatting the addresses with `format_address`.
+    fn into_params_with(
+        self,
+        list_params: TxListParams,
+        format_address: impl Fn(&Address) -> String,
+    ) -> HashMap<&'static str, String> {
         let mut params: HashMap<&'static str, String> = list_params.into();
         match self {
             TokenQueryOption::ByAddress(address) => {
-                params.insert("address", format!("{address:?}"));
+                params.insert("address", format_address(&address));
                 params
             }
             TokenQueryOption::ByContract(contract) => {
-                params.insert("contractaddress", format!("{contract:?}"));
+                params.insert("contractaddress", format_address(&contract));
                 params
             }
             TokenQueryOption::ByAddressAndContract(address, contract) => {
-                params.insert("address", format!("{address:?}"));
-                params.insert("contractaddress", format!("{contract:?}"));
+                params.insert("address", format_address(&address));
+                params.insert("contractaddress", format_address(&contract));
+                params
+            }
+            TokenQueryOption::ByAddressAndRange(address, start, end) => {
+                params.insert("address", format_address(&address));
+                params.insert("startBlock", start.to_string());
+                params.insert("endBlock", end.to_string());
+                params
+            }
+            TokenQueryOption::ByContractAndRange(contract, start, end) => {
+                params.insert("contractaddress", format_address(&contract));
+                params.insert("startBlock", start.to_string());
+                params.insert("endBlock", end.to_string());
                 params
             }
         }
     }
 }
 
-/// The pre-defined block type for retrieving mined blocks
-#[derive(Copy, Clone, Debug, Default)]
-pub enum BlockType {
+/// Which transfers of an address to keep, by the side of the transfer the address is on
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// Transfers to the address
+    In,
+    /// Transfers from the address
+    Out,
     #[default]
-    CanonicalBlocks,
-    Uncles,
+    Both,
 }
 
-impl Display for BlockType {
+impl Direction {
+    /// Returns whether a transfer from `from` to `to` moves tokens of `address` in this
+    /// direction. Transfers of an address to itself are both inbound and outbound.
+    pub fn matches(self, address: &Address, from: &Address, to: Option<&Address>) -> bool {
+        let inbound = to == Some(address);
+        let outbound = from == address;
+        match self {
+            Direction::In => inbound,
+            Direction::Out => outbound,
+            Direction::Both => inbound || outbound,
+        }
+    }
+}
+
+/// The pre-defined block type for retrieving mined blocks
+///
+/// Since the Merge there are no uncle blocks, so [BlockType::Uncles] only returns blocks from
+/// before the switch to proof of stake.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum BlockType {
+    #[default]
+    CanonicalBlocks,
+    /// Uncle blocks, which only exist before the Merge
+    Uncles,
+}
+
+impl Display for BlockType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::result::Result<(), Error> {
         match self {
             BlockType::CanonicalBlocks => write!(f, "blocks"),
@@ -480,6 +1383,10 @@ impl Display for BlockType {
 impl Client {
     /// Returns the Ether balance of a given address.
     ///
+    /// Fails with [EtherscanError::BalanceFailed] if the explorer reports an error other than a
+    /// rate limit or an invalid API key. With the `providers` feature, the balance is fetched from
+    /// the node configured with `ClientBuilder::with_fallback_provider` if the explorer fails.
+    ///
     /// ```no_run
     /// # use ethers_etherscan::Client;
     /// # use ethers_core::types::Chain;
@@ -498,23 +1405,88 @@ impl Client {
         tag: Option<Tag>,
     ) -> Result<AccountBalance> {
         let tag_str = tag.unwrap_or_default().to_string();
-        let addr_str = format!("{address:?}");
+        let addr_str = self.format_address(address);
         let query = self.create_query(
             "account",
             "balance",
             HashMap::from([("address", &addr_str), ("tag", &tag_str)]),
         );
-        let response: Response<String> = self.get_json(&query).await?;
+        let balance = self.get_json(&query).await.and_then(|response: Response<String>| {
+            check_string_ok(&response.status, &response.message, response.result)
+                .map_err(balance_failed)
+        });
+        #[cfg(feature = "providers")]
+        let balance = match balance {
+            Err(err) => self.fallback_balance(address, tag.unwrap_or_default(), err).await,
+            balance => balance,
+        };
+        Ok(AccountBalance { account: *address, balance: balance? })
+    }
+
+    /// Returns the latest Ether balance of an address together with the number of the head
+    /// block, e.g. to record which block a balance was audited at.
+    ///
+    /// The block number is fetched with [Client::get_block_number] right before the balance.
+    /// Etherscan can't serve the balance at a given block, so if a block is mined in between
+    /// the balance reflects that later block: the returned block is a lower bound.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let (balance, block) = client
+    ///         .get_ether_balance_at_head(&"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap())
+    ///         .await
+    ///         .unwrap();
+    /// # }
+    /// ```
+    pub async fn get_ether_balance_at_head(
+        &self,
+        address: &Address,
+    ) -> Result<(AccountBalance, u64)> {
+        let block = self.get_block_number().await?;
+        let balance = self.get_ether_balance_single(address, Some(Tag::Latest)).await?;
+        Ok((balance, block))
+    }
 
-        match response.status.as_str() {
-            "0" => Err(EtherscanError::BalanceFailed),
-            "1" => Ok(AccountBalance { account: *address, balance: response.result }),
-            err => Err(EtherscanError::BadStatusCode(err.to_string())),
+    /// Returns the Ether balance of a given address for each of the [Tag]s.
+    ///
+    /// The balances are fetched one after another with [Self::get_ether_balance_single].
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, account::Tag};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let balances = client
+    ///         .get_ether_balance_all_tags(&"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap())
+    ///         .await.unwrap();
+    ///     let latest = balances[&Tag::Latest];
+    /// # }
+    /// ```
+    pub async fn get_ether_balance_all_tags(
+        &self,
+        address: &Address,
+    ) -> Result<HashMap<Tag, U256>> {
+        let mut balances = HashMap::new();
+        for tag in [Tag::Earliest, Tag::Latest, Tag::Pending] {
+            let balance = self.get_ether_balance_single(address, Some(tag)).await?;
+            let balance =
+                U256::from_dec_str(&balance.balance).map_err(|_| EtherscanError::BalanceFailed)?;
+            balances.insert(tag, balance);
         }
+        Ok(balances)
     }
 
     /// Returns the balance of the accounts from a list of addresses.
     ///
+    /// Fails with [EtherscanError::BalanceFailed] like [Self::get_ether_balance_single].
+    ///
     /// ```no_run
     /// # use ethers_etherscan::Client;
     /// # use ethers_core::types::Chain;
@@ -533,7 +1505,8 @@ impl Client {
         tag: Option<Tag>,
     ) -> Result<Vec<AccountBalance>> {
         let tag_str = tag.unwrap_or_default().to_string();
-        let addrs = addresses.iter().map(|x| format!("{x:?}")).collect::<Vec<String>>().join(",");
+        let addrs =
+            addresses.iter().map(|x| self.format_address(x)).collect::<Vec<String>>().join(",");
         let query: Query<HashMap<&str, &str>> = self.create_query(
             "account",
             "balancemulti",
@@ -541,11 +1514,138 @@ impl Client {
         );
         let response: Response<Vec<AccountBalance>> = self.get_json(&query).await?;
 
-        match response.status.as_str() {
-            "0" => Err(EtherscanError::BalanceFailed),
-            "1" => Ok(response.result),
-            err => Err(EtherscanError::BadStatusCode(err.to_string())),
+        check_ok(&response.status, &response.message, response.result).map_err(balance_failed)
+    }
+
+    /// Returns a stream of the balances of any number of addresses, requesting them in batches
+    /// of 20 with [Self::get_ether_balance_multi] as the stream is polled.
+    ///
+    /// The balances are yielded in the order of `addresses`, each holding its address.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::{Address, Chain};
+    /// use futures_util::TryStreamExt;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let addresses = (1..=100u8).map(Address::repeat_byte);
+    ///     let mut balances = Box::pin(client.get_ether_balance_stream(addresses, None));
+    ///     while let Some(balance) = balances.try_next().await.unwrap() {
+    ///         println!("{:?}: {}", balance.account, balance.balance);
+    ///     }
+    /// # }
+    /// ```
+    pub fn get_ether_balance_stream<'a, I>(
+        &'a self,
+        addresses: I,
+        tag: Option<Tag>,
+    ) -> impl Stream<Item = Result<AccountBalance>> + 'a
+    where
+        I: IntoIterator<Item = Address>,
+        I::IntoIter: 'a,
+    {
+        stream::try_unfold(addresses.into_iter(), move |mut addresses| async move {
+            let batch: Vec<Address> = addresses.by_ref().take(MAX_BALANCE_ADDRESSES).collect();
+            if batch.is_empty() {
+                return Ok::<_, EtherscanError>(None)
+            }
+            let mut balances =
+                self.get_ether_balance_multi(&batch.iter().collect::<Vec<_>>(), tag).await?;
+            // the explorer answers in the order of the request, but don't rely on it
+            balances.sort_by_key(|balance| {
+                batch.iter().position(|address| *address == balance.account)
+            });
+            Ok(Some((stream::iter(balances.into_iter().map(Ok)), addresses)))
+        })
+        .try_flatten()
+    }
+
+    /// Returns the balance of an address as of a block, using the API Pro `balancehistory`
+    /// endpoint.
+    #[cfg(feature = "pro")]
+    pub async fn get_ether_balance_at_block(
+        &self,
+        address: &Address,
+        block: u64,
+    ) -> Result<AccountBalance> {
+        let query = self.create_query(
+            "account",
+            "balancehistory",
+            HashMap::from([
+                ("address", self.format_address(address)),
+                ("blockno", block.to_string()),
+            ]),
+        );
+        let response: Response<String> = self.get_json(&query).await?;
+
+        let balance = check_string_ok(&response.status, &response.message, response.result)?;
+        Ok(AccountBalance { account: *address, balance })
+    }
+
+    /// Returns the balances of a list of addresses as of a block, e.g. for a snapshot.
+    ///
+    /// `balancehistory` takes a single address, so this sends one request per address, in order.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let balances = client
+    ///         .get_ether_balances_at_block(
+    ///             &[&"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap()],
+    ///             8000000,
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "pro")]
+    pub async fn get_ether_balances_at_block(
+        &self,
+        addresses: &[&Address],
+        block: u64,
+    ) -> Result<Vec<AccountBalance>> {
+        let mut balances = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            balances.push(self.get_ether_balance_at_block(address, block).await?);
         }
+        Ok(balances)
+    }
+
+    /// Returns the number of transactions sent from an address, i.e. its nonce, using the
+    /// `eth_getTransactionCount` proxy.
+    ///
+    /// At [Tag::Pending] this is the nonce of the next transaction. Unlike counting the results
+    /// of [Self::get_transactions], this includes failed transactions and excludes received ones.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, account::Tag};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let nonce = client
+    ///         .get_transaction_count(&"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap(),
+    ///         Some(Tag::Pending)).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_transaction_count(&self, address: &Address, tag: Option<Tag>) -> Result<U256> {
+        let query = self.create_query(
+            "proxy",
+            "eth_getTransactionCount",
+            HashMap::from([
+                ("address", self.format_address(address)),
+                ("tag", tag.unwrap_or_default().to_string()),
+            ]),
+        );
+        let response: ProxyResponse<U256> = self.get_json_rpc(&query).await?;
+        Ok(response.result)
     }
 
     /// Returns the list of transactions performed by an address, with optional pagination.
@@ -567,140 +1667,218 @@ impl Client {
         address: &Address,
         params: Option<TxListParams>,
     ) -> Result<Vec<NormalTransaction>> {
-        let mut tx_params: HashMap<&str, String> = params.unwrap_or_default().into();
-        tx_params.insert("address", format!("{address:?}"));
+        let params = params.unwrap_or_default();
+        let mut tx_params: HashMap<&str, String> = params.into();
+        tx_params.insert("address", self.format_address(address));
         let query = self.create_query("account", "txlist", tx_params);
-        let response: Response<Vec<NormalTransaction>> = self.get_json(&query).await?;
+        let response: Response<Vec<NormalTransaction>> = self.get_list_json(&query).await?;
 
-        Ok(response.result)
+        let mut txs = check_ok(&response.status, &response.message, response.result)?;
+        txs.retain(|tx| params.keeps(tx.value, tx.confirmations));
+        if params.sorted {
+            sort_transactions(&mut txs, params.sort);
+        }
+        Ok(txs)
     }
 
-    /// Returns the list of internal transactions performed by an address or within a transaction,
-    /// with optional pagination.
+    /// Like [Self::get_transactions], passing transactions that borrow their text fields from
+    /// the response body to `f` instead of allocating them.
+    ///
+    /// This is meant for processing large pages of transactions without keeping them. The
+    /// [TxListParams::min_value] and [TxListParams::min_confirmations] filters are applied,
+    /// [TxListParams::sorted] is not.
     ///
     /// ```no_run
-    /// # use ethers_etherscan::{Client, account::InternalTxQueryOption};
+    /// # use ethers_etherscan::Client;
     /// # use ethers_core::types::Chain;
     ///
     /// # #[tokio::main]
     /// # async fn main() {
     ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
-    ///     let txs = client
-    ///         .get_internal_transactions(
-    ///             InternalTxQueryOption::ByAddress(
-    ///                 "0x2c1ba59d6f58433fb1eaee7d20b26ed83bda51a3".parse().unwrap()), None).await.unwrap();
+    ///     let failed = client
+    ///         .get_transactions_with(
+    ///             &"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap(),
+    ///             None,
+    ///             |txs| txs.iter().filter(|tx| tx.is_error == "1").count(),
+    ///         )
+    ///         .await
+    ///         .unwrap();
     /// # }
     /// ```
-    pub async fn get_internal_transactions(
+    pub async fn get_transactions_with<R>(
         &self,
-        tx_query_option: InternalTxQueryOption,
+        address: &Address,
         params: Option<TxListParams>,
-    ) -> Result<Vec<InternalTransaction>> {
-        let mut tx_params: HashMap<&str, String> = params.unwrap_or_default().into();
-        match tx_query_option {
-            InternalTxQueryOption::ByAddress(address) => {
-                tx_params.insert("address", format!("{address:?}"));
-            }
-            InternalTxQueryOption::ByTransactionHash(tx_hash) => {
-                tx_params.insert("txhash", format!("{tx_hash:?}"));
-            }
-            _ => {}
-        }
-        let query = self.create_query("account", "txlistinternal", tx_params);
-        let response: Response<Vec<InternalTransaction>> = self.get_json(&query).await?;
+        f: impl FnOnce(Vec<NormalTransactionRef<'_>>) -> R,
+    ) -> Result<R> {
+        let params = params.unwrap_or_default();
+        let mut tx_params: HashMap<&str, String> = params.into();
+        tx_params.insert("address", self.format_address(address));
+        let query = self.create_query("account", "txlist", tx_params);
+        let body = self.get_list_body(&query).await?;
+        let response: Response<Vec<NormalTransactionRef<'_>>> =
+            self.sanitize_list_response(&body)?;
 
-        Ok(response.result)
+        check_ok(&response.status, &response.message, ())?;
+        let mut txs = response.result;
+        txs.retain(|tx| params.keeps(tx.value, tx.confirmations));
+        Ok(f(txs))
     }
 
-    /// Returns the list of ERC-20 tokens transferred by an address, with optional filtering by
-    /// token contract.
+    /// Returns the first transaction of an address, e.g. to tell the age of an account, or `None`
+    /// if it has no transactions.
+    ///
+    /// Only the earliest row is requested, not the whole history. Internal transactions and token
+    /// transfers, which may come earlier, are not considered.
     ///
     /// ```no_run
-    /// # use ethers_etherscan::{Client, account::TokenQueryOption};
+    /// # use ethers_etherscan::Client;
     /// # use ethers_core::types::Chain;
     ///
     /// # #[tokio::main]
     /// # async fn main() {
     ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
-    ///     let txs = client
-    ///         .get_erc20_token_transfer_events(
-    ///             TokenQueryOption::ByAddress(
-    ///                 "0x4e83362442b8d1bec281594cea3050c8eb01311c".parse().unwrap()), None).await.unwrap();
+    ///     let first = client
+    ///         .get_first_transaction(&"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap())
+    ///         .await
+    ///         .unwrap();
     /// # }
     /// ```
-    pub async fn get_erc20_token_transfer_events(
+    pub async fn get_first_transaction(
         &self,
-        event_query_option: TokenQueryOption,
-        params: Option<TxListParams>,
-    ) -> Result<Vec<ERC20TokenTransferEvent>> {
-        let params = event_query_option.into_params(params.unwrap_or_default());
-        let query = self.create_query("account", "tokentx", params);
-        let response: Response<Vec<ERC20TokenTransferEvent>> = self.get_json(&query).await?;
+        address: &Address,
+    ) -> Result<Option<NormalTransaction>> {
+        self.get_single_transaction(address, Sort::Asc).await
+    }
 
-        Ok(response.result)
+    /// Returns the most recent transaction of an address, e.g. to tell when it was last active,
+    /// or `None` if it has no transactions.
+    ///
+    /// Only the latest row is requested, not the whole history. Internal transactions and token
+    /// transfers, which may come later, are not considered.
+    pub async fn get_last_transaction(
+        &self,
+        address: &Address,
+    ) -> Result<Option<NormalTransaction>> {
+        self.get_single_transaction(address, Sort::Desc).await
     }
 
-    /// Returns the list of ERC-721 ( NFT ) tokens transferred by an address, with optional
-    /// filtering by token contract.
+    /// Returns the balance, transaction count, first and last transaction of an address and
+    /// whether it's a contract.
+    ///
+    /// Etherscan has no consolidated endpoint for this, so the overview is assembled from five
+    /// requests, each counting towards the rate limit.
     ///
     /// ```no_run
-    /// # use ethers_etherscan::{Client, account::TokenQueryOption};
+    /// # use ethers_etherscan::Client;
     /// # use ethers_core::types::Chain;
     ///
     /// # #[tokio::main]
     /// # async fn main() {
     ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
-    ///     let txs = client
-    ///         .get_erc721_token_transfer_events(
-    ///             TokenQueryOption::ByAddressAndContract(
-    ///                 "0x6975be450864c02b4613023c2152ee0743572325".parse().unwrap(),
-    ///                 "0x06012c8cf97bead5deae237070f9587f8e7a266d".parse().unwrap(),
-    ///          ), None).await.unwrap();
+    ///     let info = client
+    ///         .get_address_info(&"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap())
+    ///         .await
+    ///         .unwrap();
+    ///     let age = info.first_transaction.map(|tx| tx.time_stamp);
     /// # }
     /// ```
-    pub async fn get_erc721_token_transfer_events(
-        &self,
-        event_query_option: TokenQueryOption,
-        params: Option<TxListParams>,
-    ) -> Result<Vec<ERC721TokenTransferEvent>> {
-        let params = event_query_option.into_params(params.unwrap_or_default());
-        let query = self.create_query("account", "tokennfttx", params);
-        let response: Response<Vec<ERC721TokenTransferEvent>> = self.get_json(&query).await?;
+    pub async fn get_address_info(&self, address: &Address) -> Result<AddressInfo> {
+        let balance = self.get_ether_balance_single(address, None).await?.balance;
+        let transaction_count = self.get_transaction_count(address, None).await?;
+        let first_transaction = self.get_first_transaction(address).await?;
+        let last_transaction = self.get_last_transaction(address).await?;
+        let is_contract = !self.get_contract_bytecode(address).await?.is_empty();
+        Ok(AddressInfo {
+            balance,
+            transaction_count,
+            first_transaction,
+            last_transaction,
+            is_contract,
+        })
+    }
 
-        Ok(response.result)
+    /// Returns the transaction of an address that comes first in the given order.
+    async fn get_single_transaction(
+        &self,
+        address: &Address,
+        sort: Sort,
+    ) -> Result<Option<NormalTransaction>> {
+        let params = TxListParams { page: 1, offset: 1, sort, ..Default::default() };
+        let txs = self.get_transactions(address, Some(params)).await?;
+        Ok(txs.into_iter().next())
     }
 
-    /// Returns the list of ERC-1155 ( NFT ) tokens transferred by an address, with optional
-    /// filtering by token contract.
+    /// Returns a stream of all transactions of an address, requesting the next page as the stream
+    /// is polled.
+    ///
+    /// The stream follows the block range and [Sort] of `params`, its `page` is ignored. Unlike
+    /// paging with [Self::get_transactions], this is not limited to the first 10000 results.
+    ///
+    /// No request is sent in the background: dropping the stream stops the pagination, cancelling
+    /// a request in flight and handing back its rate limit slot.
     ///
     /// ```no_run
-    /// # use ethers_etherscan::{Client, account::TokenQueryOption};
+    /// # use ethers_etherscan::Client;
     /// # use ethers_core::types::Chain;
+    /// use futures_util::TryStreamExt;
     ///
     /// # #[tokio::main]
     /// # async fn main() {
     ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
-    ///     let txs = client
-    ///         .get_erc1155_token_transfer_events(
-    ///             TokenQueryOption::ByAddressAndContract(
-    ///                 "0x216CD350a4044e7016f14936663e2880Dd2A39d7".parse().unwrap(),
-    ///                 "0x495f947276749ce646f68ac8c248420045cb7b5e".parse().unwrap(),
-    ///          ), None).await.unwrap();
+    ///     let txs: Vec<_> = client
+    ///         .get_transactions_stream(&"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap(), None)
+    ///         .try_collect()
+    ///         .await.unwrap();
     /// # }
     /// ```
-    pub async fn get_erc1155_token_transfer_events(
+    pub fn get_transactions_stream(
         &self,
-        event_query_option: TokenQueryOption,
+        address: &Address,
         params: Option<TxListParams>,
-    ) -> Result<Vec<ERC1155TokenTransferEvent>> {
-        let params = event_query_option.into_params(params.unwrap_or_default());
-        let query = self.create_query("account", "token1155tx", params);
-        let response: Response<Vec<ERC1155TokenTransferEvent>> = self.get_json(&query).await?;
+    ) -> impl Stream<Item = Result<NormalTransaction>> + '_ {
+        self.transaction_pages(*address, params.unwrap_or_default())
+            .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+            .try_flatten()
+    }
 
-        Ok(response.result)
+    /// Like [Self::get_transactions_stream], yielding the transactions one page at a time.
+    ///
+    /// Each item holds the transactions of one API page of up to the params' page size, e.g. to
+    /// insert them into a database in bulk. Transactions that were already yielded, or that are
+    /// filtered out by the params' `min_value` or `min_confirmations`, are dropped from their
+    /// page, and pages left empty are skipped.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, account::TxListParams};
+    /// # use ethers_core::types::Chain;
+    /// use futures_util::TryStreamExt;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let params = TxListParams::default().page_size(10000);
+    ///     let mut pages = Box::pin(client.get_transactions_pages(
+    ///         &"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap(),
+    ///         Some(params),
+    ///     ));
+    ///     while let Some(page) = pages.try_next().await.unwrap() {
+    ///         println!("{} transactions", page.len());
+    ///     }
+    /// # }
+    /// ```
+    pub fn get_transactions_pages(
+        &self,
+        address: &Address,
+        params: Option<TxListParams>,
+    ) -> impl Stream<Item = Result<Vec<NormalTransaction>>> + '_ {
+        self.transaction_pages(*address, params.unwrap_or_default())
     }
 
-    /// Returns the list of blocks mined by an address.
+    /// Writes all transactions of an address to `writer` as CSV, with a header row.
+    ///
+    /// The transactions are fetched page by page with [Self::get_transactions_stream] and written
+    /// as they arrive. Timestamps are formatted as RFC 3339 and values in ether.
     ///
     /// ```no_run
     /// # use ethers_etherscan::Client;
@@ -709,27 +1887,1977 @@ impl Client {
     /// # #[tokio::main]
     /// # async fn main() {
     ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
-    ///     let blocks = client
-    ///         .get_mined_blocks(&"0x9dd134d14d1e65f84b706d6f205cd5b1cd03a46b".parse().unwrap(), None, None)
+    ///     let file = std::fs::File::create("transactions.csv").unwrap();
+    ///     client
+    ///         .export_transactions_csv(&"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap(), file)
     ///         .await.unwrap();
     /// # }
     /// ```
-    pub async fn get_mined_blocks(
+    #[cfg(feature = "csv")]
+    pub async fn export_transactions_csv<W: std::io::Write>(
         &self,
         address: &Address,
-        block_type: Option<BlockType>,
-        page_and_offset: Option<(u64, u64)>,
-    ) -> Result<Vec<MinedBlock>> {
-        let mut params = HashMap::new();
-        params.insert("address", format!("{address:?}"));
-        params.insert("blocktype", block_type.unwrap_or_default().to_string());
-        if let Some((page, offset)) = page_and_offset {
-            params.insert("page", page.to_string());
-            params.insert("offset", offset.to_string());
+        writer: W,
+    ) -> Result<()> {
+        use futures_util::StreamExt;
+
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(CSV_HEADER)?;
+
+        let mut txs = Box::pin(self.get_transactions_stream(address, None));
+        while let Some(tx) = txs.next().await {
+            write_csv_row(&mut writer, &tx?)?;
         }
-        let query = self.create_query("account", "getminedblocks", params);
-        let response: Response<Vec<MinedBlock>> = self.get_json(&query).await?;
+        writer.flush()?;
+        Ok(())
+    }
 
-        Ok(response.result)
+    /// Returns a stream of the pages of an address's transactions, see [TxPageCursor].
+    fn transaction_pages(
+        &self,
+        address: Address,
+        params: TxListParams,
+    ) -> impl Stream<Item = Result<Vec<NormalTransaction>>> + '_ {
+        // the cursor needs the unfiltered pages to find where the next one starts
+        let unfiltered = TxListParams { min_value: None, min_confirmations: None, ..params };
+        let cursor = TxPageCursor::new(address, unfiltered);
+        stream::try_unfold(cursor, move |mut cursor| async move {
+            while !cursor.done {
+                let txs = self.get_transactions(&cursor.address, Some(cursor.params)).await?;
+                let mut page = cursor.advance(txs);
+                page.retain(|tx| params.keeps(tx.value, tx.confirmations));
+                if !page.is_empty() {
+                    return Ok(Some((page, cursor)))
+                }
+            }
+            Ok(None)
+        })
+    }
+
+    /// Returns all transactions of an address with aggregates over them, see [TxSummary].
+    ///
+    /// The transactions are fetched page by page like [Self::get_transactions_stream], following
+    /// the block range and filters of `params`, and added to the aggregates as they arrive.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let summary = client
+    ///         .get_transactions_summary(&"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap(), None)
+    ///         .await.unwrap();
+    ///     println!("{} transactions, {} wei in gas", summary.count(), summary.gas_spent);
+    /// # }
+    /// ```
+    pub async fn get_transactions_summary(
+        &self,
+        address: &Address,
+        params: Option<TxListParams>,
+    ) -> Result<TxSummary> {
+        self.transaction_pages(*address, params.unwrap_or_default())
+            .try_fold(TxSummary::default(), |mut summary, page| async move {
+                for tx in page {
+                    summary.push(address, tx);
+                }
+                Ok(summary)
+            })
+            .await
+    }
+
+    /// Returns the number of transactions of an address per UTC day between two blocks,
+    /// inclusive, e.g. for an activity heatmap.
+    ///
+    /// The transactions are fetched page by page like [Self::get_transactions_stream]. Only days
+    /// with transactions appear in the map, all other days have none.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let heatmap = client
+    ///         .transaction_heatmap(
+    ///             &"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap(),
+    ///             17000000,
+    ///             17500000,
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    ///     for (day, count) in heatmap {
+    ///         println!("{day}: {count}");
+    ///     }
+    /// # }
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub async fn transaction_heatmap(
+        &self,
+        address: &Address,
+        start: u64,
+        end: u64,
+    ) -> Result<BTreeMap<chrono::NaiveDate, u64>> {
+        let params = TxListParams::default().start_block(start).end_block(end);
+        self.transaction_pages(*address, params)
+            .try_fold(BTreeMap::new(), |mut days, page| async move {
+                for tx in page {
+                    if let Some(date) = crate::analytics::utc_date(&tx.time_stamp) {
+                        *days.entry(date).or_default() += 1;
+                    }
+                }
+                Ok(days)
+            })
+            .await
+    }
+
+    /// Returns the transactions between two addresses, in either direction.
+    ///
+    /// Etherscan can't filter by counterparty, so this fetches the transactions of `a` with the
+    /// given params and keeps those from or to `b`: it costs the same quota as
+    /// [Self::get_transactions] for `a` and only sees the page of `a`'s history selected by
+    /// `params`. Internal transactions are not included.
+    ///
+    /// The transactions are sorted by block number and transaction index, in the order of the
+    /// params' [Sort].
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let txs = client
+    ///         .get_transactions_between(
+    ///             &"0x4F26FfBe5F04ED43630fdC30A87638d53D0b0876".parse().unwrap(),
+    ///             &"0xdAC17F958D2ee523a2206206994597C13D831ec7".parse().unwrap(),
+    ///             None,
+    ///         )
+    ///         .await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_transactions_between(
+        &self,
+        a: &Address,
+        b: &Address,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<NormalTransaction>> {
+        let params = params.unwrap_or_default();
+        let mut txs = self.get_transactions(a, Some(params)).await?;
+        txs.retain(|tx| tx.to.as_ref() == Some(b) || tx.from.value() == Some(b));
+        sort_transactions(&mut txs, params.sort);
+        Ok(txs)
+    }
+
+    /// Returns the transactions calling a contract directly, i.e. those sent to it.
+    ///
+    /// This fetches the transactions of the contract with the given params and drops the ones
+    /// it sent, e.g. its creation or, for a smart contract wallet, its own calls. Calls reaching
+    /// the contract through other contracts are internal transactions and not included, see
+    /// [Self::get_internal_transactions].
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let calls = client
+    ///         .get_contract_transactions(
+    ///             &"0xdAC17F958D2ee523a2206206994597C13D831ec7".parse().unwrap(),
+    ///             None,
+    ///         )
+    ///         .await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_contract_transactions(
+        &self,
+        contract: &Address,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<NormalTransaction>> {
+        let mut txs = self.get_transactions(contract, params).await?;
+        txs.retain(|tx| tx.to.as_ref() == Some(contract));
+        Ok(txs)
+    }
+
+    /// Returns the total transaction fees paid by an address, in wei.
+    ///
+    /// Pages through all transactions of the address with [Self::get_transactions_stream],
+    /// following the block range of `params`, and sums `gas_used * gas_price` of those sent from
+    /// it. Failed transactions are included since they still pay for their gas. For EIP-1559
+    /// transactions Etherscan reports the effective gas price, so the sum is what was actually
+    /// paid. Transactions without a gas price are skipped. Fees paid for internal transactions
+    /// or by relayers aren't included.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let fees = client
+    ///         .total_gas_spent(&"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap(), None)
+    ///         .await.unwrap();
+    /// # }
+    /// ```
+    pub async fn total_gas_spent(
+        &self,
+        address: &Address,
+        params: Option<TxListParams>,
+    ) -> Result<U256> {
+        let params = TxListParams { min_value: None, ..params.unwrap_or_default() };
+        self.transaction_pages(*address, params)
+            .try_fold(U256::zero(), |total, page| async move {
+                let fees = page
+                    .iter()
+                    .filter(|tx| tx.from.value() == Some(address))
+                    .filter_map(|tx| Some(tx.gas_used.saturating_mul(tx.gas_price?)));
+                Ok(fees.fold(total, U256::saturating_add))
+            })
+            .await
+    }
+
+    /// Decodes the input of a transaction calling a contract with the given [Abi].
+    ///
+    /// The function is matched against the selector in the first 4 bytes of the input. Fails if
+    /// the input is empty, e.g. for plain ether transfers, or if the selector is not in the ABI.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let contract = "0xdAC17F958D2ee523a2206206994597C13D831ec7".parse().unwrap();
+    ///     let abi = client.contract_abi(contract).await.unwrap();
+    ///     let txs = client.get_transactions(&contract, None).await.unwrap();
+    ///     for tx in txs.iter().filter(|tx| tx.to == Some(contract)) {
+    ///         let (function, args) = client.decode_input(tx, &abi).unwrap();
+    ///     }
+    /// # }
+    /// ```
+    pub fn decode_input(
+        &self,
+        tx: &NormalTransaction,
+        abi: &Abi,
+    ) -> Result<(Function, Vec<Token>)> {
+        if tx.input.is_empty() {
+            return Err(EtherscanError::EmptyTransactionInput)
+        }
+        let (selector, data) = tx.input.split_at(tx.input.len().min(4));
+        let function = abi
+            .functions()
+            .find(|function| function.short_signature() == selector)
+            .ok_or_else(|| EtherscanError::UnknownFunctionSelector(selector.to_vec().into()))?;
+        let tokens = function.decode_input(data)?;
+        Ok((function.clone(), tokens))
+    }
+
+    /// Returns the list of internal transactions performed by an address or within a transaction,
+    /// with optional pagination.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, account::InternalTxQueryOption};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let txs = client
+    ///         .get_internal_transactions(
+    ///             InternalTxQueryOption::ByAddress(
+    ///                 "0x2c1ba59d6f58433fb1eaee7d20b26ed83bda51a3".parse().unwrap()), None).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_internal_transactions(
+        &self,
+        tx_query_option: InternalTxQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<InternalTransaction>> {
+        let mut tx_params: HashMap<&str, String> = params.unwrap_or_default().into();
+        match tx_query_option {
+            InternalTxQueryOption::ByAddress(address) => {
+                tx_params.insert("address", self.format_address(&address));
+            }
+            InternalTxQueryOption::ByTransactionHash(tx_hash) => {
+                tx_params.insert("txhash", format!("{tx_hash:?}"));
+            }
+            _ => {}
+        }
+        let query = self.create_query("account", "txlistinternal", tx_params);
+        let response: Response<Vec<InternalTransaction>> = self.get_list_json(&query).await?;
+
+        check_ok(&response.status, &response.message, response.result)
+    }
+
+    /// Returns the transactions and internal transactions of an address as one ledger.
+    ///
+    /// Both lists are fetched with the same params. The entries are ordered by block number and
+    /// transaction index, internal transactions follow their parent transaction in trace id
+    /// order. Failed transactions are included and flagged with [LedgerEntry::is_error].
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let ledger = client
+    ///         .get_full_ledger(&"0x2c1ba59d6f58433fb1eaee7d20b26ed83bda51a3".parse().unwrap(), None)
+    ///         .await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_full_ledger(
+        &self,
+        address: &Address,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<LedgerEntry>> {
+        let txs = self.get_transactions(address, params).await?;
+        let internal = self
+            .get_internal_transactions(InternalTxQueryOption::ByAddress(*address), params)
+            .await?;
+        Ok(merge_ledger(txs, internal))
+    }
+
+    /// Returns all contracts deployed by an address, paired with the hash of their creation
+    /// transaction, ordered by block.
+    ///
+    /// Besides transactions of `deployer` that create a contract, this scans its internal
+    /// transactions for `create` and `create2` calls, so contracts deployed by a factory are found
+    /// by querying the factory. Both lists are fetched with the same params. Failed deployments
+    /// are skipped.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let contracts = client
+    ///         .get_deployed_contracts(&"0x5c69bee701ef814a2b6a3edd4b1652cb9cc5aa6f".parse().unwrap(), None)
+    ///         .await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_deployed_contracts(
+        &self,
+        deployer: &Address,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<(Address, H256)>> {
+        let txs = self.get_transactions(deployer, params).await?;
+        let internal = self
+            .get_internal_transactions(InternalTxQueryOption::ByAddress(*deployer), params)
+            .await?;
+        Ok(deployed_contracts(deployer, txs, internal))
+    }
+
+    /// Returns the internal transactions of a single transaction, ordered by their trace id.
+    ///
+    /// Unlike [Self::get_internal_transactions] this doesn't paginate, since the internal
+    /// transactions of a single transaction fit in one response. See also [build_trace_tree].
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let txs = client
+    ///         .get_internal_transactions_for_tx(
+    ///             &"0x40eb908387324f2b575b4879cd9d7188f69c8fc9d87c901b9e2daaea4b442170".parse().unwrap())
+    ///         .await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_internal_transactions_for_tx(
+        &self,
+        hash: &H256,
+    ) -> Result<Vec<InternalTransaction>> {
+        let query = self.create_query(
+            "account",
+            "txlistinternal",
+            HashMap::from([("txhash", format!("{hash:?}"))]),
+        );
+        let response: Response<Vec<InternalTransaction>> = self.get_json(&query).await?;
+
+        let mut txs = check_ok(&response.status, &response.message, response.result)?;
+        // unparsable trace ids sort last
+        txs.sort_by_cached_key(|tx| {
+            let path = trace_path(&tx.trace_id);
+            (path.is_none(), path)
+        });
+        Ok(txs)
+    }
+
+    /// Returns the transactions of an address, each with its internal transactions.
+    ///
+    /// This is expensive: besides the transaction list, the internal transactions of every
+    /// transaction are requested one by one with [Self::get_internal_transactions_for_tx], so a
+    /// page of 100 transactions costs 101 requests. The requests are sent one after another,
+    /// within the client's rate limit. Use [Self::get_internal_transactions] for an address's own
+    /// internal transactions if the calls between other contracts are not needed.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, account::TxListParams};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let txs = client
+    ///         .get_transactions_with_internals(
+    ///             &"0x4F26FfBe5F04ED43630fdC30A87638d53D0b0876".parse().unwrap(),
+    ///             Some(TxListParams::default().page_size(10)),
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    /// # }
+    /// ```
+    pub async fn get_transactions_with_internals(
+        &self,
+        address: &Address,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<EnrichedTransaction>> {
+        let txs = self.get_transactions(address, params).await?;
+        let mut enriched = Vec::with_capacity(txs.len());
+        for tx in txs {
+            // genesis allocations have no hash, nor internal transactions
+            let internals = match tx.hash.value() {
+                Some(hash) => self.get_internal_transactions_for_tx(hash).await?,
+                None => Vec::new(),
+            };
+            enriched.push(EnrichedTransaction { tx, internals });
+        }
+        Ok(enriched)
+    }
+
+    /// Returns the list of ERC-20 tokens transferred by an address, with optional filtering by
+    /// token contract.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, account::TokenQueryOption};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let txs = client
+    ///         .get_erc20_token_transfer_events(
+    ///             TokenQueryOption::ByAddress(
+    ///                 "0x4e83362442b8d1bec281594cea3050c8eb01311c".parse().unwrap()), None).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_erc20_token_transfer_events(
+        &self,
+        event_query_option: TokenQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<ERC20TokenTransferEvent>> {
+        let params = event_query_option
+            .into_params_with(params.unwrap_or_default(), |address| self.format_address(address));
+        let query = self.create_query("account", "tokentx", params);
+        let response: Response<Vec<ERC20TokenTransferEvent>> = self.get_list_json(&query).await?;
+
+        check_ok(&response.status, &response.message, response.result)
+    }
+
+    /// Like [Self::get_erc20_token_transfer_events], for the transfers between two unix
+    /// timestamps in seconds, both inclusive.
+    ///
+    /// The timestamps are resolved to blocks with [Client::get_block_range_by_time] and replace
+    /// the block range of `params`, this costs two requests more. Returns no transfers if no
+    /// block was mined in the range.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, account::TokenQueryOption};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     // January 2023
+    ///     let transfers = client
+    ///         .get_erc20_token_transfer_events_between_times(
+    ///             TokenQueryOption::ByAddress(
+    ///                 "0x4e83362442b8d1bec281594cea3050c8eb01311c".parse().unwrap(),
+    ///             ),
+    ///             1672531200,
+    ///             1675209599,
+    ///             None,
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    /// # }
+    /// ```
+    pub async fn get_erc20_token_transfer_events_between_times(
+        &self,
+        event_query_option: TokenQueryOption,
+        start_time: u64,
+        end_time: u64,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<ERC20TokenTransferEvent>> {
+        let (start, end) = match self.get_block_range_by_time(start_time, end_time).await? {
+            Some(range) => range,
+            None => return Ok(Vec::new()),
+        };
+        let params = params.unwrap_or_default().start_block(start).end_block(end);
+        self.get_erc20_token_transfer_events(event_query_option, Some(params)).await
+    }
+
+    /// Like [Self::get_erc20_token_transfer_events], passing transfers that borrow their text
+    /// fields from the response body to `f` instead of allocating them.
+    pub async fn get_erc20_token_transfer_events_with<R>(
+        &self,
+        event_query_option: TokenQueryOption,
+        params: Option<TxListParams>,
+        f: impl FnOnce(Vec<ERC20TokenTransferEventRef<'_>>) -> R,
+    ) -> Result<R> {
+        let params = event_query_option
+            .into_params_with(params.unwrap_or_default(), |address| self.format_address(address));
+        let query = self.create_query("account", "tokentx", params);
+        let body = self.get_list_body(&query).await?;
+        let response: Response<Vec<ERC20TokenTransferEventRef<'_>>> =
+            self.sanitize_list_response(&body)?;
+
+        check_ok(&response.status, &response.message, ())?;
+        Ok(f(response.result))
+    }
+
+    /// Returns the ERC-20 token transfers of an address in one [Direction], with optional
+    /// filtering by token contract.
+    ///
+    /// Etherscan returns the transfers in both directions, the filtering is done locally, so
+    /// pagination in `params` applies to the unfiltered list.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, account::Direction};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let received = client
+    ///         .get_erc20_token_transfer_events_by_direction(
+    ///             &"0x4e83362442b8d1bec281594cea3050c8eb01311c".parse().unwrap(),
+    ///             None,
+    ///             Direction::In,
+    ///             None,
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    /// # }
+    /// ```
+    pub async fn get_erc20_token_transfer_events_by_direction(
+        &self,
+        address: &Address,
+        contract: Option<Address>,
+        direction: Direction,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<ERC20TokenTransferEvent>> {
+        let event_query_option = match contract {
+            Some(contract) => TokenQueryOption::ByAddressAndContract(*address, contract),
+            None => TokenQueryOption::ByAddress(*address),
+        };
+        let mut transfers =
+            self.get_erc20_token_transfer_events(event_query_option, params).await?;
+        transfers
+            .retain(|transfer| direction.matches(address, &transfer.from, transfer.to.as_ref()));
+        Ok(transfers)
+    }
+
+    /// Returns all ERC-20 transfers of a token contract between two blocks, inclusive, splitting
+    /// the block range as needed.
+    ///
+    /// Etherscan only serves the first 10000 results of a query, so whenever a range hits that
+    /// cap it's bisected and both halves are queried again. The transfers are ordered by block
+    /// number and transaction index.
+    ///
+    /// Etherscan doesn't report log indices, so transfers are deduplicated by transaction hash and
+    /// their position among the transaction's transfers instead. Identical transfers within one
+    /// transaction are all kept.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [EtherscanError::ResultWindowTooLarge] if a single block has more transfers
+    /// than a query can return.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let transfers = client
+    ///         .get_all_erc20_transfers_for_contract(
+    ///             &"0xdac17f958d2ee523a2206206994597c13d831ec7".parse().unwrap(),
+    ///             17000000,
+    ///             17010000,
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    /// # }
+    /// ```
+    pub async fn get_all_erc20_transfers_for_contract(
+        &self,
+        contract: &Address,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<ERC20TokenTransferEvent>> {
+        self.get_all_erc20_transfers_with_limit(contract, start, end, MAX_RESULT_WINDOW).await
+    }
+
+    async fn get_all_erc20_transfers_with_limit(
+        &self,
+        contract: &Address,
+        start: u64,
+        end: u64,
+        limit: u64,
+    ) -> Result<Vec<ERC20TokenTransferEvent>> {
+        // transfers keyed by block, hash and position in the transaction
+        let mut transfers = BTreeMap::new();
+        // ranges left to query, the next one is at the end
+        let mut ranges = vec![(start, end)];
+        while let Some((from, to)) = ranges.pop() {
+            let params = TxListParams::new(from, to, 1, limit, Sort::Asc);
+            let page = self
+                .get_erc20_token_transfer_events(
+                    TokenQueryOption::ByContract(*contract),
+                    Some(params),
+                )
+                .await?;
+            if (page.len() as u64) < limit {
+                let mut positions = HashMap::<H256, usize>::new();
+                for transfer in page {
+                    let position = positions.entry(transfer.hash).or_default();
+                    let key = (
+                        transfer.block_number.as_number(),
+                        transfer.transaction_index,
+                        transfer.hash,
+                        *position,
+                    );
+                    *position += 1;
+                    transfers.entry(key).or_insert(transfer);
+                }
+            } else if from < to {
+                let mid = from + (to - from) / 2;
+                ranges.push((mid + 1, to));
+                ranges.push((from, mid));
+            } else {
+                return Err(EtherscanError::ResultWindowTooLarge { page: 1, offset: limit })
+            }
+        }
+        Ok(transfers.into_values().collect())
+    }
+
+    /// Returns the number and volume of a token's transfers per UTC day between two blocks,
+    /// inclusive, ordered by day.
+    ///
+    /// The transfers are fetched with [Self::get_all_erc20_transfers_for_contract], so the block
+    /// range is split as needed to get all of them. Days at the ends of the range only count the
+    /// transfers within it.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let days = client
+    ///         .daily_token_activity(
+    ///             &"0xdac17f958d2ee523a2206206994597c13d831ec7".parse().unwrap(),
+    ///             17000000,
+    ///             17010000,
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    ///     for day in days {
+    ///         println!("{}: {} transfers, {} volume", day.date, day.count, day.volume);
+    ///     }
+    /// # }
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub async fn daily_token_activity(
+        &self,
+        contract: &Address,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<crate::analytics::DailyTokenActivity>> {
+        let transfers = self.get_all_erc20_transfers_for_contract(contract, start, end).await?;
+        Ok(crate::analytics::aggregate_transfers_by_day(&transfers))
+    }
+
+    /// Returns the list of ERC-721 ( NFT ) tokens transferred by an address, with optional
+    /// filtering by token contract.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, account::TokenQueryOption};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let txs = client
+    ///         .get_erc721_token_transfer_events(
+    ///             TokenQueryOption::ByAddressAndContract(
+    ///                 "0x6975be450864c02b4613023c2152ee0743572325".parse().unwrap(),
+    ///                 "0x06012c8cf97bead5deae237070f9587f8e7a266d".parse().unwrap(),
+    ///          ), None).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_erc721_token_transfer_events(
+        &self,
+        event_query_option: TokenQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<ERC721TokenTransferEvent>> {
+        let params = event_query_option
+            .into_params_with(params.unwrap_or_default(), |address| self.format_address(address));
+        let query = self.create_query("account", "tokennfttx", params);
+        let response: Response<Vec<ERC721TokenTransferEvent>> = self.get_list_json(&query).await?;
+
+        check_ok(&response.status, &response.message, response.result)
+    }
+
+    /// Returns the list of ERC-1155 ( NFT ) tokens transferred by an address, with optional
+    /// filtering by token contract.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, account::TokenQueryOption};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let txs = client
+    ///         .get_erc1155_token_transfer_events(
+    ///             TokenQueryOption::ByAddressAndContract(
+    ///                 "0x216CD350a4044e7016f14936663e2880Dd2A39d7".parse().unwrap(),
+    ///                 "0x495f947276749ce646f68ac8c248420045cb7b5e".parse().unwrap(),
+    ///          ), None).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_erc1155_token_transfer_events(
+        &self,
+        event_query_option: TokenQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<ERC1155TokenTransferEvent>> {
+        let params = event_query_option
+            .into_params_with(params.unwrap_or_default(), |address| self.format_address(address));
+        let query = self.create_query("account", "token1155tx", params);
+        let response: Response<Vec<ERC1155TokenTransferEvent>> = self.get_list_json(&query).await?;
+
+        check_ok(&response.status, &response.message, response.result)
+    }
+
+    /// Returns the list of blocks mined by an address.
+    ///
+    /// For blocks proposed after the Merge see [Self::get_validated_blocks].
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let blocks = client
+    ///         .get_mined_blocks(&"0x9dd134d14d1e65f84b706d6f205cd5b1cd03a46b".parse().unwrap(), None, None)
+    ///         .await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_mined_blocks(
+        &self,
+        address: &Address,
+        block_type: Option<BlockType>,
+        page_and_offset: Option<(u64, u64)>,
+    ) -> Result<Vec<MinedBlock>> {
+        let mut params = HashMap::new();
+        params.insert("address", self.format_address(address));
+        params.insert("blocktype", block_type.unwrap_or_default().to_string());
+        if let Some((page, offset)) = page_and_offset {
+            params.insert("page", page.to_string());
+            params.insert("offset", offset.to_string());
+        }
+        let query = self.create_query("account", "getminedblocks", params);
+        let response: Response<Vec<MinedBlock>> = self.get_json(&query).await?;
+
+        check_ok(&response.status, &response.message, response.result)
+    }
+
+    /// Returns the list of blocks validated, i.e. proposed, by an address.
+    ///
+    /// Since the Merge blocks are proposed by validators instead of mined, with the address being
+    /// the fee recipient of the block. This is the same as [Self::get_mined_blocks], which also
+    /// returns the proof of work blocks mined before the Merge.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let blocks = client
+    ///         .get_validated_blocks(&"0x95222290DD7278Aa3Ddd389Cc1E1d165CC4BAfe5".parse().unwrap(), None)
+    ///         .await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_validated_blocks(
+        &self,
+        address: &Address,
+        page_and_offset: Option<(u64, u64)>,
+    ) -> Result<Vec<MinedBlock>> {
+        self.get_mined_blocks(address, Some(BlockType::CanonicalBlocks), page_and_offset).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::{
+            self, empty_list, hashed_tx_json, with_fields, ERC20_TRANSFER, NORMAL_TRANSACTION,
+        },
+        mock::{query_param, MockResponse, MockServer},
+    };
+    use ethers_core::{abi::parse_abi, types::Chain};
+
+    fn internal_tx(trace_id: &str) -> InternalTransaction {
+        let mut tx = fixtures::internal_transaction();
+        tx.trace_id = trace_id.to_string();
+        tx
+    }
+
+    fn normal_tx(input: &str) -> NormalTransaction {
+        normal_tx_at(14923678, 61, input)
+    }
+
+    fn normal_tx_at(block: u64, index: u64, input: &str) -> NormalTransaction {
+        serde_json::from_value(normal_tx_json(block, index, input)).unwrap()
+    }
+
+    /// A transaction as returned by the API
+    fn normal_tx_json(block: u64, index: u64, input: &str) -> serde_json::Value {
+        with_fields(
+            NORMAL_TRANSACTION,
+            serde_json::json!({
+                "blockNumber": block.to_string(),
+                "transactionIndex": index.to_string(),
+                "input": input,
+            }),
+        )
+    }
+
+    fn hashed_tx(block: u64, hash: u64) -> NormalTransaction {
+        let mut tx = normal_tx_at(block, 0, "0x");
+        tx.hash = GenesisOption::Some(H256::from_low_u64_be(hash));
+        tx
+    }
+
+    fn hashes(txs: &[NormalTransaction]) -> Vec<u64> {
+        txs.iter().map(|tx| tx.hash.value().unwrap().to_low_u64_be()).collect()
+    }
+
+    #[test]
+    fn can_match_direction() {
+        let (address, other) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let inbound = (other, Some(address));
+        let outbound = (address, Some(other));
+        let to_self = (address, Some(address));
+        let creation = (address, None);
+
+        let matches = |direction: Direction| {
+            [inbound, outbound, to_self, creation]
+                .map(|(from, to)| direction.matches(&address, &from, to.as_ref()))
+        };
+        assert_eq!(matches(Direction::In), [true, false, true, false]);
+        assert_eq!(matches(Direction::Out), [false, true, true, true]);
+        assert_eq!(matches(Direction::Both), [true, true, true, true]);
+        assert!(!Direction::Both.matches(&address, &other, Some(&other)));
+    }
+
+    #[test]
+    fn can_find_deployed_contracts() {
+        let deployer: Address = "0x9aa99c23f67c81701c772b106b4f83f6e858dd2e".parse().unwrap();
+        let contract = |byte| Address::repeat_byte(byte);
+        let creation = |block: u64, hash: u64, created: u8| {
+            let mut tx = hashed_tx(block, hash);
+            tx.to = None;
+            tx.contract_address = Some(contract(created));
+            tx
+        };
+        let mut failed = creation(1, 4, 4);
+        failed.is_error = "1".to_string();
+        let txs = vec![creation(3, 1, 1), hashed_tx(2, 2), failed];
+
+        let create = |block: u64, hash: u64, kind: &str, created: u8| {
+            let mut tx = internal_tx("0");
+            tx.block_number = block.into();
+            tx.hash = H256::from_low_u64_be(hash);
+            tx.from = deployer;
+            tx.result_type = kind.to_string();
+            tx.contract_address = GenesisOption::Some(contract(created));
+            tx
+        };
+        let mut foreign = create(2, 6, "create", 6);
+        foreign.from = contract(9);
+        let internal = vec![
+            create(2, 3, "create2", 2),
+            create(2, 5, "call", 5),
+            foreign,
+            create(4, 7, "create", 1),
+        ];
+
+        let deployed = deployed_contracts(&deployer, txs, internal);
+        assert_eq!(
+            deployed,
+            [(contract(2), H256::from_low_u64_be(3)), (contract(1), H256::from_low_u64_be(1))]
+        );
+    }
+
+    #[test]
+    fn can_merge_ledger() {
+        let mut failed = hashed_tx(2, 1);
+        failed.transaction_index = Some(5);
+        failed.is_error = "1".to_string();
+        let mut first = hashed_tx(2, 2);
+        first.transaction_index = Some(1);
+        let txs = vec![failed, hashed_tx(1, 3), first];
+
+        let internal_at = |block: u64, hash: u64, trace_id: &str| {
+            let mut tx = internal_tx(trace_id);
+            tx.block_number = block.into();
+            tx.hash = H256::from_low_u64_be(hash);
+            tx
+        };
+        let internal = vec![
+            internal_at(2, 1, "0_1"),
+            // the parent is not in the ledger
+            internal_at(2, 9, "0"),
+            internal_at(2, 1, "0"),
+        ];
+
+        let ledger = merge_ledger(txs, internal);
+        let order: Vec<_> = ledger
+            .iter()
+            .map(|entry| (entry.hash.unwrap().to_low_u64_be(), entry.trace_id.as_deref()))
+            .collect();
+        assert_eq!(
+            order,
+            [(3, None), (2, None), (1, None), (1, Some("0")), (1, Some("0_1")), (9, Some("0"))]
+        );
+        assert!(ledger[2].is_error);
+        assert_eq!(ledger[3].source, LedgerSource::Internal);
+        assert_eq!(ledger[3].transaction_index, Some(5));
+    }
+
+    #[test]
+    fn can_page_with_block_cursor() {
+        let params = TxListParams::default().page_size(3);
+        let mut cursor = TxPageCursor::new(Address::zero(), params);
+
+        let page = cursor.advance(vec![hashed_tx(1, 1), hashed_tx(2, 2), hashed_tx(2, 3)]);
+        assert_eq!(hashes(&page), [1, 2, 3]);
+        assert_eq!(cursor.params.start_block, 2.into());
+        assert_eq!(cursor.params.page, 1);
+
+        // the cursor block is returned again
+        let page = cursor.advance(vec![hashed_tx(2, 2), hashed_tx(2, 3), hashed_tx(2, 4)]);
+        assert_eq!(hashes(&page), [4]);
+        assert_eq!(cursor.params.start_block, 2.into());
+        assert_eq!(cursor.params.page, 2);
+        assert!(!cursor.done);
+
+        let page = cursor.advance(vec![hashed_tx(2, 5), hashed_tx(3, 6)]);
+        assert_eq!(hashes(&page), [5, 6]);
+        assert!(cursor.done);
+    }
+
+    #[test]
+    fn can_page_descending() {
+        let params = TxListParams::new(0, 99999999, 0, 2, Sort::Desc);
+        let mut cursor = TxPageCursor::new(Address::zero(), params);
+
+        let page = cursor.advance(vec![hashed_tx(9, 1), hashed_tx(8, 2)]);
+        assert_eq!(hashes(&page), [1, 2]);
+        assert_eq!(cursor.params.end_block, 8.into());
+        assert_eq!(cursor.params.start_block, 0.into());
+
+        let page = cursor.advance(vec![hashed_tx(8, 2)]);
+        assert!(page.is_empty());
+        assert!(cursor.done);
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn can_write_csv_row() {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(CSV_HEADER).unwrap();
+        write_csv_row(&mut writer, &normal_tx("0x")).unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        let mut lines = csv.lines();
+        assert_eq!(lines.next().unwrap(), CSV_HEADER.join(","));
+        assert_eq!(
+            lines.next().unwrap(),
+            "0xc276cc8d0b5e9b5ef5a9e3e41e7dd2c4e5cb34af571b9fd4e9a4bf77d1d1a6b8,14923678,\
+             2022-06-08T00:00:11Z,0x9aa99c23f67c81701c772b106b4f83f6e858dd2e,\
+             0xdac17f958d2ee523a2206206994597c13d831ec7,0.000000000000000000,94813,32861441914,\
+             63209,0,,\"transfer(address _to, uint256 _value)\""
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "arrow")]
+    fn can_convert_transactions_to_record_batch() {
+        use arrow::{
+            array::{Array, AsArray},
+            datatypes::{DataType, Decimal128Type, Int64Type, TimeUnit, TimestampSecondType},
+        };
+
+        let txs = [fixtures::normal_transaction(), fixtures::genesis_transaction()];
+        let batch = transactions_to_record_batch(&txs);
+
+        let schema = batch.schema();
+        let names: Vec<_> = schema.fields().iter().map(|field| field.name().as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "hash",
+                "block_number",
+                "timestamp",
+                "from",
+                "to",
+                "value",
+                "gas",
+                "gas_price",
+                "gas_used",
+                "is_error",
+                "contract_address",
+                "function_name",
+            ]
+        );
+        assert_eq!(schema.field(1).data_type(), &DataType::Int64);
+        assert_eq!(
+            schema.field(2).data_type(),
+            &DataType::Timestamp(TimeUnit::Second, Some("UTC".into()))
+        );
+        assert_eq!(schema.field(5).data_type(), &DataType::Decimal128(38, 0));
+        assert_eq!(batch.num_rows(), 2);
+
+        let column = |name| batch.column_by_name(name).unwrap();
+        assert_eq!(column("block_number").as_primitive::<Int64Type>().values(), &[14923678, 0]);
+        assert_eq!(
+            column("timestamp").as_primitive::<TimestampSecondType>().values(),
+            &[1654646411, 1438269973]
+        );
+        assert_eq!(
+            column("value").as_primitive::<Decimal128Type>().value(1),
+            10_000_000_000_000_000_000_000
+        );
+        assert_eq!(
+            column("to").as_string::<i32>().value(0),
+            "0xdac17f958d2ee523a2206206994597c13d831ec7"
+        );
+
+        // the genesis allocation has no hash, sender, gas price or function
+        for name in ["hash", "from", "gas_price", "function_name"] {
+            assert!(column(name).is_valid(0), "{name} of the transaction is null");
+            assert!(column(name).is_null(1), "{name} of the genesis allocation isn't null");
+        }
+        assert_eq!(column("contract_address").null_count(), 2);
+    }
+
+    fn trace_ids(node: &TraceNode) -> Vec<&str> {
+        node.children.iter().map(|child| child.trace_id.as_str()).collect()
+    }
+
+    #[test]
+    fn can_build_trace_tree() {
+        let txs: Vec<_> =
+            ["0_1_1", "0", "0_2", "0_1", "1", "0_1_0"].into_iter().map(internal_tx).collect();
+        let root = build_trace_tree(&txs);
+
+        assert_eq!(root.trace_id, "");
+        assert_eq!(trace_ids(&root), ["0", "1"]);
+        assert_eq!(trace_ids(&root.children[0]), ["0_1", "0_2"]);
+        assert_eq!(trace_ids(&root.children[0].children[0]), ["0_1_0", "0_1_1"]);
+        assert!(root.children[1].children.is_empty());
+        assert_eq!(root.children[0].gas, txs[1].gas);
+    }
+
+    #[test]
+    fn attaches_orphans_to_root() {
+        let txs: Vec<_> =
+            ["0", "2_1", "garbage", "0", "0.1", ""].into_iter().map(internal_tx).collect();
+        let root = build_trace_tree(&txs);
+
+        assert_eq!(trace_ids(&root), ["0", "2_1", "garbage", "0", ""]);
+        assert_eq!(trace_ids(&root.children[0]), ["0.1"]);
+    }
+
+    #[test]
+    fn sorts_calls_with_missing_parents_among_top_level_calls() {
+        let txs: Vec<_> = ["2", "bad", "1_3", "0", "0_4_1"].into_iter().map(internal_tx).collect();
+        let root = build_trace_tree(&txs);
+
+        assert_eq!(trace_ids(&root), ["0", "0_4_1", "1_3", "2", "bad"]);
+        assert!(root.children[0].children.is_empty());
+    }
+
+    #[test]
+    fn can_decode_input() {
+        let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+        let abi = parse_abi(&["function transfer(address to, uint256 value)"]).unwrap();
+
+        let tx = normal_tx("0xa9059cbb0000000000000000000000009aa99c23f67c81701c772b106b4f83f6e858dd2e00000000000000000000000000000000000000000000000000000000000f4240");
+        let (function, args) = client.decode_input(&tx, &abi).unwrap();
+        assert_eq!(function.name, "transfer");
+        assert_eq!(
+            args,
+            [
+                Token::Address("0x9aa99c23f67c81701c772b106b4f83f6e858dd2e".parse().unwrap()),
+                Token::Uint(1_000_000u64.into())
+            ]
+        );
+
+        let err = client.decode_input(&normal_tx("0x"), &abi).unwrap_err();
+        assert!(matches!(err, EtherscanError::EmptyTransactionInput));
+
+        let err = client.decode_input(&normal_tx("0x095ea7b3"), &abi).unwrap_err();
+        assert!(matches!(err, EtherscanError::UnknownFunctionSelector(_)));
+
+        let err = client.decode_input(&normal_tx("0xa9059cbb00"), &abi).unwrap_err();
+        assert!(matches!(err, EtherscanError::Abi(_)));
+    }
+
+    #[test]
+    fn page_size_takes_precedence_over_offset() {
+        let params: HashMap<_, _> = TxListParams::new(0, 100, 1, 50, Sort::Asc).into();
+        assert_eq!(params["offset"], "50");
+
+        let params: HashMap<_, _> =
+            TxListParams::new(0, 100, 1, 50, Sort::Asc).page_size(20).into();
+        assert_eq!(params["offset"], "20");
+
+        let params: HashMap<_, _> = TxListParams::default().page_size(20).into();
+        assert_eq!(params["offset"], "20");
+    }
+
+    #[test]
+    #[cfg(feature = "decimal")]
+    fn can_convert_to_decimal() {
+        let balance =
+            AccountBalance { account: Address::zero(), balance: "1500000000000000001".to_string() };
+        assert_eq!(balance.balance_decimal().unwrap().to_string(), "1.500000000000000001");
+
+        let balance = AccountBalance { account: Address::zero(), balance: "0".to_string() };
+        assert_eq!(balance.balance_decimal().unwrap(), Decimal::ZERO);
+
+        // 2^96 exceeds the mantissa of a `Decimal`
+        assert_eq!(to_decimal(U256::from(2).pow(96.into()), 18), None);
+        assert_eq!(to_decimal(1u64.into(), 29), None);
+        assert_eq!(to_decimal(1_000_000u64.into(), 6).unwrap(), Decimal::ONE);
+
+        assert_eq!(normal_tx("0x").value_decimal().unwrap(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn can_sort_transactions() {
+        let mut txs =
+            vec![normal_tx_at(2, 0, "0x"), normal_tx_at(1, 5, "0x"), normal_tx_at(1, 2, "0x")];
+        let order = |txs: &[NormalTransaction]| {
+            txs.iter()
+                .map(|tx| (tx.block_number.as_number().unwrap().as_u64(), tx.transaction_index))
+                .collect::<Vec<_>>()
+        };
+
+        sort_transactions(&mut txs, Sort::Asc);
+        assert_eq!(order(&txs), [(1, Some(2)), (1, Some(5)), (2, Some(0))]);
+
+        sort_transactions(&mut txs, Sort::Desc);
+        assert_eq!(order(&txs), [(2, Some(0)), (1, Some(5)), (1, Some(2))]);
+    }
+
+    #[cfg(feature = "pro")]
+    #[tokio::test]
+    async fn can_get_balances_at_block() {
+        let server = MockServer::start(vec![
+            MockResponse::json(r#"{"status":"1","message":"OK","result":"10"}"#),
+            MockResponse::json(r#"{"status":"1","message":"OK","result":"20"}"#),
+        ])
+        .await;
+
+        let addresses = [Address::repeat_byte(1), Address::repeat_byte(2)];
+        let balances = server
+            .client()
+            .get_ether_balances_at_block(&[&addresses[0], &addresses[1]], 8000000)
+            .await
+            .unwrap();
+        let balances: Vec<_> = balances.iter().map(|b| (b.account, b.balance.as_str())).collect();
+        assert_eq!(balances, [(addresses[0], "10"), (addresses[1], "20")]);
+
+        let requests = server.requests();
+        assert_eq!(query_param(&requests[1], "action"), Some("balancehistory"));
+        assert_eq!(
+            query_param(&requests[1], "address"),
+            Some(format!("{:?}", addresses[1]).as_str())
+        );
+        assert_eq!(query_param(&requests[1], "blockno"), Some("8000000"));
+    }
+
+    #[tokio::test]
+    async fn balance_errors_are_typed() {
+        let server = MockServer::start(vec![
+            MockResponse::json(
+                r#"{"status":"0","message":"NOTOK","result":"Max rate limit reached, please use API Key for higher rate limit"}"#,
+            ),
+            MockResponse::json(
+                r#"{"status":"0","message":"NOTOK","result":"Error! Invalid address format"}"#,
+            ),
+        ])
+        .await;
+
+        let address = Address::repeat_byte(1);
+        let err = server.client().get_ether_balance_single(&address, None).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::RateLimitExceeded));
+        let err = server.client().get_ether_balance_single(&address, None).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::BalanceFailed));
+    }
+
+    #[tokio::test]
+    async fn can_get_transaction_count() {
+        let server = MockServer::start(vec![MockResponse::json(
+            r#"{"jsonrpc":"2.0","id":1,"result":"0x2a"}"#,
+        )])
+        .await;
+
+        let address = Address::repeat_byte(1);
+        let count =
+            server.client().get_transaction_count(&address, Some(Tag::Pending)).await.unwrap();
+        assert_eq!(count, 42.into());
+
+        let request = &server.requests()[0];
+        assert_eq!(query_param(request, "action"), Some("eth_getTransactionCount"));
+        assert_eq!(query_param(request, "tag"), Some("pending"));
+    }
+
+    #[tokio::test]
+    async fn can_post_sort_transactions() {
+        let txs =
+            [normal_tx_json(1, 5, "0x"), normal_tx_json(1, 2, "0x"), normal_tx_json(2, 0, "0x")];
+        let body = serde_json::json!({ "status": "1", "message": "OK", "result": txs });
+        let server = MockServer::start(vec![MockResponse::json(body.to_string())]).await;
+        let indices = |txs: Vec<NormalTransaction>| {
+            txs.into_iter().map(|tx| tx.transaction_index.unwrap()).collect::<Vec<_>>()
+        };
+
+        let address = Address::repeat_byte(1);
+        let params = TxListParams::default().sorted(true);
+        let txs = server.client().get_transactions(&address, Some(params)).await.unwrap();
+        assert_eq!(indices(txs), [2, 5, 0]);
+
+        let txs = server.client().get_transactions(&address, None).await.unwrap();
+        assert_eq!(indices(txs), [5, 2, 0]);
+    }
+
+    fn erc20_transfer_json(block: u64, hash: u64) -> serde_json::Value {
+        with_fields(
+            ERC20_TRANSFER,
+            serde_json::json!({
+                "blockNumber": block.to_string(),
+                "hash": format!("{:?}", H256::from_low_u64_be(hash)),
+                "blockHash": format!("{:?}", H256::from_low_u64_be(block)),
+            }),
+        )
+    }
+
+    fn list_response(rows: Vec<serde_json::Value>) -> MockResponse {
+        let rows: Vec<_> = rows.iter().map(serde_json::Value::to_string).collect();
+        let rows: Vec<_> = rows.iter().map(String::as_str).collect();
+        MockResponse::json(fixtures::list_response(&rows))
+    }
+
+    #[tokio::test]
+    async fn can_split_erc20_transfer_ranges() {
+        let server = MockServer::start(vec![
+            // 0..=3 hits the cap
+            list_response(vec![
+                erc20_transfer_json(1, 1),
+                erc20_transfer_json(2, 2),
+                erc20_transfer_json(3, 3),
+            ]),
+            list_response(vec![erc20_transfer_json(1, 1)]),
+            // 2..=3 hits the cap
+            list_response(vec![
+                erc20_transfer_json(2, 2),
+                erc20_transfer_json(3, 3),
+                erc20_transfer_json(3, 3),
+            ]),
+            list_response(vec![erc20_transfer_json(2, 2)]),
+            // two identical transfers in one transaction
+            list_response(vec![erc20_transfer_json(3, 3), erc20_transfer_json(3, 3)]),
+        ])
+        .await;
+
+        let contract = Address::repeat_byte(1);
+        let transfers =
+            server.client().get_all_erc20_transfers_with_limit(&contract, 0, 3, 3).await;
+        let hashes: Vec<_> =
+            transfers.unwrap().into_iter().map(|t| t.hash.to_low_u64_be()).collect();
+        assert_eq!(hashes, [1, 2, 3, 3]);
+
+        let requests = server.requests();
+        let ranges: Vec<_> = requests
+            .iter()
+            .map(|r| (query_param(r, "startBlock").unwrap(), query_param(r, "endBlock").unwrap()))
+            .collect();
+        assert_eq!(ranges, [("0", "3"), ("0", "1"), ("2", "3"), ("2", "2"), ("3", "3")]);
+        assert_eq!(
+            query_param(&server.requests()[0], "contractaddress"),
+            Some("0x0101010101010101010101010101010101010101")
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_on_capped_erc20_transfer_block() {
+        let server =
+            MockServer::start(vec![list_response(vec![erc20_transfer_json(5, 1); 3])]).await;
+
+        let contract = Address::repeat_byte(1);
+        let err = server
+            .client()
+            .get_all_erc20_transfers_with_limit(&contract, 5, 5, 3)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EtherscanError::ResultWindowTooLarge { page: 1, offset: 3 }));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "chrono")]
+    async fn can_get_daily_token_activity() {
+        let transfer = |hash, time_stamp: &str, value: &str| {
+            let mut transfer = erc20_transfer_json(1, hash);
+            transfer["timeStamp"] = time_stamp.into();
+            transfer["value"] = value.into();
+            transfer
+        };
+        // 2022-06-07 23:59:59, 2022-06-08 00:00:00 and 2022-06-08 12:00:00 UTC
+        let server = MockServer::start(vec![list_response(vec![
+            transfer(1, "1654646399", "5"),
+            transfer(2, "1654646400", "10"),
+            transfer(3, "1654689600", "20"),
+        ])])
+        .await;
+
+        let days =
+            server.client().daily_token_activity(&Address::repeat_byte(1), 1, 2).await.unwrap();
+        let days: Vec<_> =
+            days.into_iter().map(|day| (day.date.to_string(), day.count, day.volume)).collect();
+        assert_eq!(
+            days,
+            [("2022-06-07".to_string(), 1, 5.into()), ("2022-06-08".to_string(), 2, 30.into())]
+        );
+    }
+
+    #[tokio::test]
+    async fn can_borrow_from_response() {
+        let mut tx = normal_tx_json(1, 0, "0xa9059cbb");
+        tx["functionName"] = "transfer(address to, uint256 \"value\")".into();
+        let server = MockServer::start(vec![
+            list_response(vec![tx]),
+            list_response(vec![erc20_transfer_json(1, 1)]),
+            MockResponse::json(r#"{"status":"0","message":"NOTOK","result":"Invalid API Key"}"#),
+        ])
+        .await;
+
+        let address = Address::repeat_byte(1);
+        let (block, time_stamp, input, function_name) = server
+            .client()
+            .get_transactions_with(&address, None, |txs| {
+                let tx = &txs[0];
+                assert!(matches!(tx.time_stamp, Cow::Borrowed(_)));
+                // escaped text can't be borrowed
+                assert!(matches!(tx.function_name, Cow::Owned(_)));
+                let text = |s: &Cow<'_, str>| s.to_string();
+                (tx.block_number, text(&tx.time_stamp), text(&tx.input), text(&tx.function_name))
+            })
+            .await
+            .unwrap();
+        assert_eq!(block, 1.into());
+        assert_eq!(time_stamp, "1654646411");
+        assert_eq!(input, "0xa9059cbb");
+        assert_eq!(function_name, "transfer(address to, uint256 \"value\")");
+
+        let symbol = server
+            .client()
+            .get_erc20_token_transfer_events_with(TokenQueryOption::ByAddress(address), None, |t| {
+                t[0].token_symbol.to_string()
+            })
+            .await
+            .unwrap();
+        assert_eq!(symbol, "USDT");
+
+        let err = server.client().get_transactions_with(&address, None, |_| ()).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::InvalidApiKey));
+    }
+
+    #[test]
+    fn can_format_balance() {
+        let balance =
+            AccountBalance { account: Address::zero(), balance: "1500000000000000000".to_string() };
+        let polygon = NativeCurrency::for_chain(ethers_core::types::Chain::Polygon);
+        assert_eq!(balance.format(&polygon).unwrap(), "1.5 MATIC");
+        let invalid = AccountBalance { balance: "-".to_string(), ..balance };
+        assert_eq!(invalid.format(&polygon), None);
+    }
+
+    #[test]
+    fn can_parse_numeric_placeholders() {
+        // a genesis allocation row, with the placeholders early rows put in numeric fields
+        let tx: NormalTransaction = serde_json::from_str(
+            r#"{"blockNumber":"0","timeStamp":"1438269973","hash":"GENESIS_ddbd2b932c763ba5b1b7ae3b362eac3e8d40121a","nonce":"","blockHash":"","transactionIndex":"-","from":"GENESIS","to":"0xddbd2b932c763ba5b1b7ae3b362eac3e8d40121a","value":"10000000000000000000000","gas":"-","gasPrice":"-","isError":"0","txreceipt_status":"","input":"0x","contractAddress":"","cumulativeGasUsed":"","gasUsed":"-","confirmations":"17000000","methodId":"0x","functionName":""}"#,
+        )
+        .unwrap();
+        assert!(tx.hash.is_genesis());
+        assert_eq!(tx.gas, U256::zero());
+        assert_eq!(tx.gas_price, None);
+        assert_eq!(tx.gas_used, U256::zero());
+        assert_eq!(tx.cumulative_gas_used, U256::zero());
+        assert_eq!(tx.transaction_index, None);
+        assert_eq!(tx.value, U256::exp10(22));
+
+        let tx: InternalTransaction = serde_json::from_str(
+            r#"{"blockNumber":"46400","timeStamp":"1438918233","hash":"0x8a1a9989bda84f80143181a68bc137ecefa64d0d4ebde45dd94fc0cf49e70cb6","from":"0x9aa99c23f67c81701c772b106b4f83f6e858dd2e","to":"","value":"-","contractAddress":"","input":"","type":"call","gas":"","gasUsed":"-","traceId":"0","isError":"0","errCode":""}"#,
+        )
+        .unwrap();
+        assert_eq!(tx.value, U256::zero());
+        assert_eq!(tx.gas, U256::zero());
+        assert_eq!(tx.gas_used, U256::zero());
+    }
+
+    #[tokio::test]
+    async fn can_get_transactions_with_internals() {
+        let tx = |hash: u64| {
+            let mut tx = normal_tx_json(1, hash, "0x");
+            tx["hash"] = format!("{:?}", H256::from_low_u64_be(hash)).into();
+            tx
+        };
+        let internal = |trace_id: &str| {
+            serde_json::json!({
+                "blockNumber": "1",
+                "timeStamp": "1654646411",
+                "hash": format!("{:?}", H256::from_low_u64_be(1)),
+                "from": "0x9aa99c23f67c81701c772b106b4f83f6e858dd2e",
+                "to": "0xdac17f958d2ee523a2206206994597c13d831ec7",
+                "value": "5",
+                "contractAddress": "",
+                "input": "",
+                "type": "call",
+                "gas": "2300",
+                "gasUsed": "0",
+                "traceId": trace_id,
+                "isError": "0",
+                "errCode": ""
+            })
+        };
+        let server = MockServer::start(vec![
+            list_response(vec![tx(1), tx(2)]),
+            list_response(vec![internal("0_1"), internal("0")]),
+            MockResponse::json(empty_list()),
+        ])
+        .await;
+
+        let txs =
+            server.client().get_transactions_with_internals(&Address::zero(), None).await.unwrap();
+        assert_eq!(txs.len(), 2);
+        let trace_ids: Vec<_> = txs[0].internals.iter().map(|tx| tx.trace_id.as_str()).collect();
+        assert_eq!(trace_ids, ["0", "0_1"]);
+        assert!(txs[1].internals.is_empty());
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        let hash = format!("{:?}", H256::from_low_u64_be(2));
+        assert_eq!(query_param(&requests[2], "txhash"), Some(hash.as_str()));
+    }
+
+    #[tokio::test]
+    async fn can_get_first_transaction() {
+        let server = MockServer::start(vec![
+            list_response(vec![normal_tx_json(46147, 0, "0x")]),
+            MockResponse::json(empty_list()),
+        ])
+        .await;
+
+        let address = Address::repeat_byte(1);
+        let tx = server.client().get_first_transaction(&address).await.unwrap().unwrap();
+        assert_eq!(tx.block_number, 46147.into());
+        assert!(server.client().get_first_transaction(&address).await.unwrap().is_none());
+
+        let request = &server.requests()[0];
+        assert_eq!(query_param(request, "sort"), Some("asc"));
+        assert_eq!(query_param(request, "page"), Some("1"));
+        assert_eq!(query_param(request, "offset"), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn can_get_address_info() {
+        let server = MockServer::start(vec![
+            MockResponse::json(r#"{"status":"1","message":"OK","result":"1000"}"#),
+            MockResponse::json(r#"{"jsonrpc":"2.0","id":1,"result":"0x2"}"#),
+            list_response(vec![normal_tx_json(1, 0, "0x")]),
+            list_response(vec![normal_tx_json(5, 0, "0x")]),
+            MockResponse::json(r#"{"jsonrpc":"2.0","id":1,"result":"0x"}"#),
+        ])
+        .await;
+
+        let info = server.client().get_address_info(&Address::repeat_byte(1)).await.unwrap();
+        assert_eq!(info.balance, "1000");
+        assert_eq!(info.transaction_count, 2.into());
+        assert_eq!(info.first_transaction.unwrap().block_number, 1.into());
+        assert_eq!(info.last_transaction.unwrap().block_number, 5.into());
+        assert!(!info.is_contract);
+        let actions: Vec<_> = server
+            .requests()
+            .iter()
+            .map(|r| query_param(r, "action").unwrap().to_owned())
+            .collect();
+        assert_eq!(
+            actions,
+            ["balance", "eth_getTransactionCount", "txlist", "txlist", "eth_getCode"]
+        );
+    }
+
+    #[tokio::test]
+    async fn can_get_last_transaction() {
+        let server = MockServer::start(vec![
+            list_response(vec![normal_tx_json(17000000, 3, "0x")]),
+            MockResponse::json(empty_list()),
+        ])
+        .await;
+
+        let address = Address::repeat_byte(1);
+        let tx = server.client().get_last_transaction(&address).await.unwrap().unwrap();
+        assert_eq!(tx.block_number, 17000000.into());
+        assert!(server.client().get_last_transaction(&address).await.unwrap().is_none());
+
+        let request = &server.requests()[0];
+        assert_eq!(query_param(request, "sort"), Some("desc"));
+        assert_eq!(query_param(request, "offset"), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn can_sum_gas_spent() {
+        let address = Address::repeat_byte(1);
+        let tx = |block, from: Address, gas_price: &str| {
+            let mut tx = hashed_tx_json(block);
+            tx["from"] = format!("{from:?}").into();
+            tx["gasUsed"] = "21000".into();
+            tx["gasPrice"] = gas_price.into();
+            tx
+        };
+        let failed = {
+            let mut tx = tx(2, address, "20");
+            tx["isError"] = "1".into();
+            tx
+        };
+        let server = MockServer::start(vec![
+            list_response(vec![tx(1, address, "10"), failed]),
+            list_response(vec![tx(3, Address::repeat_byte(2), "10"), tx(4, address, "")]),
+            MockResponse::json(empty_list()),
+        ])
+        .await;
+
+        let params = TxListParams::default().page_size(2);
+        let total = server.client().total_gas_spent(&address, Some(params)).await.unwrap();
+        assert_eq!(total, U256::from(21000 * 30));
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "chrono")]
+    async fn can_get_transaction_heatmap() {
+        let tx = |block, time_stamp: &str| {
+            let mut tx = hashed_tx_json(block);
+            tx["timeStamp"] = time_stamp.into();
+            tx
+        };
+        // 2022-06-07 23:59:59, 2022-06-08 00:00:00 and 2022-06-10 12:00:00 UTC
+        let server = MockServer::start(vec![list_response(vec![
+            tx(1, "1654646399"),
+            tx(2, "1654646400"),
+            tx(3, "1654862400"),
+        ])])
+        .await;
+
+        let heatmap = server.client().transaction_heatmap(&Address::repeat_byte(1), 1, 3).await;
+        let days: Vec<_> =
+            heatmap.unwrap().into_iter().map(|(day, count)| (day.to_string(), count)).collect();
+        assert_eq!(
+            days,
+            [
+                ("2022-06-07".to_string(), 1),
+                ("2022-06-08".to_string(), 1),
+                ("2022-06-10".to_string(), 1)
+            ]
+        );
+        let request = &server.requests()[0];
+        assert_eq!(query_param(request, "startBlock"), Some("1"));
+        assert_eq!(query_param(request, "endBlock"), Some("3"));
+    }
+
+    #[tokio::test]
+    async fn can_summarize_transactions_across_pages() {
+        let address = Address::repeat_byte(1);
+        let tx = |block| {
+            let mut tx = hashed_tx_json(block);
+            tx["timeStamp"] = (1000 + block).to_string().into();
+            tx["to"] = format!("{address:?}").into();
+            tx
+        };
+        let server =
+            MockServer::start(vec![list_response(vec![tx(1), tx(2)]), list_response(vec![tx(3)])])
+                .await;
+
+        let params = TxListParams::default().page_size(2);
+        let summary =
+            server.client().get_transactions_summary(&address, Some(params)).await.unwrap();
+        assert_eq!(summary.count(), 3);
+        assert_eq!((summary.first_timestamp, summary.last_timestamp), (Some(1001), Some(1003)));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn can_get_erc20_transfers_between_times() {
+        let block = |number| {
+            MockResponse::json(format!(r#"{{"status":"1","message":"OK","result":"{number}"}}"#))
+        };
+        let server = MockServer::start(vec![
+            block(16308190),
+            block(16530479),
+            list_response(vec![erc20_transfer_json(16400000, 1)]),
+        ])
+        .await;
+
+        let address = Address::repeat_byte(1);
+        let transfers = server
+            .client()
+            .get_erc20_token_transfer_events_between_times(
+                TokenQueryOption::ByAddress(address),
+                1672531200,
+                1675209599,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(transfers.len(), 1);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(query_param(&requests[2], "action"), Some("tokentx"));
+        assert_eq!(query_param(&requests[2], "startBlock"), Some("16308190"));
+        assert_eq!(query_param(&requests[2], "endBlock"), Some("16530479"));
+    }
+
+    #[tokio::test]
+    async fn can_get_contract_transactions() {
+        let contract = Address::repeat_byte(1);
+        let tx = |block, to: Option<Address>| {
+            let mut tx = hashed_tx_json(block);
+            tx["to"] = to.map(|to| format!("{to:?}")).unwrap_or_default().into();
+            tx
+        };
+        let mut outgoing = tx(3, Some(Address::repeat_byte(2)));
+        outgoing["from"] = format!("{contract:?}").into();
+        let server = MockServer::start(vec![list_response(vec![
+            tx(1, None),
+            tx(2, Some(contract)),
+            outgoing,
+            tx(4, Some(contract)),
+        ])])
+        .await;
+
+        let txs = server.client().get_contract_transactions(&contract, None).await.unwrap();
+        let blocks: Vec<_> = txs.iter().map(|tx| tx.block_number.as_number().unwrap()).collect();
+        assert_eq!(blocks, [2.into(), 4.into()]);
+    }
+
+    #[tokio::test]
+    async fn can_get_balance_at_head() {
+        let server = MockServer::start(vec![
+            MockResponse::json(r#"{"jsonrpc":"2.0","id":83,"result":"0x10d4f"}"#),
+            MockResponse::json(
+                r#"{"status":"1","message":"OK","result":"40891626854930000000000"}"#,
+            ),
+        ])
+        .await;
+
+        let address = Address::repeat_byte(1);
+        let (balance, block) = server.client().get_ether_balance_at_head(&address).await.unwrap();
+        assert_eq!(block, 68943);
+        assert_eq!(balance.balance, "40891626854930000000000");
+        let requests = server.requests();
+        assert_eq!(query_param(&requests[0], "action"), Some("eth_blockNumber"));
+        assert_eq!(query_param(&requests[1], "tag"), Some("latest"));
+    }
+
+    #[tokio::test]
+    async fn can_stream_transaction_pages() {
+        use futures_util::TryStreamExt;
+
+        let server = MockServer::start(vec![
+            list_response(vec![hashed_tx_json(1), hashed_tx_json(2)]),
+            list_response(vec![hashed_tx_json(2), hashed_tx_json(3)]),
+            MockResponse::json(empty_list()),
+        ])
+        .await;
+
+        let params = TxListParams::default().page_size(2);
+        let pages: Vec<_> = server
+            .client()
+            .get_transactions_pages(&Address::repeat_byte(1), Some(params))
+            .try_collect()
+            .await
+            .unwrap();
+        let blocks: Vec<Vec<_>> = pages
+            .iter()
+            .map(|page| page.iter().map(|tx| tx.block_number.as_number().unwrap()).collect())
+            .collect();
+        assert_eq!(blocks, [vec![1.into(), 2.into()], vec![3.into()]]);
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[test]
+    fn can_collapse_genesis_options() {
+        let tx = fixtures::normal_transaction();
+        assert!(!tx.is_genesis());
+        assert!(tx.hash().is_some() && tx.from_address().is_some());
+
+        let genesis = fixtures::genesis_transaction();
+        assert!(genesis.is_genesis());
+        assert_eq!((genesis.hash(), genesis.from_address()), (None, None));
+
+        let mut internal = fixtures::internal_transaction();
+        assert!(!internal.is_genesis());
+        internal.to = GenesisOption::Genesis;
+        internal.contract_address = GenesisOption::Some(Address::repeat_byte(1));
+        assert!(internal.is_genesis());
+        assert_eq!(internal.to_address(), None);
+        assert_eq!(internal.contract_address(), Some(Address::repeat_byte(1)));
+    }
+
+    #[test]
+    fn can_parse_call_type() {
+        let mut tx = fixtures::internal_transaction();
+        for (kind, expected) in [
+            ("call", CallType::Call),
+            ("delegatecall", CallType::DelegateCall),
+            ("CREATE2", CallType::Create2),
+            ("suicide", CallType::SelfDestruct),
+            ("reward", CallType::Other("reward".to_string())),
+        ] {
+            tx.result_type = kind.to_string();
+            assert_eq!(tx.call_type(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn can_stream_balances() {
+        let balances = |addresses: std::ops::RangeInclusive<u8>| {
+            let result: Vec<_> = addresses
+                .rev()
+                .map(|byte| {
+                    serde_json::json!({
+                        "account": format!("{:?}", Address::repeat_byte(byte)),
+                        "balance": byte.to_string(),
+                    })
+                })
+                .collect();
+            list_response(result)
+        };
+        let server =
+            MockServer::start(vec![balances(1..=20), balances(21..=40), balances(41..=45)]).await;
+
+        let addresses: Vec<_> = (1..=45).map(Address::repeat_byte).collect();
+        let streamed: Vec<_> = server
+            .client()
+            .get_ether_balance_stream(addresses.clone(), None)
+            .try_collect()
+            .await
+            .unwrap();
+        let accounts: Vec<_> = streamed.iter().map(|balance| balance.account).collect();
+        assert_eq!(accounts, addresses);
+        assert!(streamed
+            .iter()
+            .zip(1..)
+            .all(|(balance, byte)| balance.balance == byte.to_string()));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        let batch = query_param(&requests[2], "address").unwrap();
+        assert_eq!(batch.matches("0x").count(), 5);
+    }
+
+    #[tokio::test]
+    async fn dropped_stream_stops_paginating() {
+        let delayed = MockResponse {
+            delay: std::time::Duration::from_millis(200),
+            ..list_response(vec![hashed_tx_json(4), hashed_tx_json(5)])
+        };
+        let server = MockServer::start(vec![
+            list_response(vec![hashed_tx_json(1), hashed_tx_json(2)]),
+            list_response(vec![hashed_tx_json(2), hashed_tx_json(3)]),
+            delayed,
+            list_response(vec![hashed_tx_json(5), hashed_tx_json(6)]),
+        ])
+        .await;
+
+        let params = TxListParams::default().page_size(2);
+        let mut txs = Box::pin(
+            server.client().get_transactions_stream(&Address::repeat_byte(1), Some(params)),
+        );
+        for _ in 0..3 {
+            txs.try_next().await.unwrap().unwrap();
+        }
+        assert_eq!(server.requests().len(), 2);
+
+        // cancel the request for the third page while the server holds its response
+        let next = tokio::time::timeout(std::time::Duration::from_millis(50), txs.try_next()).await;
+        assert!(next.is_err());
+        drop(txs);
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn can_filter_by_min_confirmations() {
+        let tx = |block, confirmations: u64| {
+            let mut tx = hashed_tx_json(block);
+            tx["confirmations"] = confirmations.to_string().into();
+            tx
+        };
+        let server =
+            MockServer::start(vec![list_response(vec![tx(1, 13), tx(2, 12), tx(3, 11)])]).await;
+
+        let params = TxListParams::default().min_confirmations(12);
+        let txs = server.client().get_transactions(&Address::repeat_byte(1), Some(params)).await;
+        let blocks: Vec<_> =
+            txs.unwrap().into_iter().map(|tx| tx.block_number.as_number().unwrap()).collect();
+        assert_eq!(blocks, [1.into(), 2.into()]);
+    }
+
+    #[tokio::test]
+    async fn can_filter_by_min_value() {
+        use futures_util::TryStreamExt;
+
+        let tx = |block, value: u64| {
+            let mut tx = hashed_tx_json(block);
+            tx["value"] = value.to_string().into();
+            tx
+        };
+        let server = MockServer::start(vec![
+            list_response(vec![tx(1, 0), tx(2, 7)]),
+            // the stream pages through the unfiltered transactions
+            list_response(vec![tx(1, 0), tx(2, 0)]),
+            list_response(vec![tx(2, 0), tx(3, 7)]),
+            MockResponse::json(empty_list()),
+        ])
+        .await;
+        let blocks = |txs: Vec<NormalTransaction>| {
+            txs.into_iter().map(|tx| tx.block_number.as_number().unwrap()).collect::<Vec<_>>()
+        };
+
+        let address = Address::repeat_byte(1);
+        let params = TxListParams::default().min_value(1.into());
+        let txs = server.client().get_transactions(&address, Some(params)).await.unwrap();
+        assert_eq!(blocks(txs), [2.into()]);
+
+        let txs: Vec<_> = server
+            .client()
+            .get_transactions_stream(&address, Some(params.page_size(2)))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(blocks(txs), [3.into()]);
+        assert_eq!(query_param(&server.requests()[2], "startBlock"), Some("2"));
+    }
+
+    #[test]
+    fn can_format_address() {
+        let address = "0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap();
+        let builder = Client::builder().with_api_key("API_KEY").chain(Chain::Mainnet).unwrap();
+        let client = builder.clone().build().unwrap();
+        assert_eq!(client.format_address(&address), "0x58eb28a67731c570ef827c365c89b5751f9e6b0a");
+        assert_eq!(
+            client.format_address(&Address::zero()),
+            "0x0000000000000000000000000000000000000000"
+        );
+        let client = builder.with_checksummed_addresses(true).build().unwrap();
+        assert_eq!(client.format_address(&address), "0x58eB28A67731c570Ef827C365c89B5751F9E6b0a");
+    }
+
+    #[test]
+    fn can_validate_token_query() {
+        let contract = Address::repeat_byte(1);
+        let by_contract = TokenQueryOption::ByContract(contract);
+        let err = by_contract.validate(&TxListParams::default()).unwrap_err();
+        assert!(matches!(err, EtherscanError::UnboundedTokenQuery(c) if c == contract));
+        let latest = TxListParams::default()
+            .start_block(BlockNumber::Earliest)
+            .end_block(BlockNumber::Latest);
+        assert!(by_contract.validate(&latest).is_err());
+
+        assert!(by_contract.validate(&TxListParams::default().start_block(17_000_000u64)).is_ok());
+        assert!(by_contract.validate(&TxListParams::new(0, 1000, 1, 100, Sort::Asc)).is_ok());
+        let by_address = TokenQueryOption::ByAddressAndContract(Address::zero(), contract);
+        assert!(by_address.validate(&TxListParams::default()).is_ok());
+        assert!(TokenQueryOption::ByAddress(contract).validate(&latest).is_ok());
+
+        let by_range = TokenQueryOption::ByContractAndRange(contract, 0, 1000);
+        assert!(by_range.validate(&TxListParams::default()).is_ok());
+        let full_range = TokenQueryOption::ByContractAndRange(contract, 0, UNBOUNDED_END_BLOCK);
+        assert!(full_range.validate(&TxListParams::default().start_block(17_000_000u64)).is_err());
+    }
+
+    #[test]
+    fn range_in_token_query_overrides_params() {
+        let address = Address::repeat_byte(1);
+        let list_params = TxListParams::new(10, 20, 2, 50, Sort::Desc);
+        let params =
+            TokenQueryOption::ByAddressAndRange(address, 100, 200).into_params(list_params);
+        assert_eq!(params["address"], "0x0101010101010101010101010101010101010101");
+        assert_eq!((params["startBlock"].as_str(), params["endBlock"].as_str()), ("100", "200"));
+        assert_eq!((params["page"].as_str(), params["sort"].as_str()), ("2", "desc"));
+
+        let params = TokenQueryOption::ByContractAndRange(address, 100, 200)
+            .into_params(TxListParams::default());
+        assert_eq!(params["contractaddress"], "0x0101010101010101010101010101010101010101");
+        assert!(!params.contains_key("address"));
+        assert_eq!((params["startBlock"].as_str(), params["endBlock"].as_str()), ("100", "200"));
+    }
+
+    #[test]
+    fn can_set_block_range() {
+        let params: HashMap<_, _> = TxListParams::new(10, 20, 1, 50, Sort::Asc).into();
+        assert_eq!(params["startBlock"], "10");
+        assert_eq!(params["endBlock"], "20");
+
+        let params: HashMap<_, _> = TxListParams::default()
+            .start_block(BlockNumber::Earliest)
+            .end_block(BlockNumber::Latest)
+            .into();
+        assert_eq!(params["startBlock"], "0");
+        assert_eq!(params["endBlock"], "latest");
+
+        let params: HashMap<_, _> = TxListParams::default().start_block(15_000_000u64).into();
+        assert_eq!(params["startBlock"], "15000000");
+        // the default range isn't capped below the head of chains past block 99999999
+        assert_eq!(params["endBlock"], "latest");
     }
 }
@@ -0,0 +1,318 @@
+//! Aggregations over fetched transactions
+
+use crate::account::{ERC20TokenTransferEvent, NormalTransaction};
+use ethers_core::types::{Address, U256};
+use std::collections::{HashMap, HashSet};
+
+#[cfg(feature = "chrono")]
+pub use self::periods::*;
+
+/// Tallies the token amounts `me` received from and sent to each counterparty, as `(in, out)`.
+///
+/// Amounts of different tokens are added up as they are, so filter the events by
+/// `contract_address` first. Events not involving `me` and self-transfers are skipped.
+pub fn group_transfers_by_counterparty(
+    events: &[ERC20TokenTransferEvent],
+    me: &Address,
+) -> HashMap<Address, (U256, U256)> {
+    let mut counterparties: HashMap<Address, (U256, U256)> = HashMap::new();
+    for event in events {
+        let to = event.to.as_ref();
+        if event.from == *me && to == Some(me) {
+            continue
+        }
+        if to == Some(me) {
+            let tally = counterparties.entry(event.from).or_default();
+            tally.0 = tally.0.saturating_add(event.value);
+        } else if let Some(to) = to.filter(|_| event.from == *me) {
+            let tally = counterparties.entry(*to).or_default();
+            tally.1 = tally.1.saturating_add(event.value);
+        }
+    }
+    counterparties
+}
+
+/// The transactions of an address with aggregates over them, see
+/// [Client::get_transactions_summary](crate::Client::get_transactions_summary)
+#[derive(Clone, Debug, Default)]
+pub struct TxSummary {
+    pub transactions: Vec<NormalTransaction>,
+    /// The number of failed transactions, which are counted but whose value isn't added up
+    pub failed: usize,
+    /// The value received, in wei
+    pub value_in: U256,
+    /// The value sent, in wei
+    pub value_out: U256,
+    /// The fees paid for the transactions sent, including failed ones, in wei
+    pub gas_spent: U256,
+    /// The earliest timestamp, in seconds
+    pub first_timestamp: Option<u64>,
+    /// The latest timestamp, in seconds
+    pub last_timestamp: Option<u64>,
+    /// The addresses that sent transactions to or received them from the address
+    pub counterparties: HashSet<Address>,
+}
+
+impl TxSummary {
+    /// Summarizes the transactions of `me`.
+    pub fn from_transactions(
+        me: &Address,
+        txs: impl IntoIterator<Item = NormalTransaction>,
+    ) -> Self {
+        let mut summary = Self::default();
+        for tx in txs {
+            summary.push(me, tx);
+        }
+        summary
+    }
+
+    /// Adds a transaction of `me` to the list and the aggregates.
+    ///
+    /// Transactions of an address to itself count as both received and sent.
+    pub fn push(&mut self, me: &Address, tx: NormalTransaction) {
+        let outgoing = tx.from.value() == Some(me);
+        let incoming = tx.to.as_ref() == Some(me);
+        if tx.is_error == "1" {
+            self.failed += 1;
+        } else {
+            if incoming {
+                self.value_in = self.value_in.saturating_add(tx.value);
+            }
+            if outgoing {
+                self.value_out = self.value_out.saturating_add(tx.value);
+            }
+        }
+        if outgoing {
+            if let Some(gas_price) = tx.gas_price {
+                self.gas_spent =
+                    self.gas_spent.saturating_add(tx.gas_used.saturating_mul(gas_price));
+            }
+        }
+        let counterparty = if outgoing { tx.to } else { tx.from.value().copied() };
+        if let Some(counterparty) = counterparty.filter(|counterparty| counterparty != me) {
+            self.counterparties.insert(counterparty);
+        }
+        if let Ok(timestamp) = tx.time_stamp.parse::<u64>() {
+            self.first_timestamp =
+                Some(self.first_timestamp.map_or(timestamp, |t| t.min(timestamp)));
+            self.last_timestamp = Some(self.last_timestamp.map_or(timestamp, |t| t.max(timestamp)));
+        }
+        self.transactions.push(tx);
+    }
+
+    /// Returns the number of transactions, including failed ones.
+    pub fn count(&self) -> usize {
+        self.transactions.len()
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod periods {
+    use crate::account::{ERC20TokenTransferEvent, NormalTransaction};
+    use chrono::{DateTime, Datelike, Days, NaiveDate};
+    use ethers_core::types::U256;
+    use std::collections::BTreeMap;
+
+    /// The length of the buckets of [aggregate_by_period]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Period {
+        Day,
+        /// An ISO week, starting on Monday
+        Week,
+        Month,
+    }
+
+    impl Period {
+        /// Returns the first day of the period containing `date`.
+        pub fn start_of(self, date: NaiveDate) -> NaiveDate {
+            match self {
+                Period::Day => date,
+                Period::Week => date - Days::new(date.weekday().num_days_from_monday().into()),
+                Period::Month => date.with_day(1).expect("every month has a first day"),
+            }
+        }
+    }
+
+    /// The transactions of one period
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct PeriodBucket {
+        /// The first day of the period, in UTC
+        pub start: NaiveDate,
+        pub count: usize,
+        /// The sum of the transferred values, in wei
+        pub total_value: U256,
+    }
+
+    /// Buckets transactions by the UTC period of their timestamp, ordered by period.
+    ///
+    /// Periods without transactions are omitted. All transactions are counted, including failed
+    /// ones, so filter by `is_error` first to only sum transferred values. Transactions with an
+    /// unparsable timestamp are skipped.
+    pub fn aggregate_by_period(txs: &[NormalTransaction], period: Period) -> Vec<PeriodBucket> {
+        let mut buckets = BTreeMap::new();
+        for tx in txs {
+            let date = match utc_date(&tx.time_stamp) {
+                Some(date) => date,
+                None => continue,
+            };
+            let bucket = buckets.entry(period.start_of(date)).or_insert((0, U256::zero()));
+            bucket.0 += 1;
+            bucket.1 = bucket.1.saturating_add(tx.value);
+        }
+        buckets
+            .into_iter()
+            .map(|(start, (count, total_value))| PeriodBucket { start, count, total_value })
+            .collect()
+    }
+
+    /// The ERC-20 transfers of one day, see
+    /// [Client::daily_token_activity](crate::Client::daily_token_activity)
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct DailyTokenActivity {
+        /// The day, in UTC
+        pub date: NaiveDate,
+        pub count: usize,
+        /// The sum of the transferred amounts, in the token's smallest unit
+        pub volume: U256,
+    }
+
+    /// Buckets token transfers by the UTC day of their timestamp, ordered by day.
+    ///
+    /// Days without transfers are omitted and transfers with an unparsable timestamp are
+    /// skipped. Amounts of different tokens are added up as they are, so filter the events by
+    /// `contract_address` first.
+    pub fn aggregate_transfers_by_day(
+        events: &[ERC20TokenTransferEvent],
+    ) -> Vec<DailyTokenActivity> {
+        let mut days = BTreeMap::new();
+        for event in events {
+            let date = match utc_date(&event.time_stamp) {
+                Some(date) => date,
+                None => continue,
+            };
+            let day = days.entry(date).or_insert((0, U256::zero()));
+            day.0 += 1;
+            day.1 = day.1.saturating_add(event.value);
+        }
+        days.into_iter()
+            .map(|(date, (count, volume))| DailyTokenActivity { date, count, volume })
+            .collect()
+    }
+
+    /// Parses a timestamp in seconds into its UTC date.
+    pub(crate) fn utc_date(time_stamp: &str) -> Option<NaiveDate> {
+        let time = DateTime::from_timestamp(time_stamp.parse().ok()?, 0)?;
+        Some(time.date_naive())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn tx_at(time_stamp: &str, value: u64) -> NormalTransaction {
+            let mut tx = crate::fixtures::normal_transaction();
+            tx.time_stamp = time_stamp.to_string();
+            tx.value = value.into();
+            tx
+        }
+
+        fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+            NaiveDate::from_ymd_opt(year, month, day).unwrap()
+        }
+
+        #[test]
+        fn can_aggregate_by_period() {
+            let txs = [
+                // Sunday 2022-01-30 23:59:59 UTC
+                tx_at("1643587199", 1),
+                // Monday 2022-01-31 00:00:00 UTC
+                tx_at("1643587200", 2),
+                // Tuesday 2022-02-01 12:00:00 UTC
+                tx_at("1643716800", 4),
+                tx_at("not a timestamp", 8),
+            ];
+            let buckets = |period| {
+                aggregate_by_period(&txs, period)
+                    .into_iter()
+                    .map(|bucket| (bucket.start, bucket.count, bucket.total_value.as_u64()))
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(
+                buckets(Period::Day),
+                [(date(2022, 1, 30), 1, 1), (date(2022, 1, 31), 1, 2), (date(2022, 2, 1), 1, 4)]
+            );
+            assert_eq!(
+                buckets(Period::Week),
+                [(date(2022, 1, 24), 1, 1), (date(2022, 1, 31), 2, 6)]
+            );
+            assert_eq!(
+                buckets(Period::Month),
+                [(date(2022, 1, 1), 2, 3), (date(2022, 2, 1), 1, 4)]
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_summarize_transactions() {
+        let me = Address::repeat_byte(1);
+        let tx = |time_stamp: &str, from: Address, to: Address, value: u64| {
+            let mut tx = crate::fixtures::normal_transaction();
+            tx.time_stamp = time_stamp.to_string();
+            tx.from = crate::account::GenesisOption::Some(from);
+            tx.to = Some(to);
+            tx.value = value.into();
+            tx.gas_used = 10.into();
+            tx.gas_price = Some(2.into());
+            tx
+        };
+        let mut failed = tx("300", me, Address::repeat_byte(3), 1000);
+        failed.is_error = "1".to_string();
+        let txs = vec![
+            tx("200", Address::repeat_byte(2), me, 50),
+            tx("100", me, Address::repeat_byte(2), 20),
+            failed,
+            tx("150", me, me, 5),
+        ];
+
+        let summary = TxSummary::from_transactions(&me, txs);
+        assert_eq!((summary.count(), summary.failed), (4, 1));
+        assert_eq!((summary.value_in, summary.value_out), (55.into(), 25.into()));
+        assert_eq!(summary.gas_spent, U256::from(3 * 20));
+        assert_eq!((summary.first_timestamp, summary.last_timestamp), (Some(100), Some(300)));
+        let counterparties = HashSet::from([Address::repeat_byte(2), Address::repeat_byte(3)]);
+        assert_eq!(summary.counterparties, counterparties);
+    }
+
+    fn transfer(from: Address, to: Address, value: u64) -> ERC20TokenTransferEvent {
+        let mut transfer = crate::fixtures::erc20_transfer();
+        transfer.from = from;
+        transfer.to = Some(to);
+        transfer.value = value.into();
+        transfer
+    }
+
+    #[test]
+    fn can_group_transfers_by_counterparty() {
+        let (me, a, b) =
+            (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3));
+        let events = [
+            transfer(a, me, 1),
+            transfer(me, a, 2),
+            transfer(a, me, 4),
+            transfer(me, b, 8),
+            transfer(me, me, 16),
+            transfer(a, b, 32),
+        ];
+
+        let counterparties = group_transfers_by_counterparty(&events, &me);
+        assert_eq!(counterparties.len(), 2);
+        assert_eq!(counterparties[&a], (5.into(), 2.into()));
+        assert_eq!(counterparties[&b], (0.into(), 8.into()));
+    }
+}
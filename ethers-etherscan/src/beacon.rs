@@ -0,0 +1,316 @@
+use crate::{
+    logs::LogQueryParams,
+    utils::{deserialize_numeric_or_zero, deserialize_u64_or_zero},
+    Client, EtherscanError, Response, Result,
+};
+use ethers_core::{
+    abi::{self, Address, ParamType, Token},
+    types::{Bytes, Chain, Log, H256, U256, U64},
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Address of the beacon chain deposit contract on mainnet
+pub const MAINNET_DEPOSIT_CONTRACT: &str = "0x00000000219ab540356cBB839Cbe05303d7705Fa";
+
+/// Address of the beacon chain deposit contract on goerli
+pub const GOERLI_DEPOSIT_CONTRACT: &str = "0xff50ed3d0ec03aC01D4C79aAd74928BFF48a7b2b";
+
+/// Address of the beacon chain deposit contract on sepolia
+pub const SEPOLIA_DEPOSIT_CONTRACT: &str = "0x7f02C3E3c98b133055B8B348B2Ac625669Ed295D";
+
+/// Topic of `DepositEvent(bytes,bytes,bytes,bytes,bytes)`
+pub const DEPOSIT_EVENT_TOPIC: &str =
+    "0x649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5";
+
+/// Parameters of `DepositEvent`: pubkey, withdrawal credentials, amount, signature and index
+const DEPOSIT_EVENT_PARAMS: [ParamType; 5] =
+    [ParamType::Bytes, ParamType::Bytes, ParamType::Bytes, ParamType::Bytes, ParamType::Bytes];
+
+/// Gwei to wei
+const GWEI: u64 = 1_000_000_000;
+
+/// A deposit into the beacon chain deposit contract
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeaconDeposit {
+    /// BLS public key of the validator
+    pub pubkey: Bytes,
+    /// Withdrawal credentials of the validator
+    pub withdrawal_credentials: H256,
+    /// Deposited amount in wei
+    pub amount: U256,
+    /// BLS signature of the deposit message
+    pub signature: Bytes,
+    /// Index of the deposit in the deposit contract
+    pub index: u64,
+    pub block_number: Option<U64>,
+    pub transaction_hash: Option<H256>,
+}
+
+impl BeaconDeposit {
+    /// Returns the withdrawal address if the credentials are of the execution layer type (`0x01`
+    /// or `0x02`)
+    pub fn withdrawal_address(&self) -> Option<Address> {
+        let credentials = self.withdrawal_credentials.as_bytes();
+        let is_execution = matches!(credentials[0], 0x01 | 0x02);
+        (is_execution && credentials[1..12].iter().all(|b| *b == 0))
+            .then(|| Address::from_slice(&credentials[12..]))
+    }
+}
+
+impl TryFrom<&Log> for BeaconDeposit {
+    type Error = EtherscanError;
+
+    fn try_from(log: &Log) -> Result<Self> {
+        let mut tokens =
+            abi::decode(&DEPOSIT_EVENT_PARAMS, &log.data)?.into_iter().map(Token::into_bytes);
+        let mut next = || tokens.next().flatten().unwrap_or_default();
+        let (pubkey, credentials, amount, signature, index) =
+            (next(), next(), next(), next(), next());
+
+        if credentials.len() != 32 {
+            return Err(abi::Error::InvalidData.into())
+        }
+
+        Ok(Self {
+            pubkey: pubkey.into(),
+            withdrawal_credentials: H256::from_slice(&credentials),
+            amount: U256::from(le_u64(&amount)?) * GWEI,
+            signature: signature.into(),
+            index: le_u64(&index)?,
+            block_number: log.block_number,
+            transaction_hash: log.transaction_hash,
+        })
+    }
+}
+
+/// The balance of a beacon chain validator, in gwei
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ValidatorBalance {
+    #[serde(rename = "validatorindex", deserialize_with = "deserialize_u64_or_zero")]
+    pub validator_index: u64,
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
+    pub balance: U256,
+    #[serde(rename = "effectivebalance", deserialize_with = "deserialize_numeric_or_zero")]
+    pub effective_balance: U256,
+}
+
+/// Decodes the little endian integers emitted by the deposit contract
+fn le_u64(bytes: &[u8]) -> Result<u64> {
+    let bytes: [u8; 8] = bytes.try_into().map_err(|_| abi::Error::InvalidData)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+impl Client {
+    /// Returns the beacon chain deposits whose withdrawal credentials point to `address`
+    ///
+    /// The deposit contract does not record the sender, so deposits are attributed by their
+    /// execution layer withdrawal credentials. Deposits with BLS (`0x00`) credentials are never
+    /// returned.
+    ///
+    /// `address` and `topics` of `params` are overwritten and the block range is passed through
+    /// to [`Client::get_all_logs`], so `page` and `offset` are ignored. The deposits of the whole
+    /// range are decoded and filtered locally.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, logs::LogQueryParams};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let params = LogQueryParams {
+    ///         from_block: Some(17000000.into()),
+    ///         to_block: Some(17001000.into()),
+    ///         ..Default::default()
+    ///     };
+    ///     let deposits = client
+    ///         .get_beacon_deposits(
+    ///             &"0x58E8dCC13BE9780fC42E8723D8EaD4CF46943dF2".parse().unwrap(),
+    ///             params,
+    ///         )
+    ///         .await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_beacon_deposits(
+        &self,
+        address: &Address,
+        params: LogQueryParams,
+    ) -> Result<Vec<BeaconDeposit>> {
+        let contract = match self.chain {
+            None | Some(Chain::Mainnet) => MAINNET_DEPOSIT_CONTRACT,
+            Some(Chain::Goerli) => GOERLI_DEPOSIT_CONTRACT,
+            Some(Chain::Sepolia) => SEPOLIA_DEPOSIT_CONTRACT,
+            Some(chain) => return Err(EtherscanError::ChainNotSupported(chain)),
+        };
+        let params = LogQueryParams {
+            address: Some(contract.parse().expect("valid address")),
+            topics: [Some(DEPOSIT_EVENT_TOPIC.parse().expect("valid topic")), None, None, None],
+            topic_operators: Vec::new(),
+            ..params
+        };
+
+        let mut deposits = Vec::new();
+        for log in self.get_all_logs(params).await? {
+            let deposit = BeaconDeposit::try_from(&log)?;
+            if deposit.withdrawal_address() == Some(*address) {
+                deposits.push(deposit);
+            }
+        }
+        Ok(deposits)
+    }
+
+    /// Returns the effective balance of a beacon chain validator in wei
+    ///
+    /// Fails with [EtherscanError::ValidatorBalanceOverflow] if the balance in gwei is too large
+    /// to convert.
+    ///
+    /// The request is sent as the `validatorbalance` action of the `beacon` module. Etherscan
+    /// itself does not serve validator balances yet and responds with an error; use the
+    /// `/eth/v1/beacon/states/head/validators/{index}` endpoint of a beacon node instead.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::builder()
+    ///         .with_api_url("https://explorer.example/api").unwrap()
+    ///         .with_url("https://explorer.example").unwrap()
+    ///         .build().unwrap();
+    ///     let balance = client.get_validator_balance(1).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_validator_balance(&self, validator_index: u64) -> Result<U256> {
+        let query = self.create_query(
+            "beacon",
+            "validatorbalance",
+            HashMap::from([("validatorindex", validator_index.to_string())]),
+        );
+        let response: Response<ValidatorBalance> = self.get_json(&query).await?;
+
+        let effective_balance = response.result.effective_balance;
+        effective_balance
+            .checked_mul(GWEI.into())
+            .ok_or(EtherscanError::ValidatorBalanceOverflow(effective_balance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{query_param, MockResponse, MockServer};
+    use ethers_core::utils::keccak256;
+
+    fn deposit_log(credentials: H256) -> Log {
+        let data = abi::encode(&[
+            Token::Bytes(vec![0xaa; 48]),
+            Token::Bytes(credentials.as_bytes().to_vec()),
+            Token::Bytes(32_000_000_000u64.to_le_bytes().to_vec()),
+            Token::Bytes(vec![0xbb; 96]),
+            Token::Bytes(42u64.to_le_bytes().to_vec()),
+        ]);
+        Log { data: data.into(), block_number: Some(1.into()), ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn can_get_validator_balance() {
+        let server = MockServer::start(vec![MockResponse::json(
+            r#"{"status":"1","message":"OK","result":{"validatorindex":"7","balance":"32001234567","effectivebalance":"32000000000"}}"#,
+        )])
+        .await;
+
+        let balance = server.client().get_validator_balance(7).await.unwrap();
+        assert_eq!(balance, U256::exp10(18) * 32);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].contains("module=beacon&action=validatorbalance&validatorindex=7"));
+    }
+
+    #[tokio::test]
+    async fn fails_on_validator_balance_overflow() {
+        let server = MockServer::start(vec![MockResponse::json(format!(
+            r#"{{"status":"1","message":"OK","result":{{"validatorindex":"7","balance":"0","effectivebalance":"{}"}}}}"#,
+            U256::MAX
+        ))])
+        .await;
+
+        let err = server.client().get_validator_balance(7).await.unwrap_err();
+        assert!(
+            matches!(err, EtherscanError::ValidatorBalanceOverflow(balance) if balance == U256::MAX)
+        );
+    }
+
+    #[tokio::test]
+    async fn can_get_beacon_deposits() {
+        let address = Address::repeat_byte(0x11);
+        let credentials = |address: Address| {
+            let mut credentials = [0u8; 32];
+            credentials[0] = 0x01;
+            credentials[12..].copy_from_slice(address.as_bytes());
+            H256(credentials)
+        };
+        let logs: Vec<_> = [credentials(address), credentials(Address::repeat_byte(0x22))]
+            .into_iter()
+            .enumerate()
+            .map(|(index, credentials)| {
+                serde_json::json!({
+                    "address": MAINNET_DEPOSIT_CONTRACT,
+                    "topics": [DEPOSIT_EVENT_TOPIC],
+                    "data": deposit_log(credentials).data,
+                    "blockNumber": "0x10",
+                    "logIndex": format!("{index:#x}"),
+                    "transactionHash": format!("{:?}", H256::repeat_byte(index as u8)),
+                    "transactionIndex": "0x0"
+                })
+            })
+            .collect();
+        let server = MockServer::start(vec![MockResponse::json(
+            serde_json::json!({"status": "1", "message": "OK", "result": logs}).to_string(),
+        )])
+        .await;
+
+        let params = LogQueryParams {
+            from_block: Some(16.into()),
+            to_block: Some(16.into()),
+            page: Some(2),
+            offset: Some(1),
+            ..Default::default()
+        };
+        let deposits = server.client().get_beacon_deposits(&address, params).await.unwrap();
+        assert_eq!(deposits.len(), 1);
+        assert_eq!(deposits[0].withdrawal_address(), Some(address));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(query_param(&requests[0], "page"), None);
+        assert_eq!(query_param(&requests[0], "offset"), None);
+    }
+
+    #[test]
+    fn deposit_topic_matches_event() {
+        let topic: H256 = DEPOSIT_EVENT_TOPIC.parse().unwrap();
+        assert_eq!(topic.as_bytes(), keccak256("DepositEvent(bytes,bytes,bytes,bytes,bytes)"));
+    }
+
+    #[test]
+    fn can_decode_deposit() {
+        let address = Address::repeat_byte(0x11);
+        let mut credentials = [0u8; 32];
+        credentials[0] = 0x01;
+        credentials[12..].copy_from_slice(address.as_bytes());
+
+        let deposit = BeaconDeposit::try_from(&deposit_log(credentials.into())).unwrap();
+        assert_eq!(deposit.pubkey.len(), 48);
+        assert_eq!(deposit.amount, U256::exp10(18) * 32);
+        assert_eq!(deposit.index, 42);
+        assert_eq!(deposit.block_number, Some(1.into()));
+        assert_eq!(deposit.withdrawal_address(), Some(address));
+
+        credentials[0] = 0x00;
+        let deposit = BeaconDeposit::try_from(&deposit_log(credentials.into())).unwrap();
+        assert_eq!(deposit.withdrawal_address(), None);
+    }
+}
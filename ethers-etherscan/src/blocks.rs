@@ -0,0 +1,108 @@
+use crate::{check_string_ok, Client, EtherscanError, Response, Result};
+use std::{collections::HashMap, fmt};
+
+/// Which block [Client::get_block_number_by_timestamp] returns for a timestamp between two blocks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Closest {
+    /// The last block mined at or before the timestamp
+    Before,
+    /// The first block mined at or after the timestamp
+    After,
+}
+
+impl fmt::Display for Closest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Closest::Before => write!(f, "before"),
+            Closest::After => write!(f, "after"),
+        }
+    }
+}
+
+impl Client {
+    /// Returns the number of the block mined closest to a unix timestamp in seconds, using the
+    /// `getblocknobytime` endpoint.
+    ///
+    /// Fails if there is no such block, e.g. for a timestamp after the head with
+    /// [Closest::After].
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, blocks::Closest};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let block = client.get_block_number_by_timestamp(1578638524, Closest::Before).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_block_number_by_timestamp(
+        &self,
+        timestamp: u64,
+        closest: Closest,
+    ) -> Result<u64> {
+        let query = self.create_query(
+            "block",
+            "getblocknobytime",
+            HashMap::from([("timestamp", timestamp.to_string()), ("closest", closest.to_string())]),
+        );
+        let response: Response<String> = self.get_json(&query).await?;
+
+        let block = check_string_ok(&response.status, &response.message, response.result)?;
+        block.parse().map_err(|_| EtherscanError::Unknown(format!("invalid block number {block}")))
+    }
+
+    /// Returns the first and last block mined between two unix timestamps in seconds, both
+    /// inclusive, or `None` if no block was.
+    ///
+    /// Each timestamp is resolved once with [Client::get_block_number_by_timestamp]: the start
+    /// to the first block at or after it and the end to the last block at or before it.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [EtherscanError::InvalidParams] if `start_time` is after `end_time`.
+    pub async fn get_block_range_by_time(
+        &self,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<Option<(u64, u64)>> {
+        if start_time > end_time {
+            return Err(EtherscanError::InvalidParams(format!(
+                "start time {start_time} is after end time {end_time}"
+            )))
+        }
+        let start = self.get_block_number_by_timestamp(start_time, Closest::After).await?;
+        let end = self.get_block_number_by_timestamp(end_time, Closest::Before).await?;
+        Ok((start <= end).then_some((start, end)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{query_param, MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn can_get_block_number_by_timestamp() {
+        let server = MockServer::start(vec![MockResponse::json(
+            r#"{"status":"1","message":"OK","result":"9251482"}"#,
+        )])
+        .await;
+
+        let block =
+            server.client().get_block_number_by_timestamp(1578638524, Closest::Before).await;
+        assert_eq!(block.unwrap(), 9251482);
+        let request = &server.requests()[0];
+        assert_eq!(query_param(request, "action"), Some("getblocknobytime"));
+        assert_eq!(query_param(request, "timestamp"), Some("1578638524"));
+        assert_eq!(query_param(request, "closest"), Some("before"));
+    }
+
+    #[tokio::test]
+    async fn rejects_reversed_time_range() {
+        let server = MockServer::start(vec![]).await;
+        let range = server.client().get_block_range_by_time(2000, 1000).await;
+        assert!(matches!(range, Err(EtherscanError::InvalidParams(_))));
+        assert!(server.requests().is_empty());
+    }
+}
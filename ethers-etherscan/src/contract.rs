@@ -1,15 +1,20 @@
 use crate::{
+    check_ok,
     source_tree::{SourceTree, SourceTreeEntry},
     utils::{deserialize_address_opt, deserialize_source_code},
-    Client, EtherscanError, Response, Result,
+    Client, EtherscanError, ProxyResponse, Response, Result,
 };
 use ethers_core::{
     abi::{Abi, Address, RawAbi},
-    types::{serde_helpers::deserialize_stringified_u64, Bytes},
+    types::{serde_helpers::deserialize_stringified_u64, Bytes, H256},
 };
+use futures_util::{stream, StreamExt};
 use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
 
 #[cfg(feature = "ethers-solc")]
 use ethers_solc::{artifacts::Settings, EvmVersion, Project, ProjectBuilder, SolcConfig};
@@ -102,6 +107,82 @@ impl SourceCodeMetadata {
     }
 }
 
+/// The license of a verified contract, as selected on Etherscan
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LicenseType {
+    /// Explicitly no license
+    NoLicense,
+    Unlicense,
+    Mit,
+    GplV2,
+    GplV3,
+    LgplV2_1,
+    LgplV3,
+    Bsd2Clause,
+    Bsd3Clause,
+    Mpl2,
+    Osl3,
+    Apache2,
+    AgplV3,
+    Bsl1_1,
+    /// A license this crate doesn't know about
+    Other(String),
+}
+
+impl LicenseType {
+    fn parse(license: &str) -> Option<Self> {
+        let license = match license.trim() {
+            "" => return None,
+            "None" | "No License" => Self::NoLicense,
+            "Unlicense" | "The Unlicense" => Self::Unlicense,
+            "MIT" => Self::Mit,
+            "GNU GPLv2" => Self::GplV2,
+            "GNU GPLv3" => Self::GplV3,
+            "GNU LGPLv2.1" => Self::LgplV2_1,
+            "GNU LGPLv3" => Self::LgplV3,
+            "BSD-2-Clause" => Self::Bsd2Clause,
+            "BSD-3-Clause" => Self::Bsd3Clause,
+            "MPL-2.0" => Self::Mpl2,
+            "OSL-3.0" => Self::Osl3,
+            "Apache-2.0" => Self::Apache2,
+            "GNU AGPLv3" => Self::AgplV3,
+            "BSL 1.1" | "BUSL-1.1" => Self::Bsl1_1,
+            other => Self::Other(other.to_string()),
+        };
+        Some(license)
+    }
+}
+
+/// Where the metadata file of a contract is published
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SourceLocation {
+    /// A swarm hash, from a `bzzr://` or `bzz-raw://` url
+    Swarm(String),
+    /// An IPFS CID, from an `ipfs://` url
+    Ipfs(String),
+    /// Any other url
+    Other(String),
+}
+
+impl SourceLocation {
+    fn parse(source: &str) -> Option<Self> {
+        let source = source.trim();
+        if source.is_empty() {
+            return None
+        }
+        let location = if let Some(hash) =
+            source.strip_prefix("bzzr://").or_else(|| source.strip_prefix("bzz-raw://"))
+        {
+            Self::Swarm(hash.to_string())
+        } else if let Some(cid) = source.strip_prefix("ipfs://") {
+            Self::Ipfs(cid.to_string())
+        } else {
+            Self::Other(source.to_string())
+        };
+        Some(location)
+    }
+}
+
 /// Etherscan contract metadata.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -206,6 +287,36 @@ impl Metadata {
         }
     }
 
+    /// Returns whether the optimizer was enabled.
+    pub fn optimization_enabled(&self) -> bool {
+        self.optimization_used == 1
+    }
+
+    /// Returns the number of optimizer runs, if the optimizer was enabled.
+    pub fn optimizer_runs(&self) -> Option<u32> {
+        self.optimization_enabled().then(|| u32::try_from(self.runs).unwrap_or(u32::MAX))
+    }
+
+    /// Returns the configured EVM version, or `None` if the compiler's default was used.
+    ///
+    /// See also `Metadata::evm_version` with the `ethers-solc` feature.
+    pub fn evm_version_name(&self) -> Option<&str> {
+        match self.evm_version.trim() {
+            "" | "Default" | "default" => None,
+            version => Some(version),
+        }
+    }
+
+    /// Returns the license of the contract, or `None` if it's not set.
+    pub fn license(&self) -> Option<LicenseType> {
+        LicenseType::parse(&self.license_type)
+    }
+
+    /// Returns where the metadata of the contract is published, or `None` if it's not set.
+    pub fn source_location(&self) -> Option<SourceLocation> {
+        SourceLocation::parse(&self.swarm_source)
+    }
+
     /// Returns whether this contract is a Vyper or a Solidity contract.
     pub fn is_vyper(&self) -> bool {
         self.compiler_version.starts_with("vyper:")
@@ -228,6 +339,77 @@ impl Metadata {
         SourceTree { entries: self.source_entries() }
     }
 
+    /// Writes the sources of this contract to `dir`, as a project that can be compiled with
+    /// Foundry.
+    ///
+    /// Multi-file sources are written at their paths, which are the source unit names the contract
+    /// was verified with, so the `remappings` of the compiler settings are written unchanged to
+    /// `remappings.txt`. A single-file source is written as `<ContractName>.sol`. `foundry.toml`
+    /// pins the compiler version, optimizer, EVM version and `via_ir` setting. Vyper contracts
+    /// only get their sources written.
+    ///
+    /// Paths are sanitized like in [SourceTree::write_to].
+    pub fn save_to(&self, dir: &Path) -> Result<()> {
+        let extension = if self.is_vyper() { "vy" } else { "sol" };
+        let entries = match &self.source_code {
+            SourceCodeMetadata::Metadata { sources, .. } => sources
+                .iter()
+                .map(|(path, entry)| SourceTreeEntry {
+                    path: path.into(),
+                    contents: entry.content.clone(),
+                })
+                .collect(),
+            SourceCodeMetadata::SourceCode(source) => vec![SourceTreeEntry {
+                path: Path::new(&self.contract_name).with_extension(extension),
+                contents: source.clone(),
+            }],
+        };
+        SourceTree { entries }.write_to(dir)?;
+        if self.is_vyper() {
+            return Ok(())
+        }
+
+        let settings = match &self.source_code {
+            SourceCodeMetadata::Metadata { settings: Some(settings), .. } => settings.clone(),
+            _ => serde_json::Value::Null,
+        };
+        let remappings: Vec<&str> = settings["remappings"]
+            .as_array()
+            .map(|remappings| remappings.iter().filter_map(|r| r.as_str()).collect())
+            .unwrap_or_default();
+        if !remappings.is_empty() {
+            std::fs::write(dir.join("remappings.txt"), remappings.join("\n") + "\n")?;
+        }
+        std::fs::write(dir.join("foundry.toml"), self.foundry_config(&settings)?)?;
+        Ok(())
+    }
+
+    /// Returns the contents of a `foundry.toml` for compiling this contract with `settings`, the
+    /// compiler settings of its standard JSON input.
+    fn foundry_config(&self, settings: &serde_json::Value) -> Result<String> {
+        let version = self.compiler_version()?;
+        let mut config = format!(
+            "[profile.default]\nsrc = \".\"\nout = \"out\"\nlibs = []\nsolc_version = \"{}.{}.{}\"\n",
+            version.major, version.minor, version.patch
+        );
+        let optimizer = &settings["optimizer"];
+        let (optimize, runs) = match optimizer["enabled"].as_bool() {
+            Some(enabled) => (enabled, optimizer["runs"].as_u64().unwrap_or(self.runs)),
+            None => (self.optimization_enabled(), self.runs),
+        };
+        config.push_str(&format!("optimizer = {optimize}\n"));
+        if optimize {
+            config.push_str(&format!("optimizer_runs = {runs}\n"));
+        }
+        if let Some(evm_version) = settings["evmVersion"].as_str().or(self.evm_version_name()) {
+            config.push_str(&format!("evm_version = \"{}\"\n", evm_version.to_lowercase()));
+        }
+        if settings["viaIR"].as_bool() == Some(true) {
+            config.push_str("via_ir = true\n");
+        }
+        Ok(config)
+    }
+
     /// Returns the contract's compiler settings.
     #[cfg(feature = "ethers-solc")]
     pub fn settings(&self) -> Result<Settings> {
@@ -304,11 +486,90 @@ impl ContractMetadata {
     pub fn source_tree(&self) -> SourceTree {
         SourceTree { entries: self.items.iter().flat_map(|item| item.source_entries()).collect() }
     }
+
+    /// Writes the sources of the contract to `dir` as a project, see [Metadata::save_to].
+    ///
+    /// If there are several contracts, each is written to a directory named after it.
+    pub fn save_to(&self, dir: &Path) -> Result<()> {
+        match self.items.as_slice() {
+            [item] => item.save_to(dir),
+            items => items.iter().try_for_each(|item| item.save_to(&dir.join(&item.contract_name))),
+        }
+    }
+}
+
+/// The deployment of a contract, see [Client::get_contract_creation]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractCreation {
+    pub contract_address: Address,
+    /// The sender of the deployment transaction, or the factory for contracts created by one
+    pub contract_creator: Address,
+    /// The hash of the deployment transaction
+    pub tx_hash: H256,
+}
+
+/// How a contract's bytecode compares to an expected ABI, see [Client::check_abi]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AbiMatch {
+    /// The signatures of the expected functions whose selectors aren't in the bytecode
+    pub missing: Vec<String>,
+}
+
+impl AbiMatch {
+    /// Returns whether all functions of the expected ABI were found.
+    pub fn is_match(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Returns the 4 byte values pushed onto the stack by EVM bytecode, which include the selectors
+/// of the functions a contract dispatches.
+///
+/// Push data is skipped while walking the opcodes, so bytes inside other constants don't count.
+/// Shorter pushes are padded to 4 bytes, since compilers drop the leading zeros of selectors
+/// like `0x00fdd58e`.
+pub fn bytecode_selectors(code: &[u8]) -> HashSet<[u8; 4]> {
+    const PUSH1: u8 = 0x60;
+    const PUSH4: u8 = 0x63;
+    const PUSH32: u8 = 0x7f;
+
+    let mut selectors = HashSet::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let op = code[pc];
+        pc += 1;
+        if !(PUSH1..=PUSH32).contains(&op) {
+            continue
+        }
+        let len = (op - PUSH1 + 1) as usize;
+        if op <= PUSH4 {
+            if let Some(data) = code.get(pc..pc + len) {
+                let mut selector = [0; 4];
+                selector[4 - len..].copy_from_slice(data);
+                selectors.insert(selector);
+            }
+        }
+        pc += len;
+    }
+    selectors
+}
+
+/// The maximum number of addresses of a `getcontractcreation` request
+const MAX_CONTRACT_CREATION_ADDRESSES: usize = 5;
+
+/// The reason given by the contract endpoints for contracts without verified source code
+const NOT_VERIFIED: &str = "Contract source code not verified";
+
+fn is_not_verified(reason: &str) -> bool {
+    reason.starts_with(NOT_VERIFIED)
 }
 
 impl Client {
     /// Fetches a verified contract's ABI.
     ///
+    /// Fails with [EtherscanError::ContractCodeNotVerified] if the contract isn't verified.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -336,12 +597,19 @@ impl Client {
             }
         }
 
-        let query = self.create_query("contract", "getabi", HashMap::from([("address", address)]));
+        let query = self.create_query(
+            "contract",
+            "getabi",
+            HashMap::from([("address", self.format_address(&address))]),
+        );
         let resp: Response<String> = self.get_json(&query).await?;
         if resp.result.starts_with("Max rate limit reached") {
             return Err(EtherscanError::RateLimitExceeded)
         }
-        if resp.result.starts_with("Contract source code not verified") {
+        if resp.result.starts_with("Invalid API Key") {
+            return Err(EtherscanError::InvalidApiKey)
+        }
+        if is_not_verified(&resp.result) || is_not_verified(&resp.message) {
             if let Some(ref cache) = self.cache {
                 cache.set_abi(address, None);
             }
@@ -356,8 +624,59 @@ impl Client {
         Ok(abi)
     }
 
+    /// Fetches the ABIs of several contracts with at most `concurrency` requests in flight, all
+    /// within the client's rate limit.
+    ///
+    /// Every address maps to its own result, so an unverified contract doesn't fail the others.
+    /// Duplicate addresses are fetched once.
+    ///
+    /// # Errors
+    ///
+    /// Fails the whole batch with [EtherscanError::InvalidApiKey], since no ABI can be fetched
+    /// with a rejected key.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new(Chain::Mainnet, "<your_api_key>")?;
+    /// let addresses = [
+    ///     "0xBB9bc244D798123fDe783fCc1C72d3Bb8C189413".parse()?,
+    ///     "0xdAC17F958D2ee523a2206206994597C13D831ec7".parse()?,
+    /// ];
+    /// let abis = client.get_abis(&addresses, 4).await?;
+    /// assert!(abis[&addresses[0]].is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_abis(
+        &self,
+        addresses: &[Address],
+        concurrency: usize,
+    ) -> Result<HashMap<Address, Result<Abi>>> {
+        let mut seen = HashSet::new();
+        let unique: Vec<_> =
+            addresses.iter().copied().filter(|address| seen.insert(*address)).collect();
+        let mut results = HashMap::with_capacity(unique.len());
+        let mut abis = stream::iter(unique)
+            .map(|address| async move { (address, self.contract_abi(address).await) })
+            .buffer_unordered(concurrency.max(1));
+
+        while let Some((address, abi)) = abis.next().await {
+            if let Err(EtherscanError::InvalidApiKey) = abi {
+                return Err(EtherscanError::InvalidApiKey)
+            }
+            results.insert(address, abi);
+        }
+        Ok(results)
+    }
+
     /// Fetches a contract's verified source code and its metadata.
     ///
+    /// Fails with [EtherscanError::ContractCodeNotVerified] if the contract isn't verified.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -384,12 +703,15 @@ impl Client {
             }
         }
 
-        let query =
-            self.create_query("contract", "getsourcecode", HashMap::from([("address", address)]));
+        let query = self.create_query(
+            "contract",
+            "getsourcecode",
+            HashMap::from([("address", self.format_address(&address))]),
+        );
         let response = self.get(&query).await?;
 
         // Source code is not verified
-        if response.contains("Contract source code not verified") {
+        if response.contains(NOT_VERIFIED) {
             if let Some(ref cache) = self.cache {
                 cache.set_source(address, None);
             }
@@ -405,4 +727,397 @@ impl Client {
 
         Ok(result)
     }
+
+    /// Returns the creator and deployment transaction of each of a list of contracts, using the
+    /// `getcontractcreation` endpoint.
+    ///
+    /// The endpoint takes up to 5 addresses, so longer lists are sent in batches of 5, one after
+    /// the other. The results are in the order of `addresses`, with `None` for addresses that
+    /// aren't contracts.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new(Chain::Mainnet, "<your_api_key>")?;
+    /// let addresses = [
+    ///     "0xBB9bc244D798123fDe783fCc1C72d3Bb8C189413".parse()?,
+    ///     "0xdAC17F958D2ee523a2206206994597C13D831ec7".parse()?,
+    /// ];
+    /// let creations = client.get_contract_creation(&addresses).await?;
+    /// assert!(creations[0].is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_contract_creation(
+        &self,
+        addresses: &[Address],
+    ) -> Result<Vec<Option<ContractCreation>>> {
+        let mut seen = HashSet::new();
+        let unique: Vec<_> =
+            addresses.iter().copied().filter(|address| seen.insert(*address)).collect();
+
+        let mut creations = HashMap::with_capacity(unique.len());
+        for batch in unique.chunks(MAX_CONTRACT_CREATION_ADDRESSES) {
+            let batch =
+                batch.iter().map(|address| self.format_address(address)).collect::<Vec<_>>();
+            let query = self.create_query(
+                "contract",
+                "getcontractcreation",
+                HashMap::from([("contractaddresses", batch.join(","))]),
+            );
+            let response: Response<Option<Vec<ContractCreation>>> = self.get_json(&query).await?;
+            // a batch without any contracts has no result
+            let found = match check_ok(&response.status, &response.message, response.result) {
+                Ok(found) => found.unwrap_or_default(),
+                Err(_) if response.message.starts_with("No data found") => Vec::new(),
+                Err(err) => return Err(err),
+            };
+            creations
+                .extend(found.into_iter().map(|creation| (creation.contract_address, creation)));
+        }
+        Ok(addresses.iter().map(|address| creations.get(address).cloned()).collect())
+    }
+
+    /// Fetches the deployed bytecode of a contract at the latest block, using the `eth_getCode`
+    /// proxy. Unlike [Self::contract_source_code] this works for unverified contracts.
+    ///
+    /// Returns empty [Bytes] for externally owned accounts.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new(Chain::Mainnet, "<your_api_key>")?;
+    /// let address = "0xBB9bc244D798123fDe783fCc1C72d3Bb8C189413".parse()?;
+    /// let code = client.get_contract_bytecode(&address).await?;
+    /// assert!(!code.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_contract_bytecode(&self, address: &Address) -> Result<Bytes> {
+        let query = self.create_query(
+            "proxy",
+            "eth_getCode",
+            HashMap::from([
+                ("address", self.format_address(address)),
+                ("tag", "latest".to_string()),
+            ]),
+        );
+        let response: ProxyResponse<Bytes> = self.get_json_rpc(&query).await?;
+        Ok(response.result)
+    }
+
+    /// Returns whether the deployed bytecode of a contract dispatches all functions of an
+    /// expected ABI, see [Self::check_abi].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use ethers_core::{abi::parse_abi, types::Chain};
+    /// # use ethers_etherscan::Client;
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new(Chain::Mainnet, "<your_api_key>")?;
+    /// let address = "0xdAC17F958D2ee523a2206206994597C13D831ec7".parse()?;
+    /// let erc20 = parse_abi(&["function transfer(address to, uint256 value)"])?;
+    /// assert!(client.matches_abi(&address, &erc20).await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn matches_abi(&self, address: &Address, expected: &Abi) -> Result<bool> {
+        Ok(self.check_abi(address, expected).await?.is_match())
+    }
+
+    /// Compares the deployed bytecode of a contract with an expected ABI, listing the functions
+    /// whose selectors it doesn't contain.
+    ///
+    /// The bytecode is fetched with [Self::get_contract_bytecode], so this works for unverified
+    /// contracts too, and the selectors are taken from it with [bytecode_selectors]. For a proxy,
+    /// check the address of its implementation: the proxy itself only forwards calls.
+    /// Externally owned accounts have no code and match no function.
+    pub async fn check_abi(&self, address: &Address, expected: &Abi) -> Result<AbiMatch> {
+        let code = self.get_contract_bytecode(address).await?;
+        let selectors = bytecode_selectors(&code);
+        let missing = expected
+            .functions()
+            .filter(|function| !selectors.contains(&function.short_signature()))
+            .map(|function| function.signature())
+            .collect();
+        Ok(AbiMatch { missing })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{query_param, MockResponse, MockServer};
+    use ethers_core::abi::parse_abi;
+
+    #[test]
+    fn can_extract_bytecode_selectors() {
+        // PUSH4 0xa9059cbb, PUSH32 with a PUSH4 0x095ea7b3 in its data, PUSH3 0xfdd58e
+        let mut code = vec![0x63, 0xa9, 0x05, 0x9c, 0xbb, 0x14, 0x7f, 0x63, 0x09, 0x5e, 0xa7, 0xb3];
+        code.extend([0; 28]);
+        code.extend([0x62, 0xfd, 0xd5, 0x8e, 0x63, 0x01]);
+
+        let selectors = bytecode_selectors(&code);
+        assert!(selectors.contains(&[0xa9, 0x05, 0x9c, 0xbb]));
+        assert!(selectors.contains(&[0x00, 0xfd, 0xd5, 0x8e]));
+        assert!(!selectors.contains(&[0x09, 0x5e, 0xa7, 0xb3]));
+        // the truncated push at the end is ignored
+        assert_eq!(selectors.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn can_check_abi() {
+        // PUSH4 transfer(address,uint256)
+        let server = MockServer::start(vec![MockResponse::json(
+            r#"{"jsonrpc":"2.0","id":1,"result":"0x63a9059cbb14"}"#,
+        )])
+        .await;
+
+        let address = Address::repeat_byte(1);
+        let expected = parse_abi(&[
+            "function transfer(address to, uint256 value)",
+            "function approve(address spender, uint256 value)",
+        ])
+        .unwrap();
+        let result = server.client().check_abi(&address, &expected).await.unwrap();
+        assert_eq!(result.missing, ["approve(address,uint256)"]);
+        assert!(!server.client().matches_abi(&address, &expected).await.unwrap());
+
+        let transfer = parse_abi(&["function transfer(address to, uint256 value)"]).unwrap();
+        assert!(server.client().matches_abi(&address, &transfer).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn can_get_contract_bytecode() {
+        let server = MockServer::start(vec![
+            MockResponse::json(r#"{"jsonrpc":"2.0","id":1,"result":"0x6080604052"}"#),
+            MockResponse::json(r#"{"jsonrpc":"2.0","id":1,"result":"0x"}"#),
+        ])
+        .await;
+
+        let address = Address::repeat_byte(1);
+        let code = server.client().get_contract_bytecode(&address).await.unwrap();
+        assert_eq!(code, Bytes::from(vec![0x60, 0x80, 0x60, 0x40, 0x52]));
+        let code = server.client().get_contract_bytecode(&address).await.unwrap();
+        assert!(code.is_empty());
+
+        let request = &server.requests()[0];
+        assert_eq!(query_param(request, "action"), Some("eth_getCode"));
+        assert_eq!(query_param(request, "tag"), Some("latest"));
+    }
+
+    #[tokio::test]
+    async fn can_get_contract_creation() {
+        let address = |byte| Address::repeat_byte(byte);
+        let creation = |byte| {
+            serde_json::json!({
+                "contractAddress": format!("{:?}", address(byte)),
+                "contractCreator": format!("{:?}", address(0xff)),
+                "txHash": format!("{:?}", H256::repeat_byte(byte)),
+            })
+        };
+        let response = |result: serde_json::Value| {
+            MockResponse::json(
+                serde_json::json!({"status": "1", "message": "OK", "result": result}).to_string(),
+            )
+        };
+        let server = MockServer::start(vec![
+            // out of order and without the account 3
+            response(serde_json::json!([creation(5), creation(1), creation(2), creation(4)])),
+            response(serde_json::json!([creation(9)])),
+            MockResponse::json(r#"{"status":"0","message":"No data found","result":null}"#),
+        ])
+        .await;
+
+        let addresses: Vec<_> = [1, 2, 3, 4, 5, 6, 7, 8, 2, 1, 9, 10, 11].map(address).to_vec();
+        let creations = server.client().get_contract_creation(&addresses).await.unwrap();
+        let created: Vec<_> =
+            creations.iter().map(|creation| creation.as_ref().map(|c| c.tx_hash)).collect();
+        let hash = |byte| Some(H256::repeat_byte(byte));
+        assert_eq!(
+            created,
+            [
+                hash(1),
+                hash(2),
+                None,
+                hash(4),
+                hash(5),
+                None,
+                None,
+                None,
+                hash(2),
+                hash(1),
+                hash(9),
+                None,
+                None
+            ]
+        );
+
+        // duplicates are only requested once
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            query_param(&requests[2], "contractaddresses").unwrap().matches("0x").count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn can_get_abis() {
+        let server = MockServer::start(vec![
+            MockResponse::json(r#"{"status":"1","message":"OK","result":"[]"}"#),
+            MockResponse::json(
+                r#"{"status":"0","message":"NOTOK","result":"Contract source code not verified"}"#,
+            ),
+        ])
+        .await;
+
+        let (verified, unverified) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let abis = server.client().get_abis(&[verified, unverified, verified], 1).await.unwrap();
+        assert_eq!(abis.len(), 2);
+        assert_eq!(abis[&verified].as_ref().unwrap(), &Abi::default());
+        assert!(matches!(
+            abis[&unverified],
+            Err(EtherscanError::ContractCodeNotVerified(address)) if address == unverified
+        ));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn unverified_contract_errors_carry_address() {
+        let server = MockServer::start(vec![
+            MockResponse::json(
+                r#"{"status":"0","message":"NOTOK","result":"Contract source code not verified"}"#,
+            ),
+            // some explorers only give the reason in the message
+            MockResponse::json(
+                r#"{"status":"0","message":"Contract source code not verified","result":""}"#,
+            ),
+            MockResponse::json(
+                r#"{"status":"1","message":"OK","result":[{"SourceCode":"","ABI":"Contract source code not verified","ContractName":"","CompilerVersion":"","OptimizationUsed":"","Runs":"","ConstructorArguments":"","EVMVersion":"Default","Library":"","LicenseType":"Unknown","Proxy":"0","Implementation":"","SwarmSource":""}]}"#,
+            ),
+        ])
+        .await;
+
+        let address = Address::repeat_byte(3);
+        let is_unverified =
+            |err| matches!(err, EtherscanError::ContractCodeNotVerified(a) if a == address);
+        assert!(is_unverified(server.client().contract_abi(address).await.unwrap_err()));
+        assert!(is_unverified(server.client().contract_abi(address).await.unwrap_err()));
+        assert!(is_unverified(server.client().contract_source_code(address).await.unwrap_err()));
+    }
+
+    #[tokio::test]
+    async fn invalid_api_key_fails_abi_batch() {
+        let server = MockServer::start(vec![MockResponse::json(
+            r#"{"status":"0","message":"NOTOK","result":"Invalid API Key"}"#,
+        )])
+        .await;
+
+        let addresses = [Address::repeat_byte(1), Address::repeat_byte(2)];
+        let err = server.client().get_abis(&addresses, 2).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::InvalidApiKey));
+    }
+
+    fn metadata(
+        optimization_used: &str,
+        evm_version: &str,
+        license: &str,
+        swarm: &str,
+    ) -> Metadata {
+        serde_json::from_value(serde_json::json!({
+            "SourceCode": "contract A {}",
+            "ABI": "[]",
+            "ContractName": "A",
+            "CompilerVersion": "v0.8.17+commit.8df45f5f",
+            "OptimizationUsed": optimization_used,
+            "Runs": "200",
+            "ConstructorArguments": "",
+            "EVMVersion": evm_version,
+            "Library": "",
+            "LicenseType": license,
+            "Proxy": "0",
+            "Implementation": "",
+            "SwarmSource": swarm
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn can_save_single_file_project() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let meta = ContractMetadata { items: vec![metadata("1", "london", "MIT", "")] };
+        meta.save_to(tempdir.path()).unwrap();
+
+        let read = |path: &str| std::fs::read_to_string(tempdir.path().join(path)).unwrap();
+        assert_eq!(read("A.sol"), "contract A {}");
+        assert_eq!(
+            read("foundry.toml"),
+            "[profile.default]\nsrc = \".\"\nout = \"out\"\nlibs = []\nsolc_version = \"0.8.17\"\noptimizer = true\noptimizer_runs = 200\nevm_version = \"london\"\n"
+        );
+        assert!(!tempdir.path().join("remappings.txt").exists());
+    }
+
+    #[test]
+    fn can_save_standard_json_project() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut meta = metadata("0", "Default", "MIT", "");
+        meta.source_code = SourceCodeMetadata::Metadata {
+            language: Some(SourceCodeLanguage::Solidity),
+            sources: HashMap::from([
+                ("src/A.sol".to_string(), "import \"lib/B.sol\";".into()),
+                ("lib/B.sol".to_string(), "contract B {}".into()),
+            ]),
+            settings: Some(serde_json::json!({
+                "remappings": ["@b/=lib/", "ds-test/=lib/ds-test/src/"],
+                "optimizer": { "enabled": true, "runs": 1000 },
+                "evmVersion": "paris",
+                "viaIR": true
+            })),
+        };
+        meta.save_to(tempdir.path()).unwrap();
+
+        let read = |path: &str| std::fs::read_to_string(tempdir.path().join(path)).unwrap();
+        assert_eq!(read("src/A.sol"), "import \"lib/B.sol\";");
+        assert_eq!(read("lib/B.sol"), "contract B {}");
+        assert_eq!(read("remappings.txt"), "@b/=lib/\nds-test/=lib/ds-test/src/\n");
+        let config = read("foundry.toml");
+        assert!(config.contains("optimizer = true\noptimizer_runs = 1000\n"));
+        assert!(config.contains("evm_version = \"paris\"\nvia_ir = true\n"));
+    }
+
+    #[test]
+    fn can_parse_typed_metadata() {
+        let meta =
+            metadata("1", "london", "MIT", "ipfs://QmWsLvc2qzE6pAT5ZU1Ct1yKKjG2XP6ebw4cGDYEj4f5TU");
+        assert!(meta.optimization_enabled());
+        assert_eq!(meta.optimizer_runs(), Some(200));
+        assert_eq!(meta.evm_version_name(), Some("london"));
+        assert_eq!(meta.license(), Some(LicenseType::Mit));
+        assert_eq!(
+            meta.source_location(),
+            Some(SourceLocation::Ipfs("QmWsLvc2qzE6pAT5ZU1Ct1yKKjG2XP6ebw4cGDYEj4f5TU".into()))
+        );
+    }
+
+    #[test]
+    fn unset_metadata_is_none() {
+        let meta = metadata("0", "Default", "", "");
+        assert!(!meta.optimization_enabled());
+        assert_eq!(meta.optimizer_runs(), None);
+        assert_eq!(meta.evm_version_name(), None);
+        assert_eq!(meta.license(), None);
+        assert_eq!(meta.source_location(), None);
+
+        let meta = metadata("0", "", "None", "bzzr://a3b7");
+        assert_eq!(meta.license(), Some(LicenseType::NoLicense));
+        assert_eq!(meta.source_location(), Some(SourceLocation::Swarm("a3b7".into())));
+    }
 }
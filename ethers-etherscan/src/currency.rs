@@ -0,0 +1,108 @@
+//! The native currencies of the supported chains
+
+use crate::Client;
+use ethers_core::{
+    types::{Chain, U256},
+    utils::format_units,
+};
+
+/// The currency gas and balances are paid in on a chain, e.g. ether on mainnet
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NativeCurrency {
+    pub symbol: &'static str,
+    pub decimals: u32,
+}
+
+impl NativeCurrency {
+    /// Ether, the native currency of mainnet, its testnets and most rollups
+    pub const ETHER: Self = Self { symbol: "ETH", decimals: 18 };
+
+    /// Returns the native currency of `chain`, ether for chains without their own.
+    pub const fn for_chain(chain: Chain) -> Self {
+        use Chain::*;
+
+        let symbol = match chain {
+            BinanceSmartChain => "BNB",
+            BinanceSmartChainTestnet => "tBNB",
+            Polygon | PolygonMumbai => "MATIC",
+            Fantom | FantomTestnet => "FTM",
+            Cronos => "CRO",
+            CronosTestnet => "TCRO",
+            Moonbeam => "GLMR",
+            Moonriver => "MOVR",
+            Moonbase | MoonbeamDev => "DEV",
+            XDai | Chiado => "xDAI",
+            Avalanche | AvalancheFuji => "AVAX",
+            Celo | CeloAlfajores | CeloBaklava => "CELO",
+            Rsk => "RBTC",
+            Poa => "POA",
+            Sokol => "SPOA",
+            Evmos => "EVMOS",
+            EvmosTestnet => "tEVMOS",
+            Oasis | Emerald | EmeraldTestnet => "ROSE",
+            FilecoinMainnet => "FIL",
+            FilecoinHyperspaceTestnet => "tFIL",
+            Canto | CantoTestnet => "CANTO",
+            _ => return Self::ETHER,
+        };
+        Self { symbol, decimals: 18 }
+    }
+
+    /// Formats an amount of the smallest unit, e.g. wei, in whole units with the symbol, like
+    /// `1.5 MATIC`.
+    pub fn format(&self, amount: U256) -> String {
+        let units = format_units(amount, self.decimals).expect("decimals are at most 77");
+        let units = match units.contains('.') {
+            true => units.trim_end_matches('0').trim_end_matches('.'),
+            false => &units,
+        };
+        format!("{units} {}", self.symbol)
+    }
+}
+
+impl Default for NativeCurrency {
+    fn default() -> Self {
+        Self::ETHER
+    }
+}
+
+impl Client {
+    /// Returns the native currency of the chain the client was configured for, or ether if no
+    /// chain was configured.
+    ///
+    /// This is embedded per chain and doesn't send a request.
+    pub fn native_currency(&self) -> NativeCurrency {
+        self.chain.map(NativeCurrency::for_chain).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_format_native_amounts() {
+        let polygon = NativeCurrency::for_chain(Chain::Polygon);
+        assert_eq!(polygon.format(U256::exp10(18) * 3 / 2), "1.5 MATIC");
+        assert_eq!(polygon.format(U256::exp10(18) * 2), "2 MATIC");
+        assert_eq!(polygon.format(U256::zero()), "0 MATIC");
+        assert_eq!(
+            NativeCurrency::for_chain(Chain::Optimism).format(1.into()),
+            "0.000000000000000001 ETH"
+        );
+    }
+
+    #[test]
+    fn client_uses_chain_currency() {
+        let client = Client::builder().chain(Chain::BinanceSmartChain).unwrap().build().unwrap();
+        assert_eq!(client.native_currency(), NativeCurrency { symbol: "BNB", decimals: 18 });
+        let client = Client::builder()
+            .with_api_url("https://explorer.example/api")
+            .unwrap()
+            .with_url("https://explorer.example")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(client.native_currency(), NativeCurrency::ETHER);
+    }
+}
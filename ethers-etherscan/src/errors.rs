@@ -1,4 +1,5 @@
-use ethers_core::types::{Address, Chain};
+use crate::plan::ApiPlan;
+use ethers_core::types::{Address, Bytes, Chain, H256, U256};
 use std::env::VarError;
 
 #[derive(Debug, thiserror::Error)]
@@ -39,12 +40,55 @@ pub enum EtherscanError {
     MissingSolcVersion(String),
     #[error("Invalid API Key")]
     InvalidApiKey,
+    #[error("Missing API Key")]
+    MissingApiKey,
     #[error("Sorry, you have been blocked by Cloudflare, See also https://community.cloudflare.com/t/sorry-you-have-been-blocked/110790")]
     BlockedByCloudflare,
     #[error("The Requested prompted a cloudflare captcha security challenge to review the security of your connection before proceeding.")]
     CloudFlareSecurityChallenge,
     #[error("Received `Page not found` response. API server is likely down")]
     PageNotFound,
+    #[error("Transaction has no input data")]
+    EmptyTransactionInput,
+    #[error("Function selector {0:?} not found in ABI")]
+    UnknownFunctionSelector(Bytes),
+    #[error(transparent)]
+    Abi(#[from] ethers_core::abi::Error),
+    #[error("Unknown chain id: {0}")]
+    UnknownChainId(u64),
+    #[error(
+        "Client is configured for chain {expected}, but the explorer indexes chain id {actual}"
+    )]
+    ChainMismatch { expected: Chain, actual: u64 },
+    #[error("Result window is too large: page {page} * offset {offset} must not exceed 10000")]
+    ResultWindowTooLarge { page: u64, offset: u64 },
+    #[cfg(feature = "csv")]
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error("Request timed out")]
+    Timeout,
+    #[error("Transaction not found: {0:?}")]
+    TransactionNotFound(H256),
+    #[error("Transaction was dropped or replaced: {0:?}")]
+    TransactionReplaced(H256),
+    #[error("Response body exceeds the limit of {limit} bytes")]
+    ResponseTooLarge { limit: usize },
+    #[error("Token query for all transfers of {0:?} needs a block range")]
+    UnboundedTokenQuery(Address),
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+    #[error("Proxy request failed with code {code}: {message}")]
+    Proxy { code: i64, message: String },
+    #[error("Request failed: {0}")]
+    Message(String),
+    #[error("Request failed with HTTP status {0}")]
+    HttpStatus(u16),
+    #[error("No token information for {0:?}")]
+    UnknownToken(Address),
+    #[error("Endpoint needs the {needed} plan, the client is configured for {current}")]
+    PlanRequired { needed: ApiPlan, current: ApiPlan },
+    #[error("Validator balance of {0} gwei does not fit in wei")]
+    ValidatorBalanceOverflow(U256),
 }
 
 /// etherscan/polyscan is protected by cloudflare, which can lead to html responses like `Sorry, you have been blocked` See also <https://community.cloudflare.com/t/sorry-you-have-been-blocked/110790>
@@ -0,0 +1,181 @@
+//! An abstraction over block explorer clients
+
+use crate::{
+    account::{
+        AccountBalance, BlockType, ERC1155TokenTransferEvent, ERC20TokenTransferEvent,
+        ERC721TokenTransferEvent, InternalTransaction, InternalTxQueryOption, MinedBlock,
+        NormalTransaction, Tag, TokenQueryOption, TxListParams,
+    },
+    Client, Result,
+};
+use async_trait::async_trait;
+use ethers_core::types::Address;
+use std::fmt::Debug;
+
+/// The account queries of a block explorer.
+///
+/// [Client] implements this trait by delegating to its inherent methods, which are documented
+/// there. Code that is generic over `ExplorerApi` can be tested with a mock implementation or run
+/// against another explorer backend.
+///
+/// ```
+/// use ethers_etherscan::{errors::EtherscanError, explorer::ExplorerApi};
+/// use ethers_core::types::Address;
+///
+/// async fn has_activity(
+///     explorer: &dyn ExplorerApi,
+///     address: &Address,
+/// ) -> Result<bool, EtherscanError> {
+///     Ok(!explorer.get_transactions(address, None).await?.is_empty())
+/// }
+/// ```
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait ExplorerApi: Sync + Send + Debug {
+    /// See [Client::get_ether_balance_single]
+    async fn get_ether_balance_single(
+        &self,
+        address: &Address,
+        tag: Option<Tag>,
+    ) -> Result<AccountBalance>;
+
+    /// See [Client::get_ether_balance_multi]
+    async fn get_ether_balance_multi(
+        &self,
+        addresses: &[&Address],
+        tag: Option<Tag>,
+    ) -> Result<Vec<AccountBalance>>;
+
+    /// See [Client::get_transactions]
+    async fn get_transactions(
+        &self,
+        address: &Address,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<NormalTransaction>>;
+
+    /// See [Client::get_internal_transactions]
+    async fn get_internal_transactions(
+        &self,
+        tx_query_option: InternalTxQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<InternalTransaction>>;
+
+    /// See [Client::get_erc20_token_transfer_events]
+    async fn get_erc20_token_transfer_events(
+        &self,
+        event_query_option: TokenQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<ERC20TokenTransferEvent>>;
+
+    /// See [Client::get_erc721_token_transfer_events]
+    async fn get_erc721_token_transfer_events(
+        &self,
+        event_query_option: TokenQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<ERC721TokenTransferEvent>>;
+
+    /// See [Client::get_erc1155_token_transfer_events]
+    async fn get_erc1155_token_transfer_events(
+        &self,
+        event_query_option: TokenQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<ERC1155TokenTransferEvent>>;
+
+    /// See [Client::get_mined_blocks]
+    async fn get_mined_blocks(
+        &self,
+        address: &Address,
+        block_type: Option<BlockType>,
+        page_and_offset: Option<(u64, u64)>,
+    ) -> Result<Vec<MinedBlock>>;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ExplorerApi for Client {
+    async fn get_ether_balance_single(
+        &self,
+        address: &Address,
+        tag: Option<Tag>,
+    ) -> Result<AccountBalance> {
+        Client::get_ether_balance_single(self, address, tag).await
+    }
+
+    async fn get_ether_balance_multi(
+        &self,
+        addresses: &[&Address],
+        tag: Option<Tag>,
+    ) -> Result<Vec<AccountBalance>> {
+        Client::get_ether_balance_multi(self, addresses, tag).await
+    }
+
+    async fn get_transactions(
+        &self,
+        address: &Address,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<NormalTransaction>> {
+        Client::get_transactions(self, address, params).await
+    }
+
+    async fn get_internal_transactions(
+        &self,
+        tx_query_option: InternalTxQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<InternalTransaction>> {
+        Client::get_internal_transactions(self, tx_query_option, params).await
+    }
+
+    async fn get_erc20_token_transfer_events(
+        &self,
+        event_query_option: TokenQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<ERC20TokenTransferEvent>> {
+        Client::get_erc20_token_transfer_events(self, event_query_option, params).await
+    }
+
+    async fn get_erc721_token_transfer_events(
+        &self,
+        event_query_option: TokenQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<ERC721TokenTransferEvent>> {
+        Client::get_erc721_token_transfer_events(self, event_query_option, params).await
+    }
+
+    async fn get_erc1155_token_transfer_events(
+        &self,
+        event_query_option: TokenQueryOption,
+        params: Option<TxListParams>,
+    ) -> Result<Vec<ERC1155TokenTransferEvent>> {
+        Client::get_erc1155_token_transfer_events(self, event_query_option, params).await
+    }
+
+    async fn get_mined_blocks(
+        &self,
+        address: &Address,
+        block_type: Option<BlockType>,
+        page_and_offset: Option<(u64, u64)>,
+    ) -> Result<Vec<MinedBlock>> {
+        Client::get_mined_blocks(self, address, block_type, page_and_offset).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{query_param, MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn client_delegates_to_inherent_methods() {
+        let server = MockServer::start(vec![MockResponse::json(
+            r#"{"status":"1","message":"OK","result":"40891626854930000000000"}"#,
+        )])
+        .await;
+
+        let explorer: &dyn ExplorerApi = server.client();
+        let address = Address::repeat_byte(1);
+        let balance = explorer.get_ether_balance_single(&address, None).await.unwrap();
+        assert_eq!(balance.account, address);
+        assert_eq!(balance.balance, "40891626854930000000000");
+        assert_eq!(query_param(&server.requests()[0], "action"), Some("balance"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Falling back to a JSON-RPC node when the explorer fails
+
+use crate::{account::Tag, errors::EtherscanError, Client, ClientBuilder, Result};
+use ethers_core::types::{Address, BlockNumber, U256};
+use ethers_providers::{JsonRpcClient, Middleware, Provider, ProviderError};
+use futures_util::FutureExt;
+use std::{fmt, sync::Arc};
+use tracing::warn;
+
+#[cfg(not(target_arch = "wasm32"))]
+type ProviderFuture<'a, T> = futures_util::future::BoxFuture<'a, T>;
+#[cfg(target_arch = "wasm32")]
+type ProviderFuture<'a, T> = futures_util::future::LocalBoxFuture<'a, T>;
+
+/// The calls of a [Provider] that endpoints fall back to, independent of its transport
+pub(crate) trait FallbackProvider: fmt::Debug + Send + Sync {
+    fn get_balance(
+        &self,
+        address: Address,
+        block: BlockNumber,
+    ) -> ProviderFuture<'_, std::result::Result<U256, ProviderError>>;
+}
+
+impl<P: JsonRpcClient + 'static> FallbackProvider for Provider<P> {
+    fn get_balance(
+        &self,
+        address: Address,
+        block: BlockNumber,
+    ) -> ProviderFuture<'_, std::result::Result<U256, ProviderError>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        return Middleware::get_balance(self, address, Some(block.into())).boxed();
+        #[cfg(target_arch = "wasm32")]
+        return Middleware::get_balance(self, address, Some(block.into())).boxed_local();
+    }
+}
+
+impl ClientBuilder {
+    /// Configures a node to query when the explorer fails to serve a request, e.g. because of
+    /// the rate limit or an outage.
+    ///
+    /// Currently [Client::get_ether_balance_single] falls back to `eth_getBalance`.
+    pub fn with_fallback_provider<P: JsonRpcClient + 'static>(
+        mut self,
+        provider: Provider<P>,
+    ) -> Self {
+        self.fallback_provider = Some(Arc::new(provider));
+        self
+    }
+}
+
+impl Client {
+    /// Fetches the balance of `address` from the fallback provider after the explorer failed
+    /// with `err`.
+    ///
+    /// Returns `err` if there's no fallback provider or it fails as well.
+    pub(crate) async fn fallback_balance(
+        &self,
+        address: &Address,
+        tag: Tag,
+        err: EtherscanError,
+    ) -> Result<String> {
+        let provider = match self.fallback_provider {
+            Some(ref provider) => provider,
+            None => return Err(err),
+        };
+        let block = match tag {
+            Tag::Earliest => BlockNumber::Earliest,
+            Tag::Pending => BlockNumber::Pending,
+            Tag::Latest => BlockNumber::Latest,
+        };
+        match provider.get_balance(*address, block).await {
+            Ok(balance) => Ok(balance.to_string()),
+            Err(provider_err) => {
+                warn!(target: "etherscan", %err, %provider_err, "Fallback provider failed");
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::{MockResponse, MockServer};
+    use ethers_core::types::{Address, U256};
+    use ethers_providers::Provider;
+
+    #[tokio::test]
+    async fn falls_back_to_provider() {
+        let error = r#"{"status":"0","message":"NOTOK","result":"Error! Service unavailable"}"#;
+        let address = Address::repeat_byte(1);
+
+        let server = MockServer::start(vec![MockResponse::json(error)]).await;
+        server.client().get_ether_balance_single(&address, None).await.unwrap_err();
+
+        let (provider, mock) = Provider::mocked();
+        mock.push(U256::from(42)).unwrap();
+        let server = MockServer::start_with(vec![MockResponse::json(error)], |builder| {
+            builder.with_fallback_provider(provider)
+        })
+        .await;
+        let balance = server.client().get_ether_balance_single(&address, None).await.unwrap();
+        assert_eq!(balance.balance, "42");
+
+        // the explorer's error is kept if the provider fails too
+        let err = server.client().get_ether_balance_single(&address, None).await.unwrap_err();
+        assert!(matches!(err, crate::errors::EtherscanError::BalanceFailed), "{err}");
+    }
+}
@@ -0,0 +1,300 @@
+//! Sample rows of the account endpoints as the explorer returns them, for testing code that
+//! consumes the parsed types.
+//!
+//! Enabled by the `test_fixtures` feature. Each constant holds the JSON of one row, with a
+//! function returning it parsed. [with_fields] replaces fields of a row, e.g. to give the rows of
+//! a list distinct blocks, and [list_response] wraps rows in the envelope of a list endpoint.
+
+use crate::account::{
+    ERC1155TokenTransferEvent, ERC20TokenTransferEvent, ERC721TokenTransferEvent,
+    InternalTransaction, MinedBlock, NormalTransaction,
+};
+use ethers_core::types::H256;
+
+/// A token transfer call of `txlist`
+pub const NORMAL_TRANSACTION: &str = r#"{"blockNumber":"14923678","timeStamp":"1654646411","hash":"0xc276cc8d0b5e9b5ef5a9e3e41e7dd2c4e5cb34af571b9fd4e9a4bf77d1d1a6b8","nonce":"6","blockHash":"0xb9367ac1df1a7c1e2cd93e4c2b1bcf2b1a6c25a2c1b74d7f58e1bbe3c10f8e2e","transactionIndex":"61","from":"0x9aa99c23f67c81701c772b106b4f83f6e858dd2e","to":"0xdac17f958d2ee523a2206206994597c13d831ec7","value":"0","gas":"94813","gasPrice":"32861441914","isError":"0","txreceipt_status":"1","input":"0xa9059cbb0000000000000000000000004e83362442b8d1bec281594cea3050c8eb01311c0000000000000000000000000000000000000000000000000000000005f5e100","contractAddress":"","cumulativeGasUsed":"4525904","gasUsed":"63209","confirmations":"2277083","methodId":"0xa9059cbb","functionName":"transfer(address _to, uint256 _value)"}"#;
+
+/// A genesis allocation of `txlist`, with placeholders instead of most fields
+pub const GENESIS_TRANSACTION: &str = r#"{"blockNumber":"0","timeStamp":"1438269973","hash":"GENESIS_ddbd2b932c763ba5b1b7ae3b362eac3e8d40121a","nonce":"","blockHash":"","transactionIndex":"-","from":"GENESIS","to":"0xddbd2b932c763ba5b1b7ae3b362eac3e8d40121a","value":"10000000000000000000000","gas":"-","gasPrice":"-","isError":"0","txreceipt_status":"","input":"0x","contractAddress":"","cumulativeGasUsed":"","gasUsed":"-","confirmations":"17000000","methodId":"0x","functionName":""}"#;
+
+/// A contract call of `txlistinternal`
+pub const INTERNAL_TRANSACTION: &str = r#"{"blockNumber":"2535479","timeStamp":"1477837690","hash":"0x8a1a9989bda84f80143181a68bc137ecefa64d0d4ebde45dd94fc0cf49e70cb6","from":"0x20d42f2e99a421147acf198d775395cac2e8b03d","to":"0x2c1ba59d6f58433fb1eaee7d20b26ed83bda51a3","value":"1000000000000000000","contractAddress":"","input":"","type":"call","gas":"2300","gasUsed":"0","traceId":"0_1","isError":"0","errCode":""}"#;
+
+/// A transfer of `tokentx`
+pub const ERC20_TRANSFER: &str = r#"{"blockNumber":"2228258","timeStamp":"1473433992","hash":"0x00d6a030a3ba0c1c54e5b67c5b1fbc3fa7ea8de1d3f4da3c2a13dd7f8c5fbdbf","nonce":"10","blockHash":"0xa6cd6d2af5bbf1945c3a7a4ba7d159d7e86cd4f0cd51d1d2a9a6f0b61a4a56b4","from":"0x4e83362442b8d1bec281594cea3050c8eb01311c","contractAddress":"0xecf8f87f810ecf450940c9f60066b4a7a501d6a7","to":"0xac75b73394c329376c214663d92156afa864a77f","value":"101000000000000000000","tokenName":"Old Tether USD","tokenSymbol":"USDT","tokenDecimal":"18","transactionIndex":"3","gas":"150000","gasPrice":"20000000000","gasUsed":"93657","cumulativeGasUsed":"167790","input":"deprecated","confirmations":"14727604"}"#;
+
+/// A transfer of `tokennfttx`
+pub const ERC721_TRANSFER: &str = r#"{"blockNumber":"4708120","timeStamp":"1512907118","hash":"0x031e6968a8de362e4328d60dcc7f72f0d6fc84284c452f63176632177146de66","nonce":"0","blockHash":"0x4be19c278bfaead5cb0bc9476fa632e2447f6e6259e0303af210302d22779a24","from":"0xb1690c08e213a35ed9bab7b318de14420fb57d8c","contractAddress":"0x06012c8cf97bead5deae237070f9587f8e7a266d","to":"0x6975be450864c02b4613023c2152ee0743572325","tokenID":"202106","tokenName":"CryptoKitties","tokenSymbol":"CK","tokenDecimal":"0","transactionIndex":"81","gas":"158820","gasPrice":"40000000000","gasUsed":"60508","cumulativeGasUsed":"4880352","input":"deprecated","confirmations":"12442759"}"#;
+
+/// A transfer of `token1155tx`
+pub const ERC1155_TRANSFER: &str = r#"{"blockNumber":"13472395","timeStamp":"1634973285","hash":"0x643b15f3ffaad5d38e33e5872b4ebaa7a643eda8b50ffd5331f682934ee65d4d","nonce":"41","blockHash":"0xa5da536dfbe8125eb146114e2ee0d0bdef2b20483aacbf30fed6b60f092059e6","transactionIndex":"100","gas":"140000","gasPrice":"52898577246","gasUsed":"105030","cumulativeGasUsed":"11739203","input":"deprecated","contractAddress":"0x76be3b62873462d2142405439777e971754e8e77","from":"0x1e63326a84d2fa207bdfa856da9278a93deba418","to":"0x83f564d180b58ad9a02a449105568189ee7de8cb","tokenID":"10371","tokenValue":"1","tokenName":"parallel","tokenSymbol":"LL","confirmations":"3740540"}"#;
+
+/// A block of `getminedblocks`
+pub const MINED_BLOCK: &str =
+    r#"{"blockNumber":"3462296","timeStamp":"1491118514","blockReward":"5194770940000000000"}"#;
+
+/// Wraps rows in the envelope of a successful list response, e.g. `txlist`.
+pub fn list_response(rows: &[&str]) -> String {
+    format!(r#"{{"status":"1","message":"OK","result":[{}]}}"#, rows.join(","))
+}
+
+/// The response of a list endpoint without rows, e.g. for a page past the last one.
+pub fn empty_list() -> String {
+    r#"{"status":"0","message":"No transactions found","result":[]}"#.to_string()
+}
+
+/// The JSON of [NORMAL_TRANSACTION] as the first transaction of `block`, with a hash derived from
+/// the block number so the transactions of different blocks are distinct.
+pub fn hashed_tx_json(block: u64) -> serde_json::Value {
+    with_fields(
+        NORMAL_TRANSACTION,
+        serde_json::json!({
+            "blockNumber": block.to_string(),
+            "transactionIndex": "0",
+            "hash": format!("{:?}", H256::from_low_u64_be(block)),
+            "input": "0x",
+        }),
+    )
+}
+
+/// Returns the JSON of `row` with the fields of the `fields` object replaced or added.
+pub fn with_fields(row: &str, fields: serde_json::Value) -> serde_json::Value {
+    let mut row: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(row).expect("valid fixture");
+    match fields {
+        serde_json::Value::Object(fields) => row.extend(fields),
+        fields => panic!("expected an object of fields, got {fields}"),
+    }
+    serde_json::Value::Object(row)
+}
+
+/// [NORMAL_TRANSACTION] parsed
+pub fn normal_transaction() -> NormalTransaction {
+    serde_json::from_str(NORMAL_TRANSACTION).expect("valid fixture")
+}
+
+/// [GENESIS_TRANSACTION] parsed
+pub fn genesis_transaction() -> NormalTransaction {
+    serde_json::from_str(GENESIS_TRANSACTION).expect("valid fixture")
+}
+
+/// [INTERNAL_TRANSACTION] parsed
+pub fn internal_transaction() -> InternalTransaction {
+    serde_json::from_str(INTERNAL_TRANSACTION).expect("valid fixture")
+}
+
+/// [ERC20_TRANSFER] parsed
+pub fn erc20_transfer() -> ERC20TokenTransferEvent {
+    serde_json::from_str(ERC20_TRANSFER).expect("valid fixture")
+}
+
+/// [ERC721_TRANSFER] parsed
+pub fn erc721_transfer() -> ERC721TokenTransferEvent {
+    serde_json::from_str(ERC721_TRANSFER).expect("valid fixture")
+}
+
+/// [ERC1155_TRANSFER] parsed
+pub fn erc1155_transfer() -> ERC1155TokenTransferEvent {
+    serde_json::from_str(ERC1155_TRANSFER).expect("valid fixture")
+}
+
+/// [MINED_BLOCK] parsed
+pub fn mined_block() -> MinedBlock {
+    serde_json::from_str(MINED_BLOCK).expect("valid fixture")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{account::GenesisOption, Response};
+    use serde::{de::DeserializeOwned, Serialize};
+
+    /// Asserts that serializing a parsed fixture and parsing it again yields the same value.
+    fn assert_round_trip<T: Serialize + DeserializeOwned>(fixture: &str) -> serde_json::Value {
+        let parsed: T = serde_json::from_str(fixture).unwrap();
+        let json = serde_json::to_value(&parsed).unwrap();
+        let reparsed: T = serde_json::from_value(json.clone())
+            .unwrap_or_else(|err| panic!("failed to parse {json}: {err}"));
+        assert_eq!(serde_json::to_value(&reparsed).unwrap(), json);
+        json
+    }
+
+    #[test]
+    fn fixtures_round_trip() {
+        let json = assert_round_trip::<NormalTransaction>(NORMAL_TRANSACTION);
+        assert_eq!(
+            json["hash"],
+            "0xc276cc8d0b5e9b5ef5a9e3e41e7dd2c4e5cb34af571b9fd4e9a4bf77d1d1a6b8"
+        );
+        assert_eq!(json["methodId"], "0xa9059cbb");
+        assert_eq!(json["contractAddress"], "");
+        assert_eq!(normal_transaction().to, Some(json["to"].as_str().unwrap().parse().unwrap()));
+        assert_round_trip::<InternalTransaction>(INTERNAL_TRANSACTION);
+        assert_round_trip::<ERC20TokenTransferEvent>(ERC20_TRANSFER);
+        assert_round_trip::<ERC721TokenTransferEvent>(ERC721_TRANSFER);
+        assert_round_trip::<ERC1155TokenTransferEvent>(ERC1155_TRANSFER);
+        assert_round_trip::<MinedBlock>(MINED_BLOCK);
+    }
+
+    #[test]
+    fn genesis_fixture_round_trips() {
+        let json = assert_round_trip::<NormalTransaction>(GENESIS_TRANSACTION);
+        assert_eq!(json["hash"], "GENESIS");
+        assert_eq!(json["from"], "GENESIS");
+        assert_eq!(json["methodId"], "0x");
+
+        let tx = genesis_transaction();
+        assert!(tx.hash.is_genesis() && tx.from.is_genesis());
+        assert_eq!(tx.nonce, None);
+        assert_eq!(tx.method_id, None);
+        assert!(matches!(internal_transaction().contract_address, GenesisOption::None));
+    }
+
+    /// JSON values exercising the edge cases of the field deserializers
+    const EDGE_VALUES: &[&str] = &[
+        r#""""#,
+        r#""-""#,
+        r#""0x""#,
+        r#""0X""#,
+        r#""0xzz""#,
+        r#""0x123""#,
+        r#""GENESIS""#,
+        r#""GENESIS_zz""#,
+        r#""\"""#,
+        r#""\\""#,
+        r#""\u0000""#,
+        r#""é""#,
+        r#""0xé""#,
+        r#""1e3""#,
+        r#""-1""#,
+        r#""184467440737095516160""#,
+        r#""0x10000000000000000""#,
+        r#""\"0x12\"""#,
+        "18446744073709551616",
+        "-1",
+        "1.5",
+        "null",
+        "true",
+        "[]",
+        "{}",
+    ];
+
+    /// Parses `json` as `T`, failing the test if the deserializer panics instead of erring.
+    fn assert_no_panic<T: DeserializeOwned>(json: &[u8]) {
+        let parsed = std::panic::catch_unwind(|| serde_json::from_slice::<T>(json).is_ok());
+        assert!(parsed.is_ok(), "panicked on {}", String::from_utf8_lossy(json));
+    }
+
+    /// Feeds `fixture` with each field replaced by edge case and random values, and randomly
+    /// mutated bytes, to the deserializer of `T`.
+    fn fuzz<T: DeserializeOwned>(fixture: &str, rng: &mut impl rand::Rng) {
+        const CHARS: &[char] =
+            &['0', '1', '9', 'a', 'f', 'x', 'X', 'z', '-', '_', '"', '\\', ' ', 'G', 'é'];
+        const BYTES: &[u8] = b"\"\\{}[],:0x-GE \xff";
+
+        let row: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(fixture).unwrap();
+        for field in row.keys() {
+            let mut values: Vec<serde_json::Value> =
+                EDGE_VALUES.iter().map(|value| serde_json::from_str(value).unwrap()).collect();
+            for _ in 0..20 {
+                let len = rng.gen_range(0..80);
+                let s = (0..len).map(|_| CHARS[rng.gen_range(0..CHARS.len())]).collect();
+                values.push(serde_json::Value::String(s));
+            }
+            for value in values {
+                let mut row = row.clone();
+                row.insert(field.clone(), value);
+                assert_no_panic::<T>(serde_json::to_string(&row).unwrap().as_bytes());
+            }
+        }
+
+        for _ in 0..2000 {
+            let mut bytes = fixture.as_bytes().to_vec();
+            for _ in 0..rng.gen_range(1..8) {
+                let at = rng.gen_range(0..bytes.len());
+                let byte = BYTES[rng.gen_range(0..BYTES.len())];
+                match rng.gen_range(0..3) {
+                    0 => bytes[at] = byte,
+                    1 => bytes.insert(at, byte),
+                    _ => {
+                        bytes.remove(at);
+                    }
+                }
+            }
+            assert_no_panic::<T>(&bytes);
+        }
+    }
+
+    #[test]
+    fn deserializers_never_panic() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(186);
+        fuzz::<NormalTransaction>(NORMAL_TRANSACTION, &mut rng);
+        fuzz::<NormalTransaction>(GENESIS_TRANSACTION, &mut rng);
+        fuzz::<InternalTransaction>(INTERNAL_TRANSACTION, &mut rng);
+        fuzz::<ERC20TokenTransferEvent>(ERC20_TRANSFER, &mut rng);
+        fuzz::<ERC721TokenTransferEvent>(ERC721_TRANSFER, &mut rng);
+        fuzz::<ERC1155TokenTransferEvent>(ERC1155_TRANSFER, &mut rng);
+        fuzz::<MinedBlock>(MINED_BLOCK, &mut rng);
+    }
+
+    /// Asserts that `simd-json` parses `fixture`, with each field replaced by the edge cases, into
+    /// the same values as `serde_json`, or fails alike.
+    #[cfg(feature = "simd-json")]
+    fn assert_simd_json_agrees<T: Serialize + DeserializeOwned>(fixture: &str) {
+        let parse = |row: &str| {
+            let body = list_response(&[row]);
+            let simd =
+                simd_json::serde::from_slice::<Response<Vec<T>>>(&mut body.clone().into_bytes());
+            let serde = serde_json::from_str::<Response<Vec<T>>>(&body);
+            let value = |res: Response<Vec<T>>| serde_json::to_value(res.result).unwrap();
+            let (simd, serde) = (simd.ok().map(value), serde.ok().map(value));
+            assert_eq!(simd, serde, "parsing {row}");
+            simd.is_some()
+        };
+        assert!(parse(fixture));
+
+        let row: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(fixture).unwrap();
+        for field in row.keys() {
+            for value in EDGE_VALUES {
+                let mut row = row.clone();
+                row.insert(field.clone(), serde_json::from_str(value).unwrap());
+                parse(&serde_json::to_string(&row).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "simd-json")]
+    fn simd_json_agrees_with_serde_json() {
+        assert_simd_json_agrees::<NormalTransaction>(NORMAL_TRANSACTION);
+        assert_simd_json_agrees::<NormalTransaction>(GENESIS_TRANSACTION);
+        assert_simd_json_agrees::<InternalTransaction>(INTERNAL_TRANSACTION);
+        assert_simd_json_agrees::<ERC20TokenTransferEvent>(ERC20_TRANSFER);
+        assert_simd_json_agrees::<ERC721TokenTransferEvent>(ERC721_TRANSFER);
+        assert_simd_json_agrees::<ERC1155TokenTransferEvent>(ERC1155_TRANSFER);
+        assert_simd_json_agrees::<MinedBlock>(MINED_BLOCK);
+    }
+
+    #[test]
+    fn keeps_escapes_in_string_fields() {
+        let mut row: serde_json::Value = serde_json::from_str(NORMAL_TRANSACTION).unwrap();
+        row["functionName"] = r#"note(string \"memo\" \b)"#.into();
+        let tx: NormalTransaction = serde_json::from_value(row).unwrap();
+        assert_eq!(tx.function_name.as_deref(), Some(r#"note(string \"memo\" \b)"#));
+    }
+
+    #[test]
+    fn can_parse_list_response() {
+        let body = list_response(&[NORMAL_TRANSACTION, GENESIS_TRANSACTION]);
+        let response: Response<Vec<NormalTransaction>> = serde_json::from_str(&body).unwrap();
+        assert_eq!(response.result.len(), 2);
+        assert_eq!(erc20_transfer().value, 101_000_000_000_000_000_000u128.into());
+        assert_eq!(erc721_transfer().token_id, "202106");
+        assert_eq!(erc1155_transfer().token_value, "1");
+        assert_eq!(mined_block().block_reward, "5194770940000000000");
+    }
+}
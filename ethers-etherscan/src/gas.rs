@@ -26,6 +26,19 @@ pub struct GasOracle {
     pub gas_used_ratio: Vec<f64>,
 }
 
+impl GasOracle {
+    /// Returns the mean of [GasOracle::gas_used_ratio] over the recent blocks, or 0 if there
+    /// are none.
+    pub fn average_gas_used_ratio(&self) -> f64 {
+        match self.gas_used_ratio.len() {
+            0 => 0.0,
+            len => self.gas_used_ratio.iter().sum::<f64>() / len as f64,
+        }
+    }
+}
+
+/// Parses a comma separated list of floats, skipping empty segments like the one after a
+/// trailing comma.
 fn deserialize_f64_vec<'de, D>(deserializer: D) -> core::result::Result<Vec<f64>, D::Error>
 where
     D: de::Deserializer<'de>,
@@ -33,6 +46,8 @@ where
     let str_sequence = String::deserialize(deserializer)?;
     str_sequence
         .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
         .map(|item| f64::from_str(item).map_err(|err| de::Error::custom(err.to_string())))
         .collect()
 }
@@ -67,3 +82,31 @@ impl Client {
         Ok(response.result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_oracle(gas_used_ratio: &str) -> serde_json::Result<GasOracle> {
+        serde_json::from_value(serde_json::json!({
+            "LastBlock": "17000000",
+            "SafeGasPrice": "20",
+            "ProposeGasPrice": "21",
+            "FastGasPrice": "22",
+            "suggestBaseFee": "19.5",
+            "gasUsedRatio": gas_used_ratio,
+        }))
+    }
+
+    #[test]
+    fn can_parse_gas_used_ratio() {
+        let oracle = parse_oracle("0.25,0.75, 0.5,").unwrap();
+        assert_eq!(oracle.gas_used_ratio, [0.25, 0.75, 0.5]);
+        assert_eq!(oracle.average_gas_used_ratio(), 0.5);
+
+        let empty = parse_oracle("").unwrap();
+        assert!(empty.gas_used_ratio.is_empty());
+        assert_eq!(empty.average_gas_used_ratio(), 0.0);
+        assert!(parse_oracle("0.5,abc").is_err());
+    }
+}
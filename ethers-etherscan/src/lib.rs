@@ -1,28 +1,62 @@
 #![doc = include_str!("../README.md")]
 #![deny(unsafe_code, rustdoc::broken_intra_doc_links)]
 
-use crate::errors::{is_blocked_by_cloudflare_response, is_cloudflare_security_challenge};
+use crate::{
+    errors::{is_blocked_by_cloudflare_response, is_cloudflare_security_challenge},
+    metrics::{default_metrics, Endpoint, Instant, Metrics},
+};
 use contract::ContractMetadata;
 use errors::EtherscanError;
 use ethers_core::{
     abi::{Abi, Address},
-    types::{Chain, H256},
+    types::{Chain, H256, U64},
 };
+use futures_util::{stream, Stream, StreamExt};
+use plan::ApiPlan;
+use rate_limit::{default_rate_limit, RateLimiter};
 use reqwest::{header, IntoUrl, Url};
+use retry::RetryPolicy;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
     borrow::Cow,
+    collections::HashMap,
+    fmt,
+    future::Future,
     io::Write,
     path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tracing::{error, trace};
 
 pub mod account;
+pub mod analytics;
+pub mod beacon;
+pub mod blocks;
 pub mod contract;
+pub mod currency;
 pub mod errors;
+pub mod explorer;
+#[cfg(feature = "providers")]
+mod fallback;
+#[cfg(any(test, feature = "test_fixtures"))]
+pub mod fixtures;
 pub mod gas;
+pub mod logs;
+pub mod metrics;
+#[cfg(test)]
+mod mock;
+pub mod plan;
+mod rate_limit;
+pub mod retry;
 pub mod source_tree;
+#[cfg(feature = "pro")]
+pub mod stats;
+#[cfg(feature = "pro")]
+pub mod token;
 pub mod transaction;
 pub mod utils;
 pub mod verify;
@@ -30,18 +64,93 @@ pub mod verify;
 pub(crate) type Result<T> = std::result::Result<T, EtherscanError>;
 
 /// The Etherscan.io API client.
-#[derive(Clone, Debug)]
+///
+/// Its `Debug` output redacts the API key.
+#[derive(Clone)]
 pub struct Client {
     /// Client that executes HTTP requests
     client: reqwest::Client,
     /// Etherscan API key
     api_key: Option<String>,
+    /// The query parameter carrying the API key, `None` to never send it
+    api_key_param: Option<String>,
     /// Etherscan API endpoint like <https://api(-chain).etherscan.io/api>
     etherscan_api_url: Url,
     /// Etherscan base endpoint like <https://etherscan.io>
     etherscan_url: Url,
     /// Path to where ABI files should be cached
     cache: Option<Cache>,
+    /// The highest block number the explorer reported so far, shared between clones
+    head_block: Arc<AtomicU64>,
+    /// When the head block was last fetched, shared between clones
+    head_block_fetched: Arc<Mutex<Option<Instant>>>,
+    /// How many blocks below the head a list range must end to be cached
+    cache_confirmations: u64,
+    /// The chain this client was configured for, if any
+    chain: Option<Chain>,
+    /// Which failed requests are retried
+    retry_policy: RetryPolicy,
+    /// Limits the requests per second, shared between clones
+    rate_limiter: Arc<RateLimiter>,
+    /// Timeouts of GET requests by action, overriding the timeout of the `reqwest::Client`
+    endpoint_timeouts: HashMap<String, Duration>,
+    /// The maximum size of a response body
+    max_response_bytes: Option<usize>,
+    /// Whether gzip or brotli compressed responses are requested
+    compression: bool,
+    /// The number of jobs [Client::batch] runs at once
+    batch_concurrency: usize,
+    /// The `message` of the last response, shared between clones
+    last_message: Arc<Mutex<Option<String>>>,
+    /// The API plan, if configured
+    plan: Option<ApiPlan>,
+    /// Receives measurements of the requests, if configured
+    metrics: Option<Arc<dyn Metrics>>,
+    /// Whether address params are sent with their EIP-55 checksum instead of lowercase
+    checksummed_addresses: bool,
+    /// The node queried when the explorer fails
+    #[cfg(feature = "providers")]
+    fallback_provider: Option<Arc<dyn fallback::FallbackProvider>>,
+    /// Token information by contract, shared between clones
+    #[cfg(feature = "pro")]
+    token_metadata: Arc<Mutex<HashMap<Address, token::TokenInfo>>>,
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Client");
+        debug
+            .field("client", &self.client)
+            .field("api_key", &redacted(&self.api_key))
+            .field("api_key_param", &self.api_key_param)
+            .field("etherscan_api_url", &self.etherscan_api_url)
+            .field("etherscan_url", &self.etherscan_url)
+            .field("cache", &self.cache)
+            .field("head_block", &self.head_block)
+            .field("head_block_fetched", &self.head_block_fetched)
+            .field("cache_confirmations", &self.cache_confirmations)
+            .field("chain", &self.chain)
+            .field("retry_policy", &self.retry_policy)
+            .field("rate_limiter", &self.rate_limiter)
+            .field("endpoint_timeouts", &self.endpoint_timeouts)
+            .field("max_response_bytes", &self.max_response_bytes)
+            .field("compression", &self.compression)
+            .field("batch_concurrency", &self.batch_concurrency)
+            .field("last_message", &self.last_message)
+            .field("plan", &self.plan)
+            .field("metrics", &self.metrics)
+            .field("checksummed_addresses", &self.checksummed_addresses);
+        #[cfg(feature = "providers")]
+        debug.field("fallback_provider", &self.fallback_provider);
+        #[cfg(feature = "pro")]
+        debug.field("token_metadata", &self.token_metadata);
+        debug.finish()
+    }
+}
+
+/// Replaces a secret with `***` in `Debug` output.
+fn redacted<T>(secret: &Option<T>) -> Option<&'static str> {
+    secret.as_ref().map(|_| "***")
 }
 
 impl Client {
@@ -73,6 +182,10 @@ impl Client {
     }
 
     /// Create a new client with the correct endpoints based on the chain and provided API key
+    ///
+    /// # Errors
+    ///
+    /// Returns [EtherscanError::ChainNotSupported] with `chain` if it has no known explorer
     pub fn new(chain: Chain, api_key: impl Into<String>) -> Result<Self> {
         Client::builder().with_api_key(api_key).chain(chain)?.build()
     }
@@ -97,8 +210,10 @@ impl Client {
             Chain::Emerald |
             Chain::EmeraldTestnet |
             Chain::Evmos |
-            Chain::EvmosTestnet => Ok(String::new()),
+            Chain::EvmosTestnet => return Self::builder().chain(chain)?.build(),
             Chain::AnvilHardhat | Chain::Dev => Err(EtherscanError::LocalNetworksNotSupported),
+            // Checked before the API key, so a missing key doesn't hide the unsupported chain
+            _ if chain.etherscan_urls().is_none() => Err(EtherscanError::ChainNotSupported(chain)),
 
             _ => chain
                 .etherscan_api_key_name()
@@ -136,6 +251,82 @@ impl Client {
         &self.etherscan_url
     }
 
+    /// Removes all list responses that were cached for closed block ranges.
+    ///
+    /// Cached ABIs and source code are kept.
+    pub fn clear_cache(&self) -> Result<()> {
+        if let Some(ref cache) = self.cache {
+            cache.clear_list_responses()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the numeric id of the chain this client was configured for, as used for EIP-155
+    /// signing, or `None` if it was built from explorer urls only.
+    ///
+    /// Unlike [Client::detect_chain] this doesn't send a request.
+    pub fn chain_id(&self) -> Option<u64> {
+        self.chain.map(u64::from)
+    }
+
+    /// Returns the maximum number of requests per second this client sends, `0` if unlimited.
+    pub fn rate_limit(&self) -> u32 {
+        self.rate_limiter.requests_per_second()
+    }
+
+    /// Returns the API plan the client was configured for, see [ClientBuilder::with_plan].
+    pub fn plan(&self) -> Option<ApiPlan> {
+        self.plan
+    }
+
+    /// Returns the `message` of the last response of an account, contract, stats, block, logs or
+    /// transaction endpoint, like `OK`, `No transactions found` or a warning about the query.
+    ///
+    /// Convenience methods return the result only, so this is where warnings end up. The message
+    /// is shared between clones of the client and overwritten by every response, so it's only
+    /// meaningful when no other requests are sent concurrently. Responses served from the cache
+    /// count too.
+    pub fn last_message(&self) -> Option<String> {
+        self.last_message.lock().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+
+    /// Runs jobs calling the client, at most [ClientBuilder::with_batch_concurrency] at once,
+    /// and yields their results in the order of the jobs.
+    ///
+    /// Each job is a closure returning a future that uses the client clone it's passed. Jobs can
+    /// mix any endpoints: the clones share the client's rate limiter, so the batch uses up the
+    /// rate limit without exceeding it. Jobs are only started as the stream is polled, and a
+    /// failed job doesn't stop the others.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::{Address, Chain};
+    /// use futures_util::StreamExt;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let addresses: Vec<Address> = vec![
+    ///         "0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap(),
+    ///         "0x4F26FfBe5F04ED43630fdC30A87638d53D0b0876".parse().unwrap(),
+    ///     ];
+    ///     let jobs = addresses.into_iter().map(|address| {
+    ///         move |client: Client| async move { client.get_transactions(&address, None).await }
+    ///     });
+    ///     let txs: Vec<_> = client.batch(jobs).collect().await;
+    /// # }
+    /// ```
+    pub fn batch<'a, F, Fut, T>(
+        &'a self,
+        jobs: impl IntoIterator<Item = F> + 'a,
+    ) -> impl Stream<Item = Result<T>> + 'a
+    where
+        F: FnOnce(Client) -> Fut + 'a,
+        Fut: Future<Output = Result<T>> + 'a,
+    {
+        stream::iter(jobs).map(move |job| job(self.clone())).buffered(self.batch_concurrency)
+    }
+
     /// Return the URL for the given block number
     pub fn block_url(&self, block: u64) -> String {
         format!("{}block/{block}", self.etherscan_url)
@@ -156,51 +347,536 @@ impl Client {
         format!("{}token/{token_hash:?}", self.etherscan_url)
     }
 
+    /// Returns the full request URL of the given query, including the API key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ethers_core::types::Chain;
+    /// use ethers_etherscan::Client;
+    /// use std::collections::HashMap;
+    ///
+    /// let client = Client::new(Chain::Mainnet, "<API KEY>").unwrap();
+    /// let query = client.create_query("stats", "ethprice", HashMap::<&str, String>::new());
+    /// let url = client.query_url(&query).unwrap();
+    /// assert_eq!(url.query(), Some("apikey=%3CAPI+KEY%3E&module=stats&action=ethprice"));
+    /// ```
+    pub fn query_url<Q: Serialize>(&self, query: &Q) -> Result<Url> {
+        let request = self.client.get(self.etherscan_api_url.clone()).query(query).build()?;
+        Ok(request.url().clone())
+    }
+
     /// Execute an GET request with parameters.
-    async fn get_json<T: DeserializeOwned, Q: Serialize>(&self, query: &Q) -> Result<Response<T>> {
-        let res = self.get(query).await?;
-        self.sanitize_response(res)
+    ///
+    /// This can be used with [`Client::create_query`] to call endpoints that have no dedicated
+    /// method yet.
+    pub async fn get_json<T: DeserializeOwned, Q: Serialize>(
+        &self,
+        query: &Q,
+    ) -> Result<Response<T>> {
+        self.get_parsed(query, |res| self.sanitize_response(res)).await
+    }
+
+    /// Execute a GET request against the `proxy` module, which responds with a JSON-RPC envelope
+    /// instead of a [Response].
+    pub async fn get_json_rpc<T: DeserializeOwned, Q: Serialize>(
+        &self,
+        query: &Q,
+    ) -> Result<ProxyResponse<T>> {
+        self.get_parsed(query, |res| self.sanitize_proxy_response(res)).await
+    }
+
+    /// Deserialize a JSON-RPC response of the `proxy` module into a [ProxyResponse].
+    fn sanitize_proxy_response<T: DeserializeOwned>(&self, res: &str) -> Result<ProxyResponse<T>> {
+        match serde_json::from_str(res) {
+            Ok(ProxyResponseData::Success(res)) => Ok(res),
+            Ok(ProxyResponseData::Error { error, .. }) => {
+                Err(EtherscanError::Proxy { code: error.code, message: error.message })
+            }
+            Err(err) => {
+                // rate limit and API key errors are reported in place of the result, or in the
+                // regular envelope
+                if let Ok(res) = serde_json::from_str::<ProxyResponse<String>>(res) {
+                    if res.result.starts_with("Max rate limit reached") {
+                        return Err(EtherscanError::RateLimitExceeded)
+                    } else if res.result.to_lowercase() == "invalid api key" {
+                        return Err(EtherscanError::InvalidApiKey)
+                    }
+                }
+                self.sanitize_response::<serde_json::Value>(res)?;
+                Err(err.into())
+            }
+        }
+    }
+
+    /// Execute a GET request and parse the response with `parse`, retrying according to the
+    /// [RetryPolicy].
+    ///
+    /// With the `tracing` feature, the attempts run in the span of `request_span`.
+    async fn get_parsed<Q: Serialize, R>(
+        &self,
+        query: &Q,
+        parse: impl Fn(&str) -> Result<R>,
+    ) -> Result<R> {
+        let request = async move {
+            let request = self.build_get(query)?;
+            let endpoint = self.endpoint(request.url());
+            let mut attempt = 0;
+            loop {
+                let request = request.try_clone().expect("GET requests have no streaming body");
+                let (result, body) = match self.send_get(request, endpoint.as_ref()).await {
+                    Ok(body) => (parse(&body), Some(body)),
+                    Err(err) => (Err(err), None),
+                };
+                match result {
+                    Err(err) if self.retry_policy.should_retry(attempt, &err, body.as_deref()) => {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(target: "etherscan", attempt, "Retrying failed request: {}", err);
+                        #[cfg(not(feature = "tracing"))]
+                        trace!(target: "etherscan", attempt, "Retrying failed request: {}", err);
+                        self.record(endpoint.as_ref(), |metrics, module, action| {
+                            metrics.on_retry(module, action, attempt)
+                        });
+                        self.retry_policy.backoff(attempt).await;
+                        attempt += 1;
+                    }
+                    result => {
+                        if let Err(ref err) = result {
+                            self.record(endpoint.as_ref(), |metrics, module, action| {
+                                metrics.on_error(module, action, err)
+                            });
+                        }
+                        return result
+                    }
+                }
+            }
+        };
+        #[cfg(feature = "tracing")]
+        let request = tracing::Instrument::instrument(request, self.request_span("GET", query));
+        request.await
+    }
+
+    /// Returns a debug span for a request with the module and action of its params and the
+    /// configured chain.
+    ///
+    /// The API key is never recorded.
+    #[cfg(feature = "tracing")]
+    fn request_span(&self, method: &'static str, params: &impl Serialize) -> tracing::Span {
+        let params = serde_json::to_value(params).unwrap_or_default();
+        let param = |name| params.get(name).and_then(|value| value.as_str()).unwrap_or_default();
+        tracing::debug_span!(
+            target: "etherscan",
+            "request",
+            method,
+            module = param("module"),
+            action = param("action"),
+            chain = ?self.chain,
+        )
     }
 
     /// Execute a GET request with parameters, without sanity checking the response.
     async fn get<Q: Serialize>(&self, query: &Q) -> Result<String> {
-        trace!(target: "etherscan", "GET {}", self.etherscan_api_url);
-        let response = self
+        let request = self.build_get(query)?;
+        let endpoint = self.endpoint(request.url());
+        self.send_get(request, endpoint.as_ref()).await
+    }
+
+    /// Builds a GET request with parameters.
+    fn build_get<Q: Serialize>(&self, query: &Q) -> Result<reqwest::Request> {
+        let request = self
             .client
             .get(self.etherscan_api_url.clone())
             .header(header::ACCEPT, "application/json")
             .query(query)
-            .send()
-            .await?
-            .text()
-            .await?;
-        Ok(response)
+            .build()?;
+        Ok(request)
+    }
+
+    /// Sends a GET request built by [Client::build_get] for the given endpoint.
+    async fn send_get(
+        &self,
+        #[allow(unused_mut)] mut request: reqwest::Request,
+        endpoint: Option<&Endpoint>,
+    ) -> Result<String> {
+        self.check_plan(request.url())?;
+        self.wait_for_rate_limit(endpoint).await;
+        trace!(target: "etherscan", "GET {}", self.etherscan_api_url);
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(timeout) = self.endpoint_timeout(request.url()) {
+            *request.timeout_mut() = Some(timeout);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        self.accept_encoding(request.headers_mut());
+        self.execute(request, endpoint).await
+    }
+
+    /// Waits for the rate limit before sending a request for the given endpoint.
+    async fn wait_for_rate_limit(&self, endpoint: Option<&Endpoint>) {
+        let wait = self.rate_limiter.acquire().await;
+        if !wait.is_zero() {
+            self.record(endpoint, |metrics, module, action| {
+                metrics.on_rate_limit_wait(module, action, wait)
+            });
+        }
+    }
+
+    /// Sends a request for the given endpoint and reads the body of a successful response.
+    async fn execute(
+        &self,
+        request: reqwest::Request,
+        endpoint: Option<&Endpoint>,
+    ) -> Result<String> {
+        let started = Instant::now();
+        let response = async {
+            let response = self.client.execute(request).await.map_err(map_timeout)?;
+            let status = response.status();
+            #[cfg(feature = "tracing")]
+            tracing::debug!(target: "etherscan", %status, "Received response");
+            Ok::<_, EtherscanError>((status, self.read_body(response).await?))
+        }
+        .await;
+        self.record(endpoint, |metrics, module, action| {
+            metrics.on_request(module, action, started.elapsed())
+        });
+        let (status, body) = response?;
+        check_http_status(status, body)
+    }
+
+    /// Opts out of compressed responses, if disabled.
+    ///
+    /// Otherwise `reqwest` requests gzip or brotli responses and decodes them while they're read.
+    /// The browser negotiates the encoding of wasm requests by itself.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn accept_encoding(&self, headers: &mut header::HeaderMap) {
+        if !self.compression {
+            headers.insert(header::ACCEPT_ENCODING, header::HeaderValue::from_static("identity"));
+        }
+    }
+
+    /// Reads the body of a response as text, failing once the decoded body exceeds the
+    /// configured maximum size.
+    async fn read_body(&self, response: reqwest::Response) -> Result<String> {
+        let body = self.read_bytes(response).await?;
+        Ok(String::from_utf8(body)
+            .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned()))
+    }
+
+    /// Reads the raw body of a response, failing once it exceeds the configured maximum size.
+    async fn read_bytes(&self, response: reqwest::Response) -> Result<Vec<u8>> {
+        let limit = match self.max_response_bytes {
+            Some(limit) => limit,
+            None => return Ok(response.bytes().await.map_err(map_timeout)?.to_vec()),
+        };
+        let too_large = || EtherscanError::ResponseTooLarge { limit };
+        if response.content_length().map_or(false, |len| len > limit as u64) {
+            return Err(too_large())
+        }
+
+        // the wasm response can only be read at once
+        #[cfg(target_arch = "wasm32")]
+        let body = response.bytes().await.map_err(map_timeout)?.to_vec();
+        #[cfg(not(target_arch = "wasm32"))]
+        let body = {
+            let mut response = response;
+            let mut body = Vec::new();
+            while let Some(chunk) = response.chunk().await.map_err(map_timeout)? {
+                if body.len() + chunk.len() > limit {
+                    return Err(too_large())
+                }
+                body.extend_from_slice(&chunk);
+            }
+            body
+        };
+        if body.len() > limit {
+            return Err(too_large())
+        }
+        Ok(body)
+    }
+
+    /// Fails if the endpoint of the request url isn't included in the configured plan.
+    fn check_plan(&self, url: &Url) -> Result<()> {
+        let current = match self.plan {
+            Some(plan) => plan,
+            None => return Ok(()),
+        };
+        let param = |name| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value)
+                .unwrap_or_default()
+        };
+        let needed = ApiPlan::required_for(&param("module"), &param("action"));
+        if needed > current {
+            return Err(EtherscanError::PlanRequired { needed, current })
+        }
+        Ok(())
+    }
+
+    /// Returns the timeout configured for the action of the request url.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn endpoint_timeout(&self, url: &Url) -> Option<Duration> {
+        let (_, action) = url.query_pairs().find(|(name, _)| name == "action")?;
+        self.endpoint_timeouts.get(action.as_ref()).copied()
     }
 
     /// Execute a POST request with a form.
-    async fn post_form<T: DeserializeOwned, F: Serialize>(&self, form: &F) -> Result<Response<T>> {
+    async fn post_form<T: DeserializeOwned, F: Serialize>(
+        &self,
+        form: &Query<'_, F>,
+    ) -> Result<Response<T>> {
         let res = self.post(form).await?;
-        self.sanitize_response(res)
+        let response = self.sanitize_response(res);
+        if let Err(ref err) = response {
+            self.record(self.form_endpoint(form).as_ref(), |metrics, module, action| {
+                metrics.on_error(module, action, err)
+            });
+        }
+        response
+    }
+
+    /// Returns the endpoint of a POST request's form, if there are metrics to label.
+    fn form_endpoint<F: Serialize>(&self, form: &Query<'_, F>) -> Option<Endpoint> {
+        self.metrics.as_ref().map(|_| Endpoint::new(&form.module, &form.action))
     }
 
     /// Execute a POST request with a form, without sanity checking the response.
-    async fn post<F: Serialize>(&self, form: &F) -> Result<String> {
+    ///
+    /// With the `tracing` feature, the request runs in the span of `request_span`.
+    async fn post<F: Serialize>(&self, form: &Query<'_, F>) -> Result<String> {
+        let endpoint = self.form_endpoint(form);
+        let request = self.send_post(form, endpoint.as_ref());
+        #[cfg(feature = "tracing")]
+        let request = tracing::Instrument::instrument(request, self.request_span("POST", form));
+        let response = request.await;
+        if let Err(ref err) = response {
+            self.record(endpoint.as_ref(), |metrics, module, action| {
+                metrics.on_error(module, action, err)
+            });
+        }
+        response
+    }
+
+    /// The request of [Client::post].
+    async fn send_post<F: Serialize>(
+        &self,
+        form: &Query<'_, F>,
+        endpoint: Option<&Endpoint>,
+    ) -> Result<String> {
+        self.wait_for_rate_limit(endpoint).await;
         trace!(target: "etherscan", "POST {}", self.etherscan_api_url);
-        let response = self
-            .client
-            .post(self.etherscan_api_url.clone())
-            .form(form)
-            .send()
-            .await?
-            .text()
-            .await?;
+        #[allow(unused_mut)]
+        let mut request = self.client.post(self.etherscan_api_url.clone()).form(form).build()?;
+        #[cfg(not(target_arch = "wasm32"))]
+        self.accept_encoding(request.headers_mut());
+        self.execute(request, endpoint).await
+    }
+
+    /// Execute a GET request for a list endpoint, serving it from the cache if possible.
+    ///
+    /// Only queries with an `endBlock` at least the configured confirmations below the current
+    /// head are cached, because those results can no longer change.
+    async fn get_list_json<T: DeserializeOwned>(
+        &self,
+        query: &Query<'_, HashMap<&'static str, String>>,
+    ) -> Result<Response<T>> {
+        self.get_list_parsed(query, |res| self.sanitize_parsed(res, from_owned_list_json(res)))
+            .await
+    }
+
+    /// Like [Client::get_list_json], returning the checked body of the response so it can be
+    /// deserialized into types borrowing from it.
+    pub(crate) async fn get_list_body(
+        &self,
+        query: &Query<'_, HashMap<&'static str, String>>,
+    ) -> Result<String> {
+        let parse = |res: &str| {
+            self.sanitize_list_response::<Vec<serde::de::IgnoredAny>>(res)?;
+            Ok(res.to_string())
+        };
+        self.get_list_parsed(query, parse).await
+    }
+
+    async fn get_list_parsed<R>(
+        &self,
+        query: &Query<'_, HashMap<&'static str, String>>,
+        parse: impl Fn(&str) -> Result<R>,
+    ) -> Result<R> {
+        let window = result_window(&query.other);
+        if let Some((page, offset)) = window {
+            if page.max(1).saturating_mul(offset) > MAX_RESULT_WINDOW {
+                return Err(EtherscanError::ResultWindowTooLarge { page, offset })
+            }
+        }
+
+        let key = match self.cache {
+            Some(_) if self.is_closed_range(&query.other).await? => Some(list_cache_key(query)),
+            _ => None,
+        };
+
+        if let (Some(cache), Some(key)) = (&self.cache, &key) {
+            if let Some(res) = cache.get_list_response(key) {
+                return parse(&res)
+            }
+        }
+
+        let (response, res) = self
+            .get_parsed(query, |res| Ok((parse(res)?, res.to_string())))
+            .await
+            .map_err(|err| match (err, window) {
+                (
+                    EtherscanError::ErrorResponse { result: Some(result), .. },
+                    Some((page, offset)),
+                ) if result.starts_with("Result window is too large") => {
+                    EtherscanError::ResultWindowTooLarge { page, offset }
+                }
+                (err, _) => err,
+            })?;
+        if let (Some(cache), Some(key)) = (&self.cache, &key) {
+            cache.set_list_response(key, &res);
+        }
         Ok(response)
     }
 
+    /// Returns whether the `endBlock` of the list params is more than the cache confirmations
+    /// below the current head.
+    ///
+    /// The head is fetched at most once per [HEAD_BLOCK_MAX_AGE], so the pages of a query share
+    /// it.
+    async fn is_closed_range(&self, params: &HashMap<&'static str, String>) -> Result<bool> {
+        let end_block = match params.get("endBlock").and_then(|b| b.parse::<u64>().ok()) {
+            Some(end_block) => end_block,
+            None => return Ok(false),
+        };
+        let closed = |head: u64| end_block.saturating_add(self.cache_confirmations) < head;
+        if closed(self.head_block.load(Ordering::Relaxed)) {
+            return Ok(true)
+        }
+        let fresh = self
+            .head_block_fetched
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .map_or(false, |fetched| fetched.elapsed() < HEAD_BLOCK_MAX_AGE);
+        if fresh {
+            return Ok(false)
+        }
+        Ok(closed(self.get_block_number().await?))
+    }
+
+    /// Opens a connection to the explorer API with a `HEAD` request, so the connection pool has
+    /// a ready connection before a burst of requests.
+    ///
+    /// This is best-effort: the connection may be closed by the server before the next request,
+    /// and one connection is opened regardless of how many requests follow concurrently. Clones of
+    /// the client, and clients built with the same
+    /// [ClientBuilder::with_client](crate::ClientBuilder::with_client), share the pool. The
+    /// request counts towards the rate limit, its response is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Fails if no connection could be established.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new(Chain::Mainnet, "<your_api_key>")?;
+    /// // a failure only means the first request pays the handshake
+    /// let _ = client.warm_up().await;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn warm_up(&self) -> Result<()> {
+        self.rate_limiter.acquire().await;
+        trace!(target: "etherscan", "HEAD {}", self.etherscan_api_url);
+        self.client.head(self.etherscan_api_url.clone()).send().await.map_err(map_timeout)?;
+        Ok(())
+    }
+
+    /// Returns the number of the most recent block, using the `eth_blockNumber` proxy.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new(Chain::Mainnet, "<your_api_key>")?;
+    /// let head = client.get_block_number().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_block_number(&self) -> Result<u64> {
+        let query = self.create_query("proxy", "eth_blockNumber", serde_json::Value::Null);
+        let res: ProxyResponse<U64> = self.get_json_rpc(&query).await?;
+        let head = res.result.as_u64();
+        self.head_block.fetch_max(head, Ordering::Relaxed);
+        *self.head_block_fetched.lock().unwrap_or_else(|err| err.into_inner()) =
+            Some(Instant::now());
+        Ok(head)
+    }
+
+    /// Returns the chain the explorer indexes, using the `eth_chainId` proxy.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    /// # async fn foo() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new(Chain::Mainnet, "<your_api_key>")?;
+    /// assert_eq!(client.detect_chain().await?, Chain::Mainnet);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn detect_chain(&self) -> Result<Chain> {
+        let query = self.create_query("proxy", "eth_chainId", serde_json::Value::Null);
+        let res: ProxyResponse<U64> = self.get_json_rpc(&query).await?;
+        let id = res.result.as_u64();
+        Chain::try_from(id).map_err(|_| EtherscanError::UnknownChainId(id))
+    }
+
+    /// Verifies that the explorer indexes the chain this client was configured for.
+    ///
+    /// This catches e.g. a testnet API url used with a mainnet [Chain], which otherwise results
+    /// in confusing errors. Does nothing if no chain was configured.
+    pub async fn verify_chain(&self) -> Result<()> {
+        let expected = match self.chain {
+            Some(chain) => chain,
+            None => return Ok(()),
+        };
+        match self.detect_chain().await {
+            Ok(chain) if chain == expected => Ok(()),
+            Ok(chain) => Err(EtherscanError::ChainMismatch { expected, actual: chain.into() }),
+            Err(EtherscanError::UnknownChainId(actual)) => {
+                Err(EtherscanError::ChainMismatch { expected, actual })
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     /// Perform sanity checks on a response and deserialize it into a [Response].
     fn sanitize_response<T: DeserializeOwned>(&self, res: impl AsRef<str>) -> Result<Response<T>> {
         let res = res.as_ref();
-        let res: ResponseData<T> = serde_json::from_str(res).map_err(|err| {
+        self.sanitize_parsed(res, serde_json::from_str(res))
+    }
+
+    /// Like [Client::sanitize_response], optimized for the large responses of list endpoints.
+    pub(crate) fn sanitize_list_response<'a, T: Deserialize<'a>>(
+        &self,
+        res: &'a str,
+    ) -> Result<Response<T>> {
+        self.sanitize_parsed(res, from_list_json(res))
+    }
+
+    /// Maps a parsed response, or the failure to parse it, to the result of the request.
+    fn sanitize_parsed<T>(
+        &self,
+        res: &str,
+        parsed: serde_json::Result<ResponseData<T>>,
+    ) -> Result<Response<T>> {
+        let res: ResponseData<T> = parsed.map_err(|err| {
             error!(target: "etherscan", ?res, "Failed to deserialize response: {}", err);
             if res == "Page not found" {
                 EtherscanError::PageNotFound
@@ -213,6 +889,12 @@ impl Client {
             }
         })?;
 
+        let message = match &res {
+            ResponseData::Error { message, .. } => message,
+            ResponseData::Success(res) => &res.message,
+        };
+        *self.last_message.lock().unwrap_or_else(|err| err.into_inner()) = Some(message.clone());
+
         match res {
             ResponseData::Error { result, message, status } => {
                 if let Some(ref result) = result {
@@ -228,14 +910,19 @@ impl Client {
         }
     }
 
-    fn create_query<T: Serialize>(
+    /// Creates a [`Query`] for the given module and action, authenticated with the API key of
+    /// this client. `other` is serialized as additional query parameters.
+    pub fn create_query<T: Serialize>(
         &self,
         module: &'static str,
         action: &'static str,
         other: T,
-    ) -> Query<T> {
+    ) -> Query<'_, T> {
+        let apikey = self.api_key_param.as_deref().zip(self.api_key.as_deref());
         Query {
-            apikey: self.api_key.as_deref().map(Cow::Borrowed),
+            apikey: ApiKeyParam(
+                apikey.map(|(name, key)| (Cow::Borrowed(name), Cow::Borrowed(key))),
+            ),
             module: Cow::Borrowed(module),
             action: Cow::Borrowed(action),
             other,
@@ -243,28 +930,85 @@ impl Client {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+/// Configures a [Client], its `Debug` output redacts the API key.
+#[derive(Clone, Default)]
 pub struct ClientBuilder {
     /// Client that executes HTTP requests
     client: Option<reqwest::Client>,
     /// Etherscan API key
     api_key: Option<String>,
+    /// The query parameter carrying the API key, `Some(None)` to never send it, defaults to
+    /// `apikey`
+    api_key_param: Option<Option<String>>,
     /// Etherscan API endpoint like <https://api(-chain).etherscan.io/api>
     etherscan_api_url: Option<Url>,
     /// Etherscan base endpoint like <https://etherscan.io>
     etherscan_url: Option<Url>,
     /// Path to where ABI files should be cached
     cache: Option<Cache>,
+    /// How many blocks below the head a list range must end to be cached, defaults to
+    /// [DEFAULT_CACHE_CONFIRMATIONS]
+    cache_confirmations: Option<u64>,
+    /// The chain configured with [ClientBuilder::chain]
+    chain: Option<Chain>,
+    /// Which failed requests are retried
+    retry_policy: RetryPolicy,
+    /// Requests per second, defaults to the free tier limit of the chain's explorer
+    rate_limit: Option<u32>,
+    /// Timeouts of GET requests by action
+    endpoint_timeouts: HashMap<String, Duration>,
+    /// The maximum size of a response body
+    max_response_bytes: Option<usize>,
+    /// Whether gzip or brotli compressed responses are requested, defaults to `true`
+    compression: Option<bool>,
+    /// The number of jobs [Client::batch] runs at once, defaults to the rate limit
+    batch_concurrency: Option<usize>,
+    /// The API plan, endpoints aren't checked if unset
+    plan: Option<ApiPlan>,
+    /// Receives measurements of the requests
+    metrics: Option<Arc<dyn Metrics>>,
+    /// Whether address params are sent with their EIP-55 checksum, defaults to `false`
+    checksummed_addresses: bool,
+    /// The node queried when the explorer fails
+    #[cfg(feature = "providers")]
+    fallback_provider: Option<Arc<dyn fallback::FallbackProvider>>,
 }
 
 // === impl ClientBuilder ===
 
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("ClientBuilder");
+        debug
+            .field("client", &self.client)
+            .field("api_key", &redacted(&self.api_key))
+            .field("api_key_param", &self.api_key_param)
+            .field("etherscan_api_url", &self.etherscan_api_url)
+            .field("etherscan_url", &self.etherscan_url)
+            .field("cache", &self.cache)
+            .field("cache_confirmations", &self.cache_confirmations)
+            .field("chain", &self.chain)
+            .field("retry_policy", &self.retry_policy)
+            .field("rate_limit", &self.rate_limit)
+            .field("endpoint_timeouts", &self.endpoint_timeouts)
+            .field("max_response_bytes", &self.max_response_bytes)
+            .field("compression", &self.compression)
+            .field("batch_concurrency", &self.batch_concurrency)
+            .field("plan", &self.plan)
+            .field("metrics", &self.metrics)
+            .field("checksummed_addresses", &self.checksummed_addresses);
+        #[cfg(feature = "providers")]
+        debug.field("fallback_provider", &self.fallback_provider);
+        debug.finish()
+    }
+}
+
 impl ClientBuilder {
     /// Configures the etherscan url and api url for the given chain
     ///
     /// # Errors
     ///
-    /// Fails if the chain is not supported by etherscan
+    /// Fails with [EtherscanError::ChainNotSupported] if the chain is not supported by etherscan
     pub fn chain(self, chain: Chain) -> Result<Self> {
         fn urls(
             api: impl IntoUrl,
@@ -276,7 +1020,9 @@ impl ClientBuilder {
             .etherscan_urls()
             .map(|(api, base)| urls(api, base))
             .ok_or_else(|| EtherscanError::ChainNotSupported(chain))?;
-        self.with_api_url(etherscan_api_url?)?.with_url(etherscan_url?)
+        let mut this = self.with_api_url(etherscan_api_url?)?.with_url(etherscan_url?)?;
+        this.chain = Some(chain);
+        Ok(this)
     }
 
     /// Configures the etherscan url
@@ -306,17 +1052,136 @@ impl ClientBuilder {
     }
 
     /// Configures the etherscan api key
+    ///
+    /// An empty key fails [ClientBuilder::build], leave it unset for keyless requests.
     pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
-        self.api_key = Some(api_key.into()).filter(|s| !s.is_empty());
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Configures the query parameter the api key is sent in, for explorers that don't use
+    /// Etherscan's `apikey`, e.g. `apiKey`. With `None` the key is never sent, e.g. to keyless
+    /// self-hosted instances.
+    pub fn with_api_key_param(mut self, name: Option<&str>) -> Self {
+        self.api_key_param = Some(name.map(str::to_string));
         self
     }
 
     /// Configures cache for etherscan request
+    ///
+    /// Besides ABIs and source code, this also caches the responses of the account list endpoints
+    /// (`txlist`, `tokentx`, ...) whose `endBlock` is far enough below the current head that
+    /// they can no longer change, see [ClientBuilder::with_cache_confirmations].
     pub fn with_cache(mut self, cache_root: Option<PathBuf>, cache_ttl: Duration) -> Self {
         self.cache = cache_root.map(|root| Cache::new(root, cache_ttl));
         self
     }
 
+    /// Configures how many blocks a list range must end below the current head to be cached,
+    /// so that blocks which may still be reorged are never cached.
+    ///
+    /// Defaults to [DEFAULT_CACHE_CONFIRMATIONS], which covers finality on Ethereum mainnet.
+    pub fn with_cache_confirmations(mut self, confirmations: u64) -> Self {
+        self.cache_confirmations = Some(confirmations);
+        self
+    }
+
+    /// Configures which failed requests are retried, see [RetryPolicy]
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Configures the maximum number of requests per second, `0` disables rate limiting.
+    ///
+    /// Defaults to the limit of the configured plan, or the free tier limit of the explorer of
+    /// the configured chain, e.g. 5 for Etherscan.
+    pub fn with_rate_limit(mut self, requests_per_second: u32) -> Self {
+        self.rate_limit = Some(requests_per_second);
+        self
+    }
+
+    /// Configures the timeout of the requests for an action, e.g. `txlist`, overriding the
+    /// timeout of the `reqwest::Client`.
+    ///
+    /// Requests that time out fail with [EtherscanError::Timeout]. Per action timeouts are not
+    /// supported on wasm, where the `reqwest::Client` timeout always applies.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use ethers_core::types::Chain;
+    /// use ethers_etherscan::Client;
+    /// use std::time::Duration;
+    ///
+    /// let client = Client::builder()
+    ///     .chain(Chain::Mainnet)
+    ///     .unwrap()
+    ///     .with_endpoint_timeout("txlist", Duration::from_secs(60))
+    ///     .with_endpoint_timeout("balance", Duration::from_secs(5))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn with_endpoint_timeout(mut self, action: impl Into<String>, timeout: Duration) -> Self {
+        self.endpoint_timeouts.insert(action.into(), timeout);
+        self
+    }
+
+    /// Configures the maximum size of a response body in bytes, by default it's unlimited.
+    ///
+    /// Bodies are read in chunks and the request fails with [EtherscanError::ResponseTooLarge]
+    /// as soon as the limit is exceeded, so an endpoint can't exhaust memory with an enormous
+    /// response. This is useful with untrusted self-hosted explorers. On wasm the limit is only
+    /// checked against the `Content-Length` header before reading and the full body after.
+    pub fn with_max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = Some(max_response_bytes);
+        self
+    }
+
+    /// Configures whether responses are requested gzip or brotli compressed, which is enabled by
+    /// default.
+    ///
+    /// The JSON of list endpoints is verbose and compresses well, so this saves most of the
+    /// bandwidth of large `txlist` responses at the cost of decompressing them. On a generated
+    /// page of 1000 `txlist` rows (721 kB, random hashes and 50 counterparties), gzip reduces the
+    /// transferred bytes by 71% and brotli by 73% at quality 4, as used for on-the-fly
+    /// compression, up to 78% at quality 11. Uncompressed responses are accepted either way. On
+    /// wasm the browser negotiates the encoding, so this has no effect.
+    pub fn with_compression(mut self, compression: bool) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Configures how many jobs [Client::batch] runs at once.
+    ///
+    /// Defaults to the rate limit, so with a response time of about a second the limit is used
+    /// up without requests queueing in the rate limiter, or to 8 without rate limiting.
+    pub fn with_batch_concurrency(mut self, concurrency: usize) -> Self {
+        self.batch_concurrency = Some(concurrency);
+        self
+    }
+
+    /// Configures the API plan of the API key.
+    ///
+    /// GET requests to endpoints the plan doesn't include then fail with
+    /// [EtherscanError::PlanRequired] without being sent, and the rate limit defaults to the
+    /// plan's. Without a plan all endpoints are requested and the explorer rejects those the key
+    /// can't use.
+    pub fn with_plan(mut self, plan: ApiPlan) -> Self {
+        self.plan = Some(plan);
+        self
+    }
+
+    /// Sends the addresses in the params of the client's requests with their EIP-55 checksum, for
+    /// explorers that reject lowercase addresses.
+    ///
+    /// Etherscan accepts both, addresses are sent lowercase by default. Queries built with
+    /// [Client::create_query] are sent as they are.
+    pub fn with_checksummed_addresses(mut self, checksummed: bool) -> Self {
+        self.checksummed_addresses = checksummed;
+        self
+    }
+
     /// Returns a Client that uses this ClientBuilder configuration.
     ///
     /// # Errors
@@ -324,20 +1189,121 @@ impl ClientBuilder {
     /// If the following required fields are missing:
     ///   - `etherscan_api_url`
     ///   - `etherscan_url`
+    ///
+    /// Fails with [EtherscanError::MissingApiKey] if the api key is empty or only whitespace, and
+    /// with [EtherscanError::InvalidApiKey] if it contains control characters or surrounding
+    /// whitespace, e.g. a trailing newline read from a file. Neither is checked if the key is
+    /// never sent, see [ClientBuilder::with_api_key_param]. Without an api key the client sends
+    /// keyless requests.
     pub fn build(self) -> Result<Client> {
-        let ClientBuilder { client, api_key, etherscan_api_url, etherscan_url, cache } = self;
+        let ClientBuilder {
+            client,
+            api_key,
+            api_key_param,
+            etherscan_api_url,
+            etherscan_url,
+            cache,
+            cache_confirmations,
+            chain,
+            retry_policy,
+            rate_limit,
+            endpoint_timeouts,
+            max_response_bytes,
+            compression,
+            batch_concurrency,
+            plan,
+            metrics,
+            checksummed_addresses,
+            #[cfg(feature = "providers")]
+            fallback_provider,
+        } = self;
+        let rate_limit = rate_limit
+            .or_else(|| plan.map(ApiPlan::rate_limit))
+            .unwrap_or_else(|| default_rate_limit(chain));
+        let batch_concurrency = batch_concurrency.unwrap_or(match rate_limit {
+            0 => DEFAULT_UNLIMITED_BATCH_CONCURRENCY,
+            rate_limit => rate_limit as usize,
+        });
+        if !matches!(api_key_param, Some(None)) {
+            match api_key.as_deref() {
+                Some(key) if key.trim().is_empty() => return Err(EtherscanError::MissingApiKey),
+                Some(key) if is_malformed_api_key(key) => return Err(EtherscanError::InvalidApiKey),
+                _ => {}
+            }
+        }
 
         let client = Client {
             client: client.unwrap_or_default(),
             api_key,
+            api_key_param: api_key_param.unwrap_or_else(|| Some("apikey".to_string())),
             etherscan_api_url: etherscan_api_url
                 .ok_or_else(|| EtherscanError::Builder("etherscan api url".to_string()))?,
             etherscan_url: etherscan_url
                 .ok_or_else(|| EtherscanError::Builder("etherscan url".to_string()))?,
             cache,
+            head_block: Default::default(),
+            head_block_fetched: Default::default(),
+            cache_confirmations: cache_confirmations.unwrap_or(DEFAULT_CACHE_CONFIRMATIONS),
+            chain,
+            retry_policy,
+            rate_limiter: Arc::new(RateLimiter::new(rate_limit)),
+            endpoint_timeouts,
+            max_response_bytes,
+            compression: compression.unwrap_or(true),
+            batch_concurrency: batch_concurrency.max(1),
+            last_message: Default::default(),
+            plan,
+            metrics: metrics.or_else(default_metrics),
+            checksummed_addresses,
+            #[cfg(feature = "providers")]
+            fallback_provider,
+            #[cfg(feature = "pro")]
+            token_metadata: Default::default(),
         };
         Ok(client)
     }
+
+    /// Returns a Client that uses this ClientBuilder configuration, after verifying that the
+    /// explorer indexes the configured chain.
+    ///
+    /// See [Client::verify_chain].
+    ///
+    /// # Errors
+    ///
+    /// If [ClientBuilder::build] fails or the live chain id does not match the configured chain.
+    pub async fn build_verified(self) -> Result<Client> {
+        let client = self.build()?;
+        client.verify_chain().await?;
+        Ok(client)
+    }
+}
+
+/// Deserializes the potentially large response of a list endpoint.
+///
+/// The untagged [ResponseData] buffers the whole document before trying its variants, which
+/// makes up about a quarter of the time to parse a page of 10000 transactions. The common case is
+/// decoded into a [Response] directly instead, only failures are decoded again as [ResponseData].
+fn from_list_json<'a, T: Deserialize<'a>>(json: &'a str) -> serde_json::Result<ResponseData<T>> {
+    serde_json::from_str(json).map(ResponseData::Success).or_else(|_| serde_json::from_str(json))
+}
+
+/// Like [from_list_json], for types that don't borrow from the response.
+///
+/// With the `simd-json` feature these are parsed with `simd-json`, which parses a copy of the
+/// response in place. Only the common case is, a response that fails to parse is decoded again
+/// with [from_list_json], so the errors are the same with and without the feature. See the
+/// `parse_list` benchmark for how the parsers compare.
+fn from_owned_list_json<T: DeserializeOwned>(json: &str) -> serde_json::Result<ResponseData<T>> {
+    #[cfg(feature = "simd-json")]
+    if let Ok(res) = simd_json::serde::from_slice(&mut json.as_bytes().to_vec()) {
+        return Ok(ResponseData::Success(res))
+    }
+    from_list_json(json)
+}
+
+/// Returns whether the key can't be valid for any explorer.
+fn is_malformed_api_key(key: &str) -> bool {
+    key.trim() != key || key.chars().any(char::is_control)
 }
 
 /// A wrapper around an Etherscan cache object with an expiry
@@ -360,23 +1326,40 @@ impl Cache {
     }
 
     fn get_abi(&self, address: Address) -> Option<Option<ethers_core::abi::Abi>> {
-        self.get("abi", address)
+        self.get("abi", &format!("{address:?}"))
     }
 
     fn set_abi(&self, address: Address, abi: Option<&Abi>) {
-        self.set("abi", address, abi)
+        self.set("abi", &format!("{address:?}"), abi)
     }
 
     fn get_source(&self, address: Address) -> Option<Option<ContractMetadata>> {
-        self.get("sources", address)
+        self.get("sources", &format!("{address:?}"))
     }
 
     fn set_source(&self, address: Address, source: Option<&ContractMetadata>) {
-        self.set("sources", address, source)
+        self.set("sources", &format!("{address:?}"), source)
+    }
+
+    fn get_list_response(&self, key: &str) -> Option<String> {
+        self.get("lists", key)
+    }
+
+    fn set_list_response(&self, key: &str, response: &str) {
+        // unlike the other prefixes, list responses are opt-in so the dir may not exist yet
+        let _ = std::fs::create_dir_all(self.root.join("lists"));
+        self.set("lists", key, response)
     }
 
-    fn set<T: Serialize>(&self, prefix: &str, address: Address, item: T) {
-        let path = self.root.join(prefix).join(format!("{address:?}.json"));
+    fn clear_list_responses(&self) -> std::io::Result<()> {
+        match std::fs::remove_dir_all(self.root.join("lists")) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    fn set<T: Serialize>(&self, prefix: &str, name: &str, item: T) {
+        let path = self.root.join(prefix).join(format!("{name}.json"));
         let writer = std::fs::File::create(path).ok().map(std::io::BufWriter::new);
         if let Some(mut writer) = writer {
             let _ = serde_json::to_writer(
@@ -395,8 +1378,8 @@ impl Cache {
         }
     }
 
-    fn get<T: DeserializeOwned>(&self, prefix: &str, address: Address) -> Option<T> {
-        let path = self.root.join(prefix).join(format!("{address:?}.json"));
+    fn get<T: DeserializeOwned>(&self, prefix: &str, name: &str) -> Option<T> {
+        let path = self.root.join(prefix).join(format!("{name}.json"));
         let reader = std::io::BufReader::new(std::fs::File::open(path).ok()?);
         if let Ok(inner) = serde_json::from_reader::<_, CacheEnvelope<T>>(reader) {
             // If this does not return None then we have passed the expiry
@@ -423,6 +1406,42 @@ pub struct Response<T> {
     pub result: T,
 }
 
+/// A `result` that is either a single value or a list of them
+///
+/// Endpoints of the same module answer in different shapes, e.g. `account/balance` returns a
+/// scalar string while `account/balancemulti` returns an array of objects. A
+/// `Response<OneOrMany<T>>` accepts both, which helps when calling endpoints through
+/// [Client::get_json] or explorers that differ from Etherscan.
+///
+/// Etherscan returns single values from `balance`, `tokenbalance`, `getabi`, `ethprice` and
+/// `getblocknobytime`, and arrays from `balancemulti`, `txlist`, `txlistinternal`, `tokentx`,
+/// `getsourcecode`, `getcontractcreation` and `getLogs`.
+///
+/// Arrays are tried first, so a `T` that is itself deserialized from an array, like
+/// [serde_json::Value], is split into its elements.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    Many(Vec<T>),
+    One(T),
+}
+
+impl<T> OneOrMany<T> {
+    /// Returns the values as a list, a single value becoming a list of one.
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::Many(values) => values,
+            OneOrMany::One(value) => vec![value],
+        }
+    }
+}
+
+impl<T> From<OneOrMany<T>> for Vec<T> {
+    fn from(values: OneOrMany<T>) -> Self {
+        values.into_vec()
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum ResponseData<T> {
@@ -430,17 +1449,197 @@ pub enum ResponseData<T> {
     Error { status: String, message: String, result: Option<String> },
 }
 
+/// The JSON-RPC response type of the `proxy` module
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProxyResponse<T> {
+    pub jsonrpc: String,
+    pub id: u64,
+    pub result: T,
+}
+
+/// The error object of a failed JSON-RPC response
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProxyError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ProxyResponseData<T> {
+    Error { jsonrpc: String, id: u64, error: ProxyError },
+    Success(ProxyResponse<T>),
+}
+
 /// The type that gets serialized as query
-#[derive(Clone, Debug, Serialize)]
-struct Query<'a, T: Serialize> {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    apikey: Option<Cow<'a, str>>,
+///
+/// Created with [`Client::create_query`] and sent with [`Client::get_json`]. Its `Debug` output
+/// redacts the API key.
+#[derive(Clone, Serialize)]
+pub struct Query<'a, T: Serialize> {
+    #[serde(flatten)]
+    apikey: ApiKeyParam<'a>,
     module: Cow<'a, str>,
     action: Cow<'a, str>,
     #[serde(flatten)]
     other: T,
 }
 
+impl<'a, T: Serialize + fmt::Debug> fmt::Debug for Query<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Query")
+            .field("apikey", &redacted(&self.apikey.0))
+            .field("module", &self.module)
+            .field("action", &self.action)
+            .field("other", &self.other)
+            .finish()
+    }
+}
+
+/// The name and value of the API key parameter of a [Query], serialized as a map so it can be
+/// flattened into the query with any name
+#[derive(Clone)]
+struct ApiKeyParam<'a>(Option<(Cow<'a, str>, Cow<'a, str>)>);
+
+impl Serialize for ApiKeyParam<'_> {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+        if let Some((name, key)) = &self.0 {
+            map.serialize_entry(name, key)?;
+        }
+        map.end()
+    }
+}
+
+impl<'a, T: Serialize> Query<'a, T> {
+    /// The API module, e.g. `account`
+    pub fn module(&self) -> &str {
+        &self.module
+    }
+
+    /// The API action, e.g. `txlist`
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    /// The additional query parameters
+    pub fn params(&self) -> &T {
+        &self.other
+    }
+}
+
+/// Returns the result of a response with the given status and message, or the error it reports.
+///
+/// Status `"1"` is a success, as is status `"0"` with the `No transactions found` or `No records
+/// found` messages of list endpoints, whose result is the empty list. For other failures the
+/// message is mapped to [EtherscanError::RateLimitExceeded] or [EtherscanError::InvalidApiKey] if
+/// it's known and to [EtherscanError::Message] otherwise, including the generic `NOTOK`. Other
+/// statuses are a [EtherscanError::BadStatusCode].
+pub(crate) fn check_ok<T>(status: &str, message: &str, result: T) -> Result<T> {
+    check_status(status, message, None)?;
+    Ok(result)
+}
+
+/// Like [check_ok] for endpoints with a string result, which holds the reason of a failure next
+/// to a `NOTOK` message.
+pub(crate) fn check_string_ok(status: &str, message: &str, result: String) -> Result<String> {
+    check_status(status, message, Some(&result))?;
+    Ok(result)
+}
+
+/// Fails with the error reported by a response's status and message, see [check_ok], preferring
+/// `reason` over the message if it isn't empty.
+fn check_status(status: &str, message: &str, reason: Option<&str>) -> Result<()> {
+    match status {
+        "1" => return Ok(()),
+        "0" => {}
+        status => return Err(EtherscanError::BadStatusCode(status.to_string())),
+    }
+    if message.starts_with("No transactions found") || message.starts_with("No records found") {
+        return Ok(())
+    }
+    let reason = reason.filter(|reason| !reason.is_empty()).unwrap_or(message);
+    if reason.starts_with("Max rate limit reached") {
+        Err(EtherscanError::RateLimitExceeded)
+    } else if reason.eq_ignore_ascii_case("invalid api key") {
+        Err(EtherscanError::InvalidApiKey)
+    } else {
+        Err(EtherscanError::Message(reason.to_string()))
+    }
+}
+
+/// Maps timed out requests to [EtherscanError::Timeout].
+fn map_timeout(err: reqwest::Error) -> EtherscanError {
+    if err.is_timeout() {
+        EtherscanError::Timeout
+    } else {
+        err.into()
+    }
+}
+
+/// Returns the body of a response, or an error if its HTTP status isn't a success.
+///
+/// Cloudflare blocks and missing pages are recognized from the body, other failures, like a
+/// gateway error or `429 Too Many Requests`, are an [EtherscanError::HttpStatus].
+fn check_http_status(status: reqwest::StatusCode, body: String) -> Result<String> {
+    if status.is_success() {
+        return Ok(body)
+    }
+    error!(target: "etherscan", ?body, "Request failed with HTTP status {}", status);
+    Err(if body == "Page not found" {
+        EtherscanError::PageNotFound
+    } else if is_blocked_by_cloudflare_response(&body) {
+        EtherscanError::BlockedByCloudflare
+    } else if is_cloudflare_security_challenge(&body) {
+        EtherscanError::CloudFlareSecurityChallenge
+    } else {
+        EtherscanError::HttpStatus(status.as_u16())
+    })
+}
+
+/// The default [ClientBuilder::with_batch_concurrency] of clients without rate limiting
+const DEFAULT_UNLIMITED_BATCH_CONCURRENCY: usize = 8;
+
+/// The default [ClientBuilder::with_cache_confirmations], two epochs on Ethereum mainnet
+pub const DEFAULT_CACHE_CONFIRMATIONS: u64 = 64;
+
+/// How long the head block is reused to decide whether a list range is closed
+const HEAD_BLOCK_MAX_AGE: Duration = Duration::from_secs(12);
+
+/// The maximum of `page * offset` Etherscan accepts for list queries
+pub(crate) const MAX_RESULT_WINDOW: u64 = 10_000;
+
+/// Returns the `page` and `offset` of list params, if both are set.
+fn result_window(params: &HashMap<&'static str, String>) -> Option<(u64, u64)> {
+    let param = |name| params.get(name).and_then(|v: &String| v.parse::<u64>().ok());
+    Some((param("page")?, param("offset")?))
+}
+
+/// Returns the cache key of a list query.
+///
+/// The key covers the module, action, queried address(es) and the pagination params.
+fn list_cache_key(query: &Query<'_, HashMap<&'static str, String>>) -> String {
+    let param = |name| query.other.get(name).map(String::as_str).unwrap_or_default();
+    [
+        query.module.as_ref(),
+        query.action.as_ref(),
+        param("address"),
+        param("contractaddress"),
+        param("txhash"),
+        param("startBlock"),
+        param("endBlock"),
+        param("page"),
+        param("offset"),
+        param("sort"),
+    ]
+    .join("-")
+}
+
 /// Ensures that the url is well formatted to be used by the Client's functions that join paths.
 fn ensure_url(url: impl IntoUrl) -> std::result::Result<Url, reqwest::Error> {
     let url_str = url.as_str();
@@ -462,8 +1661,14 @@ fn into_url(url: impl IntoUrl) -> std::result::Result<Url, reqwest::Error> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Client, EtherscanError, ResponseData};
-    use ethers_core::types::{Address, Chain, H256};
+    use crate::{
+        account::{Sort, TxListParams},
+        check_ok, check_string_ok, from_list_json, list_cache_key,
+        mock::{query_param, MockResponse, MockServer},
+        Cache, Client, EtherscanError, OneOrMany, ProxyResponseData, Response, ResponseData,
+    };
+    use ethers_core::types::{Address, Chain, H256, U64};
+    use std::{collections::HashMap, time::Duration};
 
     // <https://github.com/foundry-rs/foundry/issues/4406>
     #[test]
@@ -473,9 +1678,106 @@ mod tests {
         assert!(matches!(resp, ResponseData::Error { .. }));
     }
 
+    #[test]
+    fn can_check_ok() {
+        assert_eq!(check_string_ok("1", "OK", "10".to_string()).unwrap(), "10");
+        assert!(check_ok("0", "No transactions found", Vec::<u64>::new()).unwrap().is_empty());
+
+        let err = check_string_ok("0", "NOTOK", "Max rate limit reached".to_string()).unwrap_err();
+        assert!(matches!(err, EtherscanError::RateLimitExceeded));
+        let err = check_string_ok("0", "NOTOK", "Invalid API Key".to_string()).unwrap_err();
+        assert!(matches!(err, EtherscanError::InvalidApiKey));
+        let err =
+            check_string_ok("0", "NOTOK", "Error! Invalid address format".to_string()).unwrap_err();
+        assert!(
+            matches!(err, EtherscanError::Message(reason) if reason == "Error! Invalid address format")
+        );
+        let err = check_string_ok("0", "NOTOK", String::new()).unwrap_err();
+        assert!(matches!(err, EtherscanError::Message(reason) if reason == "NOTOK"));
+        let err = check_ok("0", "NOTOK", vec![1u64]).unwrap_err();
+        assert!(matches!(err, EtherscanError::Message(reason) if reason == "NOTOK"));
+        let err = check_ok("0", "Max rate limit reached", ()).unwrap_err();
+        assert!(matches!(err, EtherscanError::RateLimitExceeded));
+        let err = check_ok("2", "OK", ()).unwrap_err();
+        assert!(matches!(err, EtherscanError::BadStatusCode(status) if status == "2"));
+    }
+
+    #[test]
+    fn list_json_matches_response_data() {
+        let ok = r#"{"status":"1","message":"OK","result":["a"]}"#;
+        let resp: ResponseData<Vec<String>> = from_list_json(ok).unwrap();
+        assert!(matches!(resp, ResponseData::Success(Response { result, .. }) if result == ["a"]));
+
+        let err = r#"{"status":"0","message":"NOTOK","result":"Max rate limit reached"}"#;
+        let resp: ResponseData<Vec<String>> = from_list_json(err).unwrap();
+        assert!(matches!(resp, ResponseData::Error { result: Some(_), .. }));
+
+        assert!(from_list_json::<Vec<String>>("<html>").is_err());
+    }
+
+    #[test]
+    fn can_parse_one_or_many_results() {
+        let one = r#"{"status":"1","message":"OK","result":"40891626854930000000000"}"#;
+        let resp: Response<OneOrMany<String>> = serde_json::from_str(one).unwrap();
+        assert_eq!(resp.result.into_vec(), ["40891626854930000000000"]);
+
+        let many = r#"{"status":"1","message":"OK","result":[{"account":"0x1","balance":"1"},{"account":"0x2","balance":"2"}]}"#;
+        let resp: Response<OneOrMany<HashMap<String, String>>> =
+            serde_json::from_str(many).unwrap();
+        let balances: Vec<_> = resp.result.into();
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances[1]["balance"], "2");
+
+        let empty: OneOrMany<String> = serde_json::from_str("[]").unwrap();
+        assert_eq!(empty, OneOrMany::Many(vec![]));
+        assert!(serde_json::from_str::<OneOrMany<u64>>(r#""a""#).is_err());
+    }
+
+    #[test]
+    fn can_parse_proxy_response() {
+        let ok = r#"{"jsonrpc":"2.0","id":83,"result":"0x10d4f"}"#;
+        let resp: ProxyResponseData<U64> = serde_json::from_str(ok).unwrap();
+        assert!(matches!(resp, ProxyResponseData::Success(res) if res.result == 0x10d4f.into()));
+
+        let err = r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32602,"message":"invalid argument 0: hex string has length 4, want 64 for common.Hash"}}"#;
+        let resp: ProxyResponseData<Option<U64>> = serde_json::from_str(err).unwrap();
+        assert!(matches!(resp, ProxyResponseData::Error { error, .. } if error.code == -32602));
+    }
+
+    #[tokio::test]
+    async fn can_get_block_number() {
+        let server = MockServer::start(vec![MockResponse::json(
+            r#"{"jsonrpc":"2.0","id":83,"result":"0x10d4f"}"#,
+        )])
+        .await;
+
+        assert_eq!(server.client().get_block_number().await.unwrap(), 0x10d4f);
+        assert_eq!(query_param(&server.requests()[0], "action"), Some("eth_blockNumber"));
+    }
+
+    #[tokio::test]
+    async fn applies_endpoint_timeouts() {
+        let slow = MockResponse {
+            delay: Duration::from_millis(300),
+            ..MockResponse::json(r#"{"status":"1","message":"OK","result":"1"}"#)
+        };
+        let server = MockServer::start_with(vec![slow], |builder| {
+            builder.with_endpoint_timeout("gasestimate", Duration::from_millis(50))
+        })
+        .await;
+
+        let err = server.client().gas_estimate(1.into()).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::Timeout));
+
+        // other actions only use the reqwest client timeout
+        let query =
+            server.client().create_query("stats", "ethprice", HashMap::<&str, String>::new());
+        server.client().get_json::<String, _>(&query).await.unwrap();
+    }
+
     #[test]
     fn test_api_paths() {
-        let client = Client::new(Chain::Goerli, "").unwrap();
+        let client = Client::new(Chain::Goerli, "API_KEY").unwrap();
         assert_eq!(client.etherscan_api_url.as_str(), "https://api-goerli.etherscan.io/api/");
 
         assert_eq!(client.block_url(100), "https://goerli.etherscan.io/block/100");
@@ -483,7 +1785,7 @@ mod tests {
 
     #[test]
     fn stringifies_block_url() {
-        let etherscan = Client::new(Chain::Mainnet, "").unwrap();
+        let etherscan = Client::new(Chain::Mainnet, "API_KEY").unwrap();
         let block: u64 = 1;
         let block_url: String = etherscan.block_url(block);
         assert_eq!(block_url, format!("https://etherscan.io/block/{block}"));
@@ -491,7 +1793,7 @@ mod tests {
 
     #[test]
     fn stringifies_address_url() {
-        let etherscan = Client::new(Chain::Mainnet, "").unwrap();
+        let etherscan = Client::new(Chain::Mainnet, "API_KEY").unwrap();
         let addr: Address = Address::zero();
         let address_url: String = etherscan.address_url(addr);
         assert_eq!(address_url, format!("https://etherscan.io/address/{addr:?}"));
@@ -499,7 +1801,7 @@ mod tests {
 
     #[test]
     fn stringifies_transaction_url() {
-        let etherscan = Client::new(Chain::Mainnet, "").unwrap();
+        let etherscan = Client::new(Chain::Mainnet, "API_KEY").unwrap();
         let tx_hash = H256::zero();
         let tx_url: String = etherscan.transaction_url(tx_hash);
         assert_eq!(tx_url, format!("https://etherscan.io/tx/{tx_hash:?}"));
@@ -507,15 +1809,444 @@ mod tests {
 
     #[test]
     fn stringifies_token_url() {
-        let etherscan = Client::new(Chain::Mainnet, "").unwrap();
+        let etherscan = Client::new(Chain::Mainnet, "API_KEY").unwrap();
         let token_hash = Address::zero();
         let token_url: String = etherscan.token_url(token_hash);
         assert_eq!(token_url, format!("https://etherscan.io/token/{token_hash:?}"));
     }
 
+    #[test]
+    fn can_build_custom_query() {
+        let client = Client::new(Chain::Mainnet, "key").unwrap();
+        let query = client.create_query(
+            "account",
+            "balance",
+            HashMap::from([("address", "0x0000000000000000000000000000000000000000")]),
+        );
+        assert_eq!(query.module(), "account");
+        assert_eq!(query.action(), "balance");
+
+        let url = client.query_url(&query).unwrap();
+        assert_eq!(url.path(), "/api/");
+        assert_eq!(
+            url.query(),
+            Some("apikey=key&module=account&action=balance&address=0x0000000000000000000000000000000000000000")
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_too_large_result_window() {
+        let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+        let query = client.create_query(
+            "account",
+            "txlist",
+            HashMap::from([("page", "3".to_string()), ("offset", "5000".to_string())]),
+        );
+        let err = client.get_list_json::<serde_json::Value>(&query).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::ResultWindowTooLarge { page: 3, offset: 5000 }));
+    }
+
+    #[test]
+    fn can_rename_api_key_param() {
+        let builder = Client::builder().with_api_key("key").chain(Chain::Mainnet).unwrap();
+        let client = builder.clone().with_api_key_param(Some("apiKey")).build().unwrap();
+        let query = client.create_query("stats", "ethprice", HashMap::<&str, &str>::new());
+        let url = client.query_url(&query).unwrap();
+        assert_eq!(url.query(), Some("apiKey=key&module=stats&action=ethprice"));
+
+        let client = builder.with_api_key_param(None).build().unwrap();
+        let query = client.create_query("stats", "ethprice", HashMap::<&str, &str>::new());
+        let url = client.query_url(&query).unwrap();
+        assert_eq!(url.query(), Some("module=stats&action=ethprice"));
+    }
+
+    #[tokio::test]
+    async fn can_checksum_address_params() {
+        let address: Address = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse().unwrap();
+        let address_param = |checksummed: bool| async move {
+            let server = MockServer::start_with(
+                vec![MockResponse::json(r#"{"status":"1","message":"OK","result":"0"}"#)],
+                |builder| builder.with_checksummed_addresses(checksummed),
+            )
+            .await;
+            server.client().get_ether_balance_single(&address, None).await.unwrap();
+            query_param(&server.requests()[0], "address").unwrap().to_string()
+        };
+
+        assert_eq!(address_param(true).await, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+        assert_eq!(address_param(false).await, "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+    }
+
+    #[test]
+    fn encodes_reserved_characters_in_api_key() {
+        let client = Client::new(Chain::Mainnet, "AB+C/D=E&F").unwrap();
+        let query = client.create_query("stats", "ethprice", HashMap::from([("tag", "a b")]));
+        let url = client.query_url(&query).unwrap();
+        assert_eq!(
+            url.query(),
+            Some("apikey=AB%2BC%2FD%3DE%26F&module=stats&action=ethprice&tag=a+b")
+        );
+        let key = url.query_pairs().find(|(name, _)| name == "apikey").unwrap().1;
+        assert_eq!(key, "AB+C/D=E&F");
+    }
+
+    #[test]
+    fn rejects_malformed_api_key() {
+        for key in ["ABC\n", " ABC", "A\u{0}BC"] {
+            let err = Client::new(Chain::Mainnet, key).unwrap_err();
+            assert!(matches!(err, EtherscanError::InvalidApiKey), "{key:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_missing_api_key() {
+        for key in ["", " ", "\t\n"] {
+            let err = Client::new(Chain::Mainnet, key).unwrap_err();
+            assert!(matches!(err, EtherscanError::MissingApiKey), "{key:?}");
+        }
+
+        let builder = Client::builder().with_api_key("").chain(Chain::Mainnet).unwrap();
+        assert!(builder.clone().with_api_key_param(None).build().is_ok());
+        assert!(Client::builder().chain(Chain::Mainnet).unwrap().build().is_ok());
+        assert!(Client::new_from_env(Chain::XDai).is_ok());
+    }
+
+    #[tokio::test]
+    async fn limits_response_size() {
+        let body = r#"{"status":"1","message":"OK","result":"40891626854930000000000"}"#;
+        let start = |limit| {
+            MockServer::start_with(vec![MockResponse::json(body)], move |builder| {
+                builder.with_max_response_bytes(limit)
+            })
+        };
+        let address = Address::repeat_byte(1);
+
+        let server = start(body.len()).await;
+        server.client().get_ether_balance_single(&address, None).await.unwrap();
+
+        let server = start(body.len() - 1).await;
+        let err = server.client().get_ether_balance_single(&address, None).await.unwrap_err();
+        assert!(
+            matches!(err, EtherscanError::ResponseTooLarge { limit } if limit == body.len() - 1)
+        );
+    }
+
+    #[test]
+    fn returns_configured_chain_id() {
+        let client = Client::new(Chain::Polygon, "ABCDEFG").unwrap();
+        assert_eq!(client.chain_id(), Some(137));
+
+        let client = Client::builder()
+            .with_api_url("https://api.example.com/api")
+            .unwrap()
+            .with_url("https://example.com")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(client.chain_id(), None);
+    }
+
+    #[tokio::test]
+    async fn keeps_last_message() {
+        let server = MockServer::start(vec![
+            MockResponse::json(r#"{"status":"1","message":"OK","result":"1"}"#),
+            MockResponse::json(crate::fixtures::empty_list()),
+        ])
+        .await;
+        let client = server.client();
+        let address = Address::repeat_byte(1);
+        assert_eq!(client.last_message(), None);
+
+        client.get_ether_balance_single(&address, None).await.unwrap();
+        assert_eq!(client.last_message().as_deref(), Some("OK"));
+
+        client.get_transactions(&address, None).await.unwrap();
+        assert_eq!(client.clone().last_message().as_deref(), Some("No transactions found"));
+    }
+
+    #[tokio::test]
+    async fn decompresses_brotli_responses() {
+        let balance = "1".repeat(1000);
+        let body = &format!(r#"{{"status":"1","message":"OK","result":"{balance}"}}"#);
+        let address = Address::repeat_byte(1);
+
+        assert!(MockResponse::brotli(body).body.len() < body.len());
+        let server = MockServer::start(vec![MockResponse::brotli(body)]).await;
+        let res = server.client().get_ether_balance_single(&address, None).await.unwrap();
+        assert_eq!(res.balance, balance);
+
+        let server =
+            MockServer::start_with(vec![MockResponse::brotli(body)], |b| b.with_compression(false))
+                .await;
+        server.client().get_ether_balance_single(&address, None).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn decompresses_gzip_responses() {
+        let balance = "1".repeat(1000);
+        let body = &format!(r#"{{"status":"1","message":"OK","result":"{balance}"}}"#);
+        let address = Address::repeat_byte(1);
+
+        let server = MockServer::start(vec![MockResponse::gzip(body)]).await;
+        let res = server.client().get_ether_balance_single(&address, None).await.unwrap();
+        assert_eq!(res.balance, balance);
+
+        // the mock only serves the compressed body if the request accepts it
+        let server =
+            MockServer::start_with(vec![MockResponse::gzip(body)], |b| b.with_compression(false))
+                .await;
+        server.client().get_ether_balance_single(&address, None).await.unwrap_err();
+
+        // the limit applies to the decompressed body
+        assert!(MockResponse::gzip(body).body.len() < body.len() - 1);
+        let server = MockServer::start_with(vec![MockResponse::gzip(body)], |builder| {
+            builder.with_max_response_bytes(body.len() - 1)
+        })
+        .await;
+        let err = server.client().get_ether_balance_single(&address, None).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::ResponseTooLarge { .. }));
+    }
+
+    #[tokio::test]
+    async fn batch_limits_concurrency() {
+        use futures_util::StreamExt;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let server =
+            MockServer::start_with(vec![], |builder| builder.with_batch_concurrency(2)).await;
+        let (running, max_running) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let jobs = (0..6).map(|i| {
+            let (running, max_running) = (&running, &max_running);
+            move |_: Client| async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now, Ordering::SeqCst);
+                // later jobs finish first
+                tokio::time::sleep(Duration::from_millis(30 - 5 * i)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                match i {
+                    3 => Err(EtherscanError::Timeout),
+                    i => Ok(i),
+                }
+            }
+        });
+
+        let results: Vec<_> = server.client().batch(jobs).collect().await;
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+        let results: Vec<_> = results.into_iter().map(|r| r.ok()).collect();
+        assert_eq!(results, [Some(0), Some(1), Some(2), None, Some(4), Some(5)]);
+    }
+
+    #[test]
+    fn debug_redacts_api_key() {
+        let key = "SECRET1234567890";
+        let builder = Client::builder().with_api_key(key).chain(Chain::Mainnet).unwrap();
+        assert!(!format!("{builder:?}").contains(key));
+        let client = builder.build().unwrap();
+        let debug = format!("{client:?}");
+        assert!(!debug.contains(key));
+        assert!(debug.contains(r#"api_key: Some("***")"#));
+        let query = client.create_query("account", "balance", HashMap::from([("tag", "latest")]));
+        let debug = format!("{query:?}");
+        assert!(!debug.contains(key));
+        assert!(debug.contains("balance"));
+    }
+
+    #[tokio::test]
+    async fn rejected_api_key_is_invalid_api_key() {
+        let server = MockServer::start(vec![MockResponse::json(
+            r#"{"status":"0","message":"NOTOK","result":"Invalid API Key"}"#,
+        )])
+        .await;
+        let err = server.client().gas_oracle().await.unwrap_err();
+        assert!(matches!(err, EtherscanError::InvalidApiKey));
+    }
+
+    #[tokio::test]
+    async fn fails_on_http_status() {
+        let response = |status, body: &str| MockResponse {
+            status,
+            content_type: "text/html",
+            ..MockResponse::json(body)
+        };
+        let address = Address::repeat_byte(1);
+        let balance = |response| async move {
+            let server = MockServer::start(vec![response]).await;
+            server.client().get_ether_balance_single(&address, None).await.unwrap_err()
+        };
+
+        let err = balance(response(502, "<html>502 Bad Gateway</html>")).await;
+        assert!(matches!(err, EtherscanError::HttpStatus(502)), "{err:?}");
+        let err = balance(response(429, "Too Many Requests")).await;
+        assert!(matches!(err, EtherscanError::HttpStatus(429)), "{err:?}");
+        let err = balance(response(403, "<p>Sorry, you have been blocked</p>")).await;
+        assert!(matches!(err, EtherscanError::BlockedByCloudflare), "{err:?}");
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn traces_requests_without_api_key() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Output(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Output {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let output = Output::default();
+        let writer = output.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let server = MockServer::start(vec![MockResponse::json(
+            r#"{"status":"1","message":"OK","result":"1"}"#,
+        )])
+        .await;
+        server.client().get_ether_balance_single(&Address::repeat_byte(1), None).await.unwrap();
+
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("Received response"), "{output}");
+        assert!(output.contains("module=\"account\" action=\"balance\""), "{output}");
+        assert!(!output.contains("MOCK_KEY"), "{output}");
+    }
+
+    #[tokio::test]
+    async fn can_warm_up() {
+        let server = MockServer::start(vec![MockResponse {
+            status: 405,
+            content_type: "text/html",
+            body: Vec::new(),
+            content_encoding: None,
+            delay: Duration::ZERO,
+        }])
+        .await;
+        server.client().warm_up().await.unwrap();
+        assert!(server.requests()[0].starts_with("HEAD /api/ "));
+
+        let client = Client::builder()
+            .with_api_url("http://127.0.0.1:1/api")
+            .unwrap()
+            .with_url("http://localhost")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(client.warm_up().await.is_err());
+    }
+
+    #[test]
+    fn unsupported_chain_is_returned() {
+        let err = Client::new(Chain::MoonbeamDev, "").unwrap_err();
+        assert!(matches!(err, EtherscanError::ChainNotSupported(Chain::MoonbeamDev)));
+
+        let err = Client::new_cached(Chain::Morden, "", None, Duration::ZERO).unwrap_err();
+        assert!(matches!(err, EtherscanError::ChainNotSupported(Chain::Morden)));
+
+        let err = Client::new_from_env(Chain::MoonbeamDev).unwrap_err();
+        assert!(matches!(err, EtherscanError::ChainNotSupported(Chain::MoonbeamDev)));
+    }
+
     #[test]
     fn local_networks_not_supported() {
         let err = Client::new_from_env(Chain::Dev).unwrap_err();
         assert!(matches!(err, EtherscanError::LocalNetworksNotSupported));
     }
+
+    #[test]
+    fn can_cache_list_responses() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let cache = Cache::new(tempdir.path().to_path_buf(), Duration::from_secs(60));
+        let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+        let params = HashMap::from([
+            ("address", format!("{:?}", Address::zero())),
+            ("startBlock", "0".to_string()),
+            ("endBlock", "100".to_string()),
+            ("page", "1".to_string()),
+            ("offset", "10".to_string()),
+            ("sort", "asc".to_string()),
+        ]);
+        let key = list_cache_key(&client.create_query("account", "txlist", params));
+        assert_eq!(
+            key,
+            "account-txlist-0x0000000000000000000000000000000000000000---0-100-1-10-asc"
+        );
+
+        assert_eq!(cache.get_list_response(&key), None);
+        cache.set_list_response(&key, r#"{"status":"1","message":"OK","result":[]}"#);
+        assert_eq!(
+            cache.get_list_response(&key).as_deref(),
+            Some(r#"{"status":"1","message":"OK","result":[]}"#)
+        );
+
+        cache.clear_list_responses().unwrap();
+        assert_eq!(cache.get_list_response(&key), None);
+        // clearing an empty cache is fine
+        cache.clear_list_responses().unwrap();
+    }
+
+    #[tokio::test]
+    async fn caches_list_ranges_below_the_confirmations() {
+        let empty = || MockResponse::json(r#"{"status":"1","message":"OK","result":[]}"#);
+        let tempdir = tempfile::tempdir().unwrap();
+        let server = MockServer::start_with(
+            vec![
+                MockResponse::json(r#"{"jsonrpc":"2.0","id":1,"result":"0x64"}"#),
+                empty(),
+                empty(),
+                empty(),
+            ],
+            |builder| {
+                builder
+                    .with_cache(Some(tempdir.path().to_path_buf()), Duration::from_secs(60))
+                    .with_cache_confirmations(10)
+            },
+        )
+        .await;
+
+        let address = Address::zero();
+        let params = |end_block: u64| Some(TxListParams::new(0, end_block, 1, 10, Sort::Asc));
+        for end_block in [89, 89, 90, 90] {
+            server.client().get_transactions(&address, params(end_block)).await.unwrap();
+        }
+
+        // the head is fetched once, block 90 is still within the confirmations of head 100
+        let requests = server.requests();
+        let requests: Vec<_> = requests
+            .iter()
+            .map(|r| (query_param(r, "action").unwrap(), query_param(r, "endBlock")))
+            .collect();
+        assert_eq!(
+            requests,
+            [
+                ("eth_blockNumber", None),
+                ("txlist", Some("89")),
+                ("txlist", Some("90")),
+                ("txlist", Some("90"))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn skips_the_head_block_without_cache() {
+        let server = MockServer::start(vec![MockResponse::json(
+            r#"{"status":"1","message":"OK","result":[]}"#,
+        )])
+        .await;
+
+        let params = TxListParams::new(0, 89, 1, 10, Sort::Asc);
+        server.client().get_transactions(&Address::zero(), Some(params)).await.unwrap();
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(query_param(&requests[0], "action"), Some("txlist"));
+    }
 }
@@ -0,0 +1,1066 @@
+use crate::{Client, EtherscanError, ProxyResponse, Response, Result};
+use ethers_core::{
+    abi::{self, Address, ParamType, Token},
+    types::{BlockNumber, Bytes, Log, H256, U256, U64},
+    utils::keccak256,
+};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Deserializer};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+/// The maximum number of logs Etherscan returns for a single request
+const MAX_LOGS_PER_REQUEST: usize = 1000;
+
+/// How two topics of a [`LogQueryParams`] are combined
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TopicOperator {
+    And,
+    Or,
+}
+
+impl fmt::Display for TopicOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TopicOperator::And => write!(f, "and"),
+            TopicOperator::Or => write!(f, "or"),
+        }
+    }
+}
+
+/// Options for querying event logs
+///
+/// Etherscan returns at most 1000 logs per request, use `page` and `offset` to paginate.
+#[derive(Clone, Debug, Default)]
+pub struct LogQueryParams {
+    /// The contract that emitted the logs, `None` matches any contract
+    pub address: Option<Address>,
+    pub from_block: Option<BlockNumber>,
+    pub to_block: Option<BlockNumber>,
+    /// `topic0` through `topic3`, `None` matches any value
+    pub topics: [Option<H256>; 4],
+    /// Operators between pairs of topics, keyed by the topic indices, e.g. `(0, 1)`
+    pub topic_operators: Vec<((usize, usize), TopicOperator)>,
+    pub page: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+impl LogQueryParams {
+    /// Creates a [LogQueryParamsBuilder], which validates the topics and their operators.
+    pub fn builder() -> LogQueryParamsBuilder {
+        LogQueryParamsBuilder::default()
+    }
+}
+
+/// Builds [LogQueryParams], checking Etherscan's topic grammar.
+///
+/// ```
+/// use ethers_core::types::H256;
+/// use ethers_etherscan::logs::{LogQueryParams, TopicOperator};
+///
+/// let transfer: H256 =
+///     "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef".parse().unwrap();
+/// let params = LogQueryParams::builder()
+///     .from_block(17000000)
+///     .to_block(17000100)
+///     .topic(0, transfer)
+///     .topic(2, H256::repeat_byte(1))
+///     .operator(0, 2, TopicOperator::And)
+///     .build()
+///     .unwrap();
+///
+/// // topic 1 is not set
+/// assert!(LogQueryParams::builder().topic(0, transfer).operator(0, 1, TopicOperator::Or).build().is_err());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct LogQueryParamsBuilder {
+    params: LogQueryParams,
+    /// The first out of range topic index, reported by [LogQueryParamsBuilder::build]
+    invalid_topic: Option<usize>,
+}
+
+impl LogQueryParamsBuilder {
+    /// Only matches logs emitted by `address`.
+    #[must_use]
+    pub fn address(mut self, address: Address) -> Self {
+        self.params.address = Some(address);
+        self
+    }
+
+    #[must_use]
+    pub fn from_block(mut self, block: impl Into<BlockNumber>) -> Self {
+        self.params.from_block = Some(block.into());
+        self
+    }
+
+    #[must_use]
+    pub fn to_block(mut self, block: impl Into<BlockNumber>) -> Self {
+        self.params.to_block = Some(block.into());
+        self
+    }
+
+    /// Only matches logs with `value` as the topic at `index`, from 0 to 3.
+    #[must_use]
+    pub fn topic(mut self, index: usize, value: H256) -> Self {
+        match self.params.topics.get_mut(index) {
+            Some(topic) => *topic = Some(value),
+            None => self.invalid_topic = self.invalid_topic.or(Some(index)),
+        }
+        self
+    }
+
+    /// Sets how the topics at `a` and `b` are combined.
+    #[must_use]
+    pub fn operator(mut self, a: usize, b: usize, operator: TopicOperator) -> Self {
+        self.params.topic_operators.push(((a, b), operator));
+        self
+    }
+
+    #[must_use]
+    pub fn page(mut self, page: u64) -> Self {
+        self.params.page = Some(page);
+        self
+    }
+
+    #[must_use]
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.params.offset = Some(offset);
+        self
+    }
+
+    /// Returns the params after validating the topics.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [EtherscanError::InvalidParams] if a topic index is above 3, or an operator
+    /// refers to the same topic twice, to a topic that is not set, or to a pair of topics that
+    /// already has an operator.
+    pub fn build(self) -> Result<LogQueryParams> {
+        let invalid = |reason: String| Err(EtherscanError::InvalidParams(reason));
+        if let Some(index) = self.invalid_topic {
+            return invalid(format!("topic{index} does not exist, topics range from 0 to 3"))
+        }
+
+        let mut pairs = HashSet::new();
+        for &((a, b), _) in &self.params.topic_operators {
+            if topic_operator_key(a, b).is_none() {
+                return invalid(format!("no operator between topic{a} and topic{b}"))
+            }
+            if let Some(missing) = [a, b].into_iter().find(|&i| self.params.topics[i].is_none()) {
+                return invalid(format!(
+                    "operator between topic{a} and topic{b}, but topic{missing} is not set"
+                ))
+            }
+            if !pairs.insert((a.min(b), a.max(b))) {
+                return invalid(format!("more than one operator between topic{a} and topic{b}"))
+            }
+        }
+        Ok(self.params)
+    }
+}
+
+impl From<LogQueryParams> for HashMap<&'static str, String> {
+    fn from(params: LogQueryParams) -> Self {
+        const TOPICS: [&str; 4] = ["topic0", "topic1", "topic2", "topic3"];
+
+        let mut map = HashMap::new();
+        if let Some(address) = params.address {
+            map.insert("address", format!("0x{address:x}"));
+        }
+        if let Some(from_block) = params.from_block {
+            map.insert("fromBlock", format_block(from_block));
+        }
+        if let Some(to_block) = params.to_block {
+            map.insert("toBlock", format_block(to_block));
+        }
+        for (key, topic) in TOPICS.iter().zip(params.topics) {
+            if let Some(topic) = topic {
+                map.insert(key, format!("{topic:?}"));
+            }
+        }
+        for ((a, b), operator) in params.topic_operators {
+            if let Some(key) = topic_operator_key(a, b) {
+                map.insert(key, operator.to_string());
+            }
+        }
+        if let Some(page) = params.page {
+            map.insert("page", page.to_string());
+        }
+        if let Some(offset) = params.offset {
+            map.insert("offset", offset.to_string());
+        }
+        map
+    }
+}
+
+fn format_block(block: BlockNumber) -> String {
+    match block {
+        BlockNumber::Number(number) => number.to_string(),
+        BlockNumber::Earliest => "0".to_string(),
+        tag => tag.to_string(),
+    }
+}
+
+/// Returns the query parameter name of the operator between two topics, if the pair is valid
+fn topic_operator_key(a: usize, b: usize) -> Option<&'static str> {
+    let key = match (a.min(b), a.max(b)) {
+        (0, 1) => "topic0_1_opr",
+        (0, 2) => "topic0_2_opr",
+        (0, 3) => "topic0_3_opr",
+        (1, 2) => "topic1_2_opr",
+        (1, 3) => "topic1_3_opr",
+        (2, 3) => "topic2_3_opr",
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// A log as returned by Etherscan, which encodes zero quantities as `"0x"`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EtherscanLog {
+    address: Address,
+    topics: Vec<H256>,
+    data: Bytes,
+    #[serde(default)]
+    block_hash: Option<H256>,
+    #[serde(deserialize_with = "deserialize_quantity")]
+    block_number: U256,
+    #[serde(deserialize_with = "deserialize_quantity")]
+    log_index: U256,
+    transaction_hash: H256,
+    #[serde(deserialize_with = "deserialize_quantity")]
+    transaction_index: U256,
+}
+
+impl From<EtherscanLog> for Log {
+    fn from(log: EtherscanLog) -> Self {
+        Log {
+            address: log.address,
+            topics: log.topics,
+            data: log.data,
+            block_hash: log.block_hash,
+            block_number: Some(U64::from(log.block_number.low_u64())),
+            transaction_hash: Some(log.transaction_hash),
+            transaction_index: Some(U64::from(log.transaction_index.low_u64())),
+            log_index: Some(log.log_index),
+            ..Default::default()
+        }
+    }
+}
+
+fn deserialize_quantity<'de, D>(deserializer: D) -> std::result::Result<U256, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match s.strip_prefix("0x") {
+        Some("") => Ok(U256::zero()),
+        Some(hex) => U256::from_str_radix(hex, 16).map_err(serde::de::Error::custom),
+        None => U256::from_dec_str(&s).map_err(serde::de::Error::custom),
+    }
+}
+
+/// The token standard of a [`TokenTransfer`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenStandard {
+    Erc20,
+    Erc721,
+    Erc1155,
+}
+
+/// A token transfer decoded from a `Transfer`, `TransferSingle` or `TransferBatch` event
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenTransfer {
+    pub standard: TokenStandard,
+    /// The token contract that emitted the event
+    pub token: Address,
+    /// The account that initiated an ERC-1155 transfer
+    pub operator: Option<Address>,
+    pub from: Address,
+    pub to: Address,
+    /// The transferred token, for ERC-721 and ERC-1155
+    pub token_id: Option<U256>,
+    /// The transferred amount, always 1 for ERC-721
+    pub value: U256,
+    pub log_index: Option<U256>,
+}
+
+impl TokenTransfer {
+    /// Decodes the transfers of a log, returns an empty list if it is not a token transfer
+    ///
+    /// ERC-20 and ERC-721 share the `Transfer` event and are told apart by whether the third
+    /// parameter is indexed. ERC-1155 batch transfers yield one transfer per token.
+    pub fn from_log(log: &Log) -> Vec<TokenTransfer> {
+        Self::try_from_log(log).unwrap_or_default()
+    }
+
+    fn try_from_log(log: &Log) -> Option<Vec<TokenTransfer>> {
+        let topic = |index: usize| log.topics.get(index).copied();
+        let address = |index: usize| topic(index).map(Address::from);
+        let topic0 = topic(0)?;
+
+        let transfers = if topic0 == H256(keccak256(TRANSFER_EVENT)) {
+            let (standard, token_id, value) = match (log.topics.len(), log.data.len()) {
+                (3, 32) => (TokenStandard::Erc20, None, U256::from_big_endian(&log.data)),
+                (4, 0) => (
+                    TokenStandard::Erc721,
+                    Some(U256::from_big_endian(topic(3)?.as_bytes())),
+                    U256::one(),
+                ),
+                _ => return None,
+            };
+            vec![TokenTransfer {
+                standard,
+                token: log.address,
+                operator: None,
+                from: address(1)?,
+                to: address(2)?,
+                token_id,
+                value,
+                log_index: log.log_index,
+            }]
+        } else if topic0 == H256(keccak256(TRANSFER_SINGLE_EVENT)) ||
+            topic0 == H256(keccak256(TRANSFER_BATCH_EVENT))
+        {
+            let (operator, from, to) = (address(1)?, address(2)?, address(3)?);
+            let (ids, values) = if topic0 == H256(keccak256(TRANSFER_SINGLE_EVENT)) {
+                let tokens = abi::decode(&[ParamType::Uint(256), ParamType::Uint(256)], &log.data);
+                let mut tokens = tokens.ok()?.into_iter();
+                (vec![tokens.next()?], vec![tokens.next()?])
+            } else {
+                let uints = ParamType::Array(Box::new(ParamType::Uint(256)));
+                let tokens = abi::decode(&[uints.clone(), uints], &log.data);
+                let mut tokens = tokens.ok()?.into_iter().map(Token::into_array);
+                (tokens.next()??, tokens.next()??)
+            };
+            if ids.len() != values.len() {
+                return None
+            }
+            ids.into_iter()
+                .zip(values)
+                .map(|(id, value)| {
+                    Some(TokenTransfer {
+                        standard: TokenStandard::Erc1155,
+                        token: log.address,
+                        operator: Some(operator),
+                        from,
+                        to,
+                        token_id: Some(id.into_uint()?),
+                        value: value.into_uint()?,
+                        log_index: log.log_index,
+                    })
+                })
+                .collect::<Option<_>>()?
+        } else {
+            return None
+        };
+        Some(transfers)
+    }
+}
+
+const TRANSFER_EVENT: &str = "Transfer(address,address,uint256)";
+const TRANSFER_SINGLE_EVENT: &str = "TransferSingle(address,address,address,uint256,uint256)";
+const TRANSFER_BATCH_EVENT: &str = "TransferBatch(address,address,address,uint256[],uint256[])";
+
+impl Client {
+    /// Returns the event logs matching the given filter
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, logs::LogQueryParams};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let params = LogQueryParams {
+    ///         address: Some("0xbd3531da5cf5857e7cfaa92426877b022e612cf8".parse().unwrap()),
+    ///         from_block: Some(12878196.into()),
+    ///         to_block: Some(12878196.into()),
+    ///         ..Default::default()
+    ///     };
+    ///     let logs = client.get_logs(params).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_logs(&self, params: LogQueryParams) -> Result<Vec<Log>> {
+        let address = params.address.map(|address| self.format_address(&address));
+        let mut params = HashMap::from(params);
+        if let Some(address) = address {
+            params.insert("address", address);
+        }
+        let query = self.create_query("logs", "getLogs", params);
+        let response: Response<Vec<EtherscanLog>> = self.get_json(&query).await?;
+
+        Ok(response.result.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns all event logs matching the given filter, splitting the block range as needed
+    ///
+    /// Whenever a request hits the cap of 1000 logs, its block range is bisected and both halves
+    /// are queried again. A single block with more logs is paginated instead. `page` and `offset`
+    /// of `params` are ignored. Without `to_block` (or with [BlockNumber::Latest]) the range ends
+    /// at the current head, and a `from_block` of [BlockNumber::Latest] or [BlockNumber::Pending]
+    /// starts it there.
+    ///
+    /// The logs are deduplicated and ordered by block number and log index.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, logs::LogQueryParams};
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let params = LogQueryParams {
+    ///         address: Some("0xdac17f958d2ee523a2206206994597c13d831ec7".parse().unwrap()),
+    ///         from_block: Some(17000000.into()),
+    ///         to_block: Some(17000100.into()),
+    ///         ..Default::default()
+    ///     };
+    ///     let logs = client.get_all_logs(params).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_all_logs(&self, params: LogQueryParams) -> Result<Vec<Log>> {
+        self.get_all_logs_with_limit(params, MAX_LOGS_PER_REQUEST).await
+    }
+
+    /// Like [Client::get_all_logs], for the logs emitted between two unix timestamps in seconds,
+    /// both inclusive.
+    ///
+    /// The timestamps are resolved with [Client::get_block_range_by_time], so a block is only
+    /// included if it was mined within the range. Returns no logs if no block was.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let logs = client
+    ///         .get_logs_between_times(
+    ///             Some("0xdac17f958d2ee523a2206206994597c13d831ec7".parse().unwrap()),
+    ///             1681430400,
+    ///             1681434000,
+    ///             Default::default(),
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    /// # }
+    /// ```
+    pub async fn get_logs_between_times(
+        &self,
+        address: Option<Address>,
+        start_time: u64,
+        end_time: u64,
+        topics: [Option<H256>; 4],
+    ) -> Result<Vec<Log>> {
+        let (from, to) = match self.get_block_range_by_time(start_time, end_time).await? {
+            Some(range) => range,
+            None => return Ok(Vec::new()),
+        };
+        let params = LogQueryParams {
+            address,
+            from_block: Some(from.into()),
+            to_block: Some(to.into()),
+            topics,
+            ..Default::default()
+        };
+        self.get_all_logs(params).await
+    }
+
+    /// Like [Client::get_all_logs], for the logs emitted by any of several contracts.
+    ///
+    /// Etherscan filters by a single address, so each address is queried on its own, as many at
+    /// once as [Client::batch] runs. The logs of all addresses are ordered by block number and log
+    /// index.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let addresses = [
+    ///         "0xdac17f958d2ee523a2206206994597c13d831ec7".parse().unwrap(),
+    ///         "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".parse().unwrap(),
+    ///     ];
+    ///     let logs = client
+    ///         .get_logs_multi(&addresses, 17000000, 17000100, Default::default())
+    ///         .await
+    ///         .unwrap();
+    /// # }
+    /// ```
+    pub async fn get_logs_multi(
+        &self,
+        addresses: &[Address],
+        from_block: impl Into<BlockNumber>,
+        to_block: impl Into<BlockNumber>,
+        topics: [Option<H256>; 4],
+    ) -> Result<Vec<Log>> {
+        let (from_block, to_block) = (from_block.into(), to_block.into());
+        let mut seen = HashSet::new();
+        let unique: Vec<_> =
+            addresses.iter().copied().filter(|address| seen.insert(*address)).collect();
+        let mut logs: Vec<_> = stream::iter(unique)
+            .map(|address| {
+                self.get_all_logs(LogQueryParams {
+                    address: Some(address),
+                    from_block: Some(from_block),
+                    to_block: Some(to_block),
+                    topics,
+                    ..Default::default()
+                })
+            })
+            .buffer_unordered(self.batch_concurrency)
+            .try_concat()
+            .await?;
+        logs.sort_by_key(|log| (log.block_number, log.log_index));
+        Ok(logs)
+    }
+
+    async fn get_all_logs_with_limit(
+        &self,
+        params: LogQueryParams,
+        limit: usize,
+    ) -> Result<Vec<Log>> {
+        let from = match params.from_block {
+            Some(BlockNumber::Number(block)) => Some(block.as_u64()),
+            None | Some(BlockNumber::Earliest) => Some(0),
+            _ => None,
+        };
+        let to = match params.to_block {
+            Some(BlockNumber::Number(block)) => Some(block.as_u64()),
+            Some(BlockNumber::Earliest) => Some(0),
+            _ => None,
+        };
+        // the bounds that aren't numbers are resolved to the current head
+        let head = match (from, to) {
+            (Some(_), Some(_)) => 0,
+            _ => self.get_block_number().await?,
+        };
+        let (from, to) = (from.unwrap_or(head), to.unwrap_or(head));
+
+        let mut logs = Vec::new();
+        // ranges left to query, the next one is at the end
+        let mut ranges = vec![(from, to)];
+        while let Some((from, to)) = ranges.pop() {
+            let range = LogQueryParams {
+                from_block: Some(from.into()),
+                to_block: Some(to.into()),
+                page: None,
+                offset: None,
+                ..params.clone()
+            };
+            let page = self.get_logs(range.clone()).await?;
+            if page.len() < limit {
+                logs.extend(page);
+            } else if from < to {
+                let mid = from + (to - from) / 2;
+                ranges.push((mid + 1, to));
+                ranges.push((from, mid));
+            } else {
+                logs.extend(self.get_block_logs_paginated(range, limit).await?);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        logs.retain(|log| seen.insert((log.block_number, log.log_index)));
+        logs.sort_by_key(|log| (log.block_number, log.log_index));
+        Ok(logs)
+    }
+
+    /// Returns all logs of a block range that can't be split any further, page by page.
+    async fn get_block_logs_paginated(
+        &self,
+        params: LogQueryParams,
+        limit: usize,
+    ) -> Result<Vec<Log>> {
+        let mut logs = Vec::new();
+        for page in 1.. {
+            let params =
+                LogQueryParams { page: Some(page), offset: Some(limit as u64), ..params.clone() };
+            let page = self.get_logs(params).await?;
+            let done = page.len() < limit;
+            logs.extend(page);
+            if done {
+                break
+            }
+        }
+        Ok(logs)
+    }
+
+    /// Decodes the indexed topics and the data of a log, e.g. one returned by
+    /// [`Client::get_logs`], as the parameters of `event`.
+    ///
+    /// The first topic must be the signature of `event`, unless it's anonymous, in which case all
+    /// topics are indexed parameters.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{Client, logs::LogQueryParams};
+    /// # use ethers_core::{abi::HumanReadableParser, types::Chain};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let event = HumanReadableParser::parse_event(
+    ///         "event Transfer(address indexed from, address indexed to, uint256 value)",
+    ///     )
+    ///     .unwrap();
+    ///     let params = LogQueryParams {
+    ///         address: Some("0xdac17f958d2ee523a2206206994597c13d831ec7".parse().unwrap()),
+    ///         from_block: Some(17000000.into()),
+    ///         to_block: Some(17000000.into()),
+    ///         topics: [Some(event.signature()), None, None, None],
+    ///         ..Default::default()
+    ///     };
+    ///     for log in client.get_logs(params).await.unwrap() {
+    ///         let transfer = client.decode_log(&log, &event).unwrap();
+    ///     }
+    /// # }
+    /// ```
+    pub fn decode_log(&self, log: &Log, event: &abi::Event) -> Result<abi::Log> {
+        let raw = abi::RawLog { topics: log.topics.clone(), data: log.data.to_vec() };
+        Ok(event.parse_log(raw)?)
+    }
+
+    /// Returns the ERC-20, ERC-721 and ERC-1155 token transfers of a transaction, ordered by
+    /// their log index
+    ///
+    /// The transfer events are decoded from the logs of the transaction's receipt, fetched with
+    /// the `eth_getTransactionReceipt` proxy. Returns an empty list for pending transactions.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let transfers = client
+    ///         .get_token_transfers_for_tx(
+    ///             &"0x40eb908387324f2b575b4879cd9d7188f69c8fc9d87c901b9e2daaea4b442170".parse().unwrap())
+    ///         .await.unwrap();
+    /// # }
+    /// ```
+    pub async fn get_token_transfers_for_tx(&self, hash: &H256) -> Result<Vec<TokenTransfer>> {
+        let receipt = match self.get_transaction_receipt(hash).await? {
+            Some(receipt) => receipt,
+            None => {
+                // only pending transactions are known without a receipt
+                return if self.is_known_transaction(hash).await? {
+                    Ok(Vec::new())
+                } else {
+                    Err(EtherscanError::TransactionNotFound(*hash))
+                }
+            }
+        };
+
+        let mut transfers: Vec<_> = receipt.logs.iter().flat_map(TokenTransfer::from_log).collect();
+        transfers.sort_by_key(|transfer| transfer.log_index);
+        Ok(transfers)
+    }
+
+    /// Returns whether the `eth_getTransactionByHash` proxy knows a transaction.
+    async fn is_known_transaction(&self, hash: &H256) -> Result<bool> {
+        let query = self.create_query(
+            "proxy",
+            "eth_getTransactionByHash",
+            HashMap::from([("txhash", format!("{hash:?}"))]),
+        );
+        let response: ProxyResponse<Option<serde::de::IgnoredAny>> =
+            self.get_json_rpc(&query).await?;
+        Ok(response.result.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{query_param, MockResponse, MockServer};
+
+    #[test]
+    fn can_build_log_params() {
+        let topic = H256::repeat_byte(0x11);
+        let params = LogQueryParams::builder()
+            .address(Address::repeat_byte(1))
+            .from_block(100)
+            .topic(0, topic)
+            .topic(3, topic)
+            .operator(3, 0, TopicOperator::Or)
+            .offset(10)
+            .build()
+            .unwrap();
+        let map: HashMap<_, _> = params.into();
+        assert_eq!(map["topic0_3_opr"], "or");
+        assert_eq!(map["topic3"], format!("{topic:?}"));
+        assert_eq!(map["fromBlock"], "100");
+        assert_eq!(map["offset"], "10");
+
+        let invalid = |builder: LogQueryParamsBuilder| {
+            matches!(builder.build(), Err(EtherscanError::InvalidParams(_)))
+        };
+        let builder = LogQueryParams::builder().topic(0, topic).topic(1, topic);
+        assert!(invalid(builder.clone().topic(4, topic)));
+        assert!(invalid(builder.clone().operator(1, 1, TopicOperator::And)));
+        assert!(invalid(builder.clone().operator(0, 2, TopicOperator::And)));
+        assert!(invalid(builder.clone().operator(0, 1, TopicOperator::And).operator(
+            1,
+            0,
+            TopicOperator::Or
+        )));
+        assert!(!invalid(builder.operator(1, 0, TopicOperator::And)));
+    }
+
+    #[test]
+    fn can_convert_log_params() {
+        let address: Address = "0xbd3531da5cf5857e7cfaa92426877b022e612cf8".parse().unwrap();
+        let topic = H256::repeat_byte(0x11);
+        let params = LogQueryParams {
+            address: Some(address),
+            from_block: Some(100.into()),
+            to_block: Some(BlockNumber::Latest),
+            topics: [Some(topic), None, Some(topic), None],
+            topic_operators: vec![((2, 0), TopicOperator::Or)],
+            ..Default::default()
+        };
+        let map: HashMap<&'static str, String> = params.into();
+
+        assert_eq!(map["address"], "0xbd3531da5cf5857e7cfaa92426877b022e612cf8");
+        assert_eq!(map["fromBlock"], "100");
+        assert_eq!(map["toBlock"], "latest");
+        assert_eq!(map["topic0"], format!("{topic:?}"));
+        assert_eq!(map["topic2"], format!("{topic:?}"));
+        assert_eq!(map["topic0_2_opr"], "or");
+        assert!(!map.contains_key("topic1"));
+        assert!(!map.contains_key("page"));
+    }
+
+    fn transfer_log(event: &str, topics: &[H256], data: Vec<u8>) -> Log {
+        let mut all_topics = vec![H256(keccak256(event))];
+        all_topics.extend_from_slice(topics);
+        Log {
+            address: Address::repeat_byte(0xcc),
+            topics: all_topics,
+            data: data.into(),
+            log_index: Some(7.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn can_decode_log() {
+        let client = Client::new(ethers_core::types::Chain::Mainnet, "API_KEY").unwrap();
+        let (from, to) = (Address::repeat_byte(2), Address::repeat_byte(3));
+        let data = abi::encode(&[Token::Uint(5.into())]);
+        let log =
+            transfer_log("Transfer(address,address,uint256)", &[from.into(), to.into()], data);
+
+        let event = abi::HumanReadableParser::parse_event(
+            "event Transfer(address indexed from, address indexed to, uint256 value)",
+        )
+        .unwrap();
+        let decoded = client.decode_log(&log, &event).unwrap();
+        let params: Vec<_> = decoded.params.into_iter().map(|param| param.value).collect();
+        assert_eq!(params, [Token::Address(from), Token::Address(to), Token::Uint(5.into())]);
+
+        let other = abi::HumanReadableParser::parse_event(
+            "event Approval(address indexed owner, address indexed spender, uint256 value)",
+        )
+        .unwrap();
+        assert!(matches!(client.decode_log(&log, &other), Err(EtherscanError::Abi(_))));
+
+        // anonymous events have no signature topic
+        let anonymous = abi::HumanReadableParser::parse_event(
+            "event Moved(bytes32 indexed id, address indexed from, address indexed to, uint256 value) anonymous",
+        )
+        .unwrap();
+        let decoded = client.decode_log(&log, &anonymous).unwrap();
+        assert_eq!(decoded.params[0].value, Token::FixedBytes(log.topics[0].0.to_vec()));
+        assert_eq!(decoded.params[2].value, Token::Address(to));
+    }
+
+    #[test]
+    fn can_decode_token_transfers() {
+        let [operator, from, to] = [1u8, 2, 3].map(Address::repeat_byte);
+        let [operator_topic, from_topic, to_topic] = [operator, from, to].map(H256::from);
+
+        let erc20 = transfer_log(
+            TRANSFER_EVENT,
+            &[from_topic, to_topic],
+            abi::encode(&[Token::Uint(100.into())]),
+        );
+        let transfers = TokenTransfer::from_log(&erc20);
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].standard, TokenStandard::Erc20);
+        assert_eq!((transfers[0].from, transfers[0].to), (from, to));
+        assert_eq!(transfers[0].value, 100.into());
+        assert_eq!(transfers[0].token_id, None);
+
+        let erc721 = transfer_log(
+            TRANSFER_EVENT,
+            &[from_topic, to_topic, H256::from_low_u64_be(42)],
+            Vec::new(),
+        );
+        let transfers = TokenTransfer::from_log(&erc721);
+        assert_eq!(transfers[0].standard, TokenStandard::Erc721);
+        assert_eq!(transfers[0].token_id, Some(42.into()));
+        assert_eq!(transfers[0].value, 1.into());
+
+        let single = transfer_log(
+            TRANSFER_SINGLE_EVENT,
+            &[operator_topic, from_topic, to_topic],
+            abi::encode(&[Token::Uint(5.into()), Token::Uint(10.into())]),
+        );
+        let transfers = TokenTransfer::from_log(&single);
+        assert_eq!(transfers[0].standard, TokenStandard::Erc1155);
+        assert_eq!(transfers[0].operator, Some(operator));
+        assert_eq!((transfers[0].from, transfers[0].to), (from, to));
+        assert_eq!((transfers[0].token_id, transfers[0].value), (Some(5.into()), 10.into()));
+
+        let batch = transfer_log(
+            TRANSFER_BATCH_EVENT,
+            &[operator_topic, from_topic, to_topic],
+            abi::encode(&[
+                Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+                Token::Array(vec![Token::Uint(3.into()), Token::Uint(4.into())]),
+            ]),
+        );
+        let transfers = TokenTransfer::from_log(&batch);
+        assert_eq!(transfers.len(), 2);
+        assert_eq!((transfers[1].token_id, transfers[1].value), (Some(2.into()), 4.into()));
+
+        let approval =
+            transfer_log("Approval(address,address,uint256)", &[from_topic, to_topic], Vec::new());
+        assert!(TokenTransfer::from_log(&approval).is_empty());
+    }
+
+    fn proxy_result(result: serde_json::Value) -> MockResponse {
+        MockResponse::json(
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": result}).to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn can_get_token_transfers_for_tx() {
+        let [from, to] = [2u8, 3].map(|byte| H256::from(Address::repeat_byte(byte)));
+        let mut erc20 =
+            transfer_log(TRANSFER_EVENT, &[from, to], abi::encode(&[Token::Uint(100.into())]));
+        erc20.log_index = Some(5.into());
+        let mut approval =
+            transfer_log("Approval(address,address,uint256)", &[from, to], Vec::new());
+        approval.log_index = Some(3.into());
+        let mut erc721 =
+            transfer_log(TRANSFER_EVENT, &[from, to, H256::from_low_u64_be(42)], Vec::new());
+        erc721.log_index = Some(2.into());
+        let receipt = serde_json::json!({
+            "transactionHash": "0x40eb908387324f2b575b4879cd9d7188f69c8fc9d87c901b9e2daaea4b442170",
+            "transactionIndex": "0x0",
+            "blockHash": "0xb9367ac1df1a7c1e2cd93e4c2b1bcf2b1a6c25a2c1b74d7f58e1bbe3c10f8e2e",
+            "blockNumber": "0x10",
+            "from": "0x9aa99c23f67c81701c772b106b4f83f6e858dd2e",
+            "to": "0xdac17f958d2ee523a2206206994597c13d831ec7",
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "contractAddress": null,
+            "logs": [erc20, approval, erc721],
+            "status": "0x1",
+            "logsBloom": format!("0x{}", "0".repeat(512)),
+        });
+        let pending = serde_json::json!({"hash": "0x40eb908387324f2b575b4879cd9d7188f69c8fc9d87c901b9e2daaea4b442170"});
+        let server = MockServer::start(vec![
+            proxy_result(receipt),
+            proxy_result(serde_json::Value::Null),
+            proxy_result(pending),
+            proxy_result(serde_json::Value::Null),
+            proxy_result(serde_json::Value::Null),
+        ])
+        .await;
+        let hash =
+            "0x40eb908387324f2b575b4879cd9d7188f69c8fc9d87c901b9e2daaea4b442170".parse().unwrap();
+
+        let transfers = server.client().get_token_transfers_for_tx(&hash).await.unwrap();
+        let standards: Vec<_> = transfers.iter().map(|transfer| transfer.standard).collect();
+        assert_eq!(standards, [TokenStandard::Erc721, TokenStandard::Erc20]);
+        assert_eq!(transfers[1].value, 100.into());
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(query_param(&server.requests()[0], "action"), Some("eth_getTransactionReceipt"));
+
+        assert!(server.client().get_token_transfers_for_tx(&hash).await.unwrap().is_empty());
+        let err = server.client().get_token_transfers_for_tx(&hash).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::TransactionNotFound(_)));
+    }
+
+    fn logs_response(blocks: &[u64]) -> MockResponse {
+        let logs: Vec<_> = blocks
+            .iter()
+            .enumerate()
+            .map(|(index, block)| {
+                serde_json::json!({
+                    "address": "0xbd3531da5cf5857e7cfaa92426877b022e612cf8",
+                    "topics": [],
+                    "data": "0x",
+                    "blockNumber": format!("{block:#x}"),
+                    "logIndex": format!("{index:#x}"),
+                    "transactionHash": "0x40eb908387324f2b575b4879cd9d7188f69c8fc9d87c901b9e2daaea4b442170",
+                    "transactionIndex": "0x"
+                })
+            })
+            .collect();
+        MockResponse::json(
+            serde_json::json!({"status": "1", "message": "OK", "result": logs}).to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn bisects_capped_log_ranges() {
+        let server = MockServer::start(vec![
+            // 0..=10 hits the cap
+            logs_response(&[1, 2]),
+            // 0..=5
+            logs_response(&[2]),
+            // 6..=10, the log of block 2 is a duplicate
+            logs_response(&[2, 7]),
+            // 6..=8
+            logs_response(&[7]),
+            // 9..=10
+            logs_response(&[]),
+        ])
+        .await;
+
+        let params = LogQueryParams {
+            from_block: Some(0.into()),
+            to_block: Some(10.into()),
+            ..Default::default()
+        };
+        let logs = server.client().get_all_logs_with_limit(params, 2).await.unwrap();
+        let blocks: Vec<_> = logs.iter().map(|log| log.block_number.unwrap().as_u64()).collect();
+        assert_eq!(blocks, [2, 7]);
+
+        let ranges: Vec<_> = server
+            .requests()
+            .iter()
+            .map(|request| {
+                let param = |name| query_param(request, name).unwrap();
+                format!("{}-{}", param("fromBlock"), param("toBlock"))
+            })
+            .collect();
+        assert_eq!(ranges, ["0-10", "0-5", "6-10", "6-8", "9-10"]);
+    }
+
+    #[tokio::test]
+    async fn resolves_latest_from_block_to_head() {
+        let server = MockServer::start(vec![
+            MockResponse::json(r#"{"jsonrpc":"2.0","id":1,"result":"0x64"}"#),
+            logs_response(&[100]),
+        ])
+        .await;
+
+        let params = LogQueryParams { from_block: Some(BlockNumber::Latest), ..Default::default() };
+        let logs = server.client().get_all_logs(params).await.unwrap();
+        assert_eq!(logs.len(), 1);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(query_param(&requests[0], "action"), Some("eth_blockNumber"));
+        assert_eq!(query_param(&requests[1], "fromBlock"), Some("100"));
+        assert_eq!(query_param(&requests[1], "toBlock"), Some("100"));
+    }
+
+    #[tokio::test]
+    async fn can_get_logs_between_times() {
+        let block = |number| {
+            MockResponse::json(format!(r#"{{"status":"1","message":"OK","result":"{number}"}}"#))
+        };
+        let server = MockServer::start(vec![block(100), block(120), logs_response(&[105])]).await;
+
+        let logs =
+            server.client().get_logs_between_times(None, 1000, 1240, Default::default()).await;
+        assert_eq!(logs.unwrap().len(), 1);
+        let requests = server.requests();
+        assert_eq!(query_param(&requests[0], "closest"), Some("after"));
+        assert_eq!(query_param(&requests[1], "closest"), Some("before"));
+        assert_eq!(query_param(&requests[2], "fromBlock"), Some("100"));
+        assert_eq!(query_param(&requests[2], "toBlock"), Some("120"));
+    }
+
+    #[tokio::test]
+    async fn can_get_logs_of_multiple_addresses() {
+        let server = MockServer::start(vec![logs_response(&[5, 9]), logs_response(&[3])]).await;
+
+        let addresses = [Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(1)];
+        let logs = server.client().get_logs_multi(&addresses, 0, 10, Default::default()).await;
+        let blocks: Vec<_> =
+            logs.unwrap().iter().map(|log| log.block_number.unwrap().as_u64()).collect();
+        assert_eq!(blocks, [3, 5, 9]);
+
+        let mut requested: Vec<_> = server
+            .requests()
+            .iter()
+            .map(|request| query_param(request, "address").unwrap().to_string())
+            .collect();
+        requested.sort();
+        assert_eq!(requested, [format!("{:?}", addresses[0]), format!("{:?}", addresses[1])]);
+    }
+
+    #[tokio::test]
+    async fn no_logs_between_blocks() {
+        let block = |number| {
+            MockResponse::json(format!(r#"{{"status":"1","message":"OK","result":"{number}"}}"#))
+        };
+        // both timestamps fall between blocks 100 and 101
+        let server = MockServer::start(vec![block(101), block(100)]).await;
+
+        let logs = server.client().get_logs_between_times(None, 1001, 1002, Default::default());
+        assert!(logs.await.unwrap().is_empty());
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn paginates_capped_single_block() {
+        let server = MockServer::start(vec![
+            logs_response(&[5, 5]),
+            logs_response(&[5, 5]),
+            logs_response(&[5]),
+        ])
+        .await;
+
+        let params = LogQueryParams {
+            from_block: Some(5.into()),
+            to_block: Some(5.into()),
+            ..Default::default()
+        };
+        server.client().get_all_logs_with_limit(params, 2).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(query_param(&requests[0], "page"), None);
+        assert_eq!(query_param(&requests[1], "page"), Some("1"));
+        assert_eq!(query_param(&requests[1], "offset"), Some("2"));
+        assert_eq!(query_param(&requests[2], "page"), Some("2"));
+    }
+
+    #[test]
+    fn can_deserialize_log() {
+        let json = r#"{
+            "address": "0x00000000219ab540356cbb839cbe05303d7705fa",
+            "topics": ["0x649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"],
+            "data": "0x",
+            "blockNumber": "0xb3a1a8",
+            "timeStamp": "0x60f5a7e4",
+            "gasPrice": "0x3b9aca00",
+            "gasUsed": "0x1af2c",
+            "logIndex": "0x",
+            "transactionHash": "0x40eb908387324f2b575b4879cd9d7188f69c8fc9d87c901b9e2daaea4b442170",
+            "transactionIndex": "0x"
+        }"#;
+        let log: Log = serde_json::from_str::<EtherscanLog>(json).unwrap().into();
+
+        assert_eq!(log.block_number, Some(0xb3a1a8.into()));
+        assert_eq!(log.log_index, Some(0.into()));
+        assert_eq!(log.transaction_index, Some(0.into()));
+    }
+}
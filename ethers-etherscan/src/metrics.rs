@@ -0,0 +1,325 @@
+//! Measuring the requests of a client per endpoint
+
+use crate::{errors::EtherscanError, Client, ClientBuilder};
+use reqwest::Url;
+use std::{borrow::Cow, fmt, sync::Arc, time::Duration};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+pub(crate) use wasm_timer::Instant;
+
+/// Receives measurements of the requests of a [Client], see [ClientBuilder::with_metrics]
+///
+/// Every event is labeled with the `module` and `action` params of its request, e.g. `account`
+/// and `txlist`, so counting [Metrics::on_request] by action shows how the quota is spent. The
+/// methods are called on the task sending the request and should return quickly, e.g. by
+/// updating counters and histograms of a metrics library. They do nothing by default.
+pub trait Metrics: fmt::Debug + Send + Sync {
+    /// Called for every HTTP request sent, including retries, with the time from sending it to
+    /// reading its body. The time spent waiting for the rate limit isn't included.
+    fn on_request(&self, module: &str, action: &str, latency: Duration) {
+        let _ = (module, action, latency);
+    }
+
+    /// Called when a request fails after its retries, e.g. on a timeout, an HTTP error status or
+    /// a response that doesn't parse.
+    ///
+    /// A response with an error `status` that parses into the expected type, like a `NOTOK`
+    /// balance, is only turned into an error by the endpoint's method and isn't reported here.
+    fn on_error(&self, module: &str, action: &str, error: &EtherscanError) {
+        let _ = (module, action, error);
+    }
+
+    /// Called before retrying a failed request, with the number of the retry starting at 0.
+    fn on_retry(&self, module: &str, action: &str, attempt: u32) {
+        let _ = (module, action, attempt);
+    }
+
+    /// Called when a request waited for the client side rate limit before being sent.
+    fn on_rate_limit_wait(&self, module: &str, action: &str, wait: Duration) {
+        let _ = (module, action, wait);
+    }
+}
+
+/// Reports the requests of a client through the [`metrics`](::metrics) crate facade, to the
+/// recorder installed by the application
+///
+/// Clients use it by default with the `metrics` feature, unless other metrics are configured with
+/// [ClientBuilder::with_metrics]. All measurements are labeled with `module` and `action`:
+///
+/// - `etherscan_requests_total`: counter of the HTTP requests sent, including retries
+/// - `etherscan_request_duration_seconds`: histogram of the latency of the requests
+/// - `etherscan_errors_total`: counter of the requests that failed after their retries
+/// - `etherscan_retries_total`: counter of the retried requests
+/// - `etherscan_rate_limit_wait_seconds`: histogram of the time spent waiting for the rate limit
+#[cfg(feature = "metrics")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MetricsFacade;
+
+#[cfg(feature = "metrics")]
+impl Metrics for MetricsFacade {
+    fn on_request(&self, module: &str, action: &str, latency: Duration) {
+        let labels = [("module", module.to_string()), ("action", action.to_string())];
+        ::metrics::counter!("etherscan_requests_total", &labels).increment(1);
+        ::metrics::histogram!("etherscan_request_duration_seconds", &labels).record(latency);
+    }
+
+    fn on_error(&self, module: &str, action: &str, _: &EtherscanError) {
+        let labels = [("module", module.to_string()), ("action", action.to_string())];
+        ::metrics::counter!("etherscan_errors_total", &labels).increment(1);
+    }
+
+    fn on_retry(&self, module: &str, action: &str, _: u32) {
+        let labels = [("module", module.to_string()), ("action", action.to_string())];
+        ::metrics::counter!("etherscan_retries_total", &labels).increment(1);
+    }
+
+    fn on_rate_limit_wait(&self, module: &str, action: &str, wait: Duration) {
+        let labels = [("module", module.to_string()), ("action", action.to_string())];
+        ::metrics::histogram!("etherscan_rate_limit_wait_seconds", &labels).record(wait);
+    }
+}
+
+/// The metrics of a client that weren't configured with [ClientBuilder::with_metrics]
+pub(crate) fn default_metrics() -> Option<Arc<dyn Metrics>> {
+    #[cfg(feature = "metrics")]
+    return Some(Arc::new(MetricsFacade));
+    #[cfg(not(feature = "metrics"))]
+    None
+}
+
+/// The `module` and `action` params of a request, which label its measurements
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Endpoint {
+    module: String,
+    action: String,
+}
+
+impl Endpoint {
+    pub(crate) fn new(module: &str, action: &str) -> Self {
+        Self { module: module.to_string(), action: action.to_string() }
+    }
+
+    /// Reads the endpoint from the query of a GET request's url.
+    pub(crate) fn of_url(url: &Url) -> Self {
+        let mut endpoint = Self::default();
+        for (name, value) in url.query_pairs() {
+            match name {
+                Cow::Borrowed("module") => endpoint.module = value.into_owned(),
+                Cow::Borrowed("action") => endpoint.action = value.into_owned(),
+                _ => {}
+            }
+        }
+        endpoint
+    }
+}
+
+impl ClientBuilder {
+    /// Reports the requests of the client to `metrics`.
+    ///
+    /// Without metrics the requests aren't measured at all, unless the `metrics` feature reports
+    /// them to the [MetricsFacade].
+    pub fn with_metrics(mut self, metrics: impl Metrics + 'static) -> Self {
+        self.metrics = Some(Arc::new(metrics));
+        self
+    }
+}
+
+impl Client {
+    /// Returns the endpoint of a GET request's url, if there are metrics to label.
+    pub(crate) fn endpoint(&self, url: &Url) -> Option<Endpoint> {
+        self.metrics.as_ref().map(|_| Endpoint::of_url(url))
+    }
+
+    /// Calls `f` with the configured metrics and the `module` and `action` of a request, if
+    /// there are metrics.
+    pub(crate) fn record(
+        &self,
+        endpoint: Option<&Endpoint>,
+        f: impl FnOnce(&dyn Metrics, &str, &str),
+    ) {
+        if let (Some(metrics), Some(endpoint)) = (&self.metrics, endpoint) {
+            f(metrics.as_ref(), &endpoint.module, &endpoint.action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::{MockResponse, MockServer},
+        retry::RetryPolicy,
+    };
+    use std::sync::Mutex;
+
+    /// Records the events as `<event> <action>`
+    #[derive(Debug, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Metrics for Recorder {
+        fn on_request(&self, _: &str, action: &str, _: Duration) {
+            self.0.lock().unwrap().push(format!("request {action}"));
+        }
+
+        fn on_error(&self, _: &str, action: &str, _: &EtherscanError) {
+            self.0.lock().unwrap().push(format!("error {action}"));
+        }
+
+        fn on_retry(&self, _: &str, action: &str, attempt: u32) {
+            self.0.lock().unwrap().push(format!("retry {attempt} {action}"));
+        }
+    }
+
+    #[tokio::test]
+    async fn records_requests_by_action() {
+        let rate_limited = MockResponse { status: 429, ..MockResponse::json("Too Many Requests") };
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Recorder(events.clone());
+        let server = MockServer::start_with(
+            vec![
+                rate_limited,
+                MockResponse::json(r#"{"status":"1","message":"OK","result":"1"}"#),
+                MockResponse { status: 500, ..MockResponse::json("Internal Server Error") },
+            ],
+            |builder| {
+                builder
+                    .with_retry_policy(RetryPolicy::new(1, Duration::from_millis(1)))
+                    .with_metrics(recorder)
+            },
+        )
+        .await;
+
+        let address = "0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap();
+        server.client().get_ether_balance_single(&address, None).await.unwrap();
+        server.client().get_ether_balance_single(&address, None).await.unwrap_err();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "request balance",
+                "retry 0 balance",
+                "request balance",
+                "request balance",
+                "error balance"
+            ]
+        );
+    }
+
+    /// Records the names and labels of the metrics emitted through the facade
+    #[cfg(feature = "metrics")]
+    #[derive(Debug, Default)]
+    struct FacadeRecorder(Arc<Mutex<Vec<String>>>);
+
+    #[cfg(feature = "metrics")]
+    struct Handle(String, Arc<Mutex<Vec<String>>>);
+
+    #[cfg(feature = "metrics")]
+    impl ::metrics::CounterFn for Handle {
+        fn increment(&self, value: u64) {
+            self.1.lock().unwrap().push(format!("{} +{value}", self.0));
+        }
+
+        fn absolute(&self, _: u64) {}
+    }
+
+    #[cfg(feature = "metrics")]
+    impl ::metrics::HistogramFn for Handle {
+        fn record(&self, _: f64) {
+            self.1.lock().unwrap().push(self.0.clone());
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    impl ::metrics::Recorder for FacadeRecorder {
+        fn describe_counter(
+            &self,
+            _: ::metrics::KeyName,
+            _: Option<::metrics::Unit>,
+            _: ::metrics::SharedString,
+        ) {
+        }
+
+        fn describe_gauge(
+            &self,
+            _: ::metrics::KeyName,
+            _: Option<::metrics::Unit>,
+            _: ::metrics::SharedString,
+        ) {
+        }
+
+        fn describe_histogram(
+            &self,
+            _: ::metrics::KeyName,
+            _: Option<::metrics::Unit>,
+            _: ::metrics::SharedString,
+        ) {
+        }
+
+        fn register_counter(
+            &self,
+            key: &::metrics::Key,
+            _: &::metrics::Metadata<'_>,
+        ) -> ::metrics::Counter {
+            ::metrics::Counter::from_arc(Arc::new(self.handle(key)))
+        }
+
+        fn register_gauge(
+            &self,
+            _: &::metrics::Key,
+            _: &::metrics::Metadata<'_>,
+        ) -> ::metrics::Gauge {
+            ::metrics::Gauge::noop()
+        }
+
+        fn register_histogram(
+            &self,
+            key: &::metrics::Key,
+            _: &::metrics::Metadata<'_>,
+        ) -> ::metrics::Histogram {
+            ::metrics::Histogram::from_arc(Arc::new(self.handle(key)))
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    impl FacadeRecorder {
+        fn handle(&self, key: &::metrics::Key) -> Handle {
+            let labels: Vec<_> =
+                key.labels().map(|label| format!("{}={}", label.key(), label.value())).collect();
+            Handle(format!("{}{{{}}}", key.name(), labels.join(",")), self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "metrics")]
+    async fn reports_to_the_metrics_facade_by_default() {
+        let recorder = FacadeRecorder::default();
+        let events = recorder.0.clone();
+        let _guard = ::metrics::set_default_local_recorder(&recorder);
+        let server = MockServer::start_with(
+            vec![
+                MockResponse { status: 429, ..MockResponse::json("Too Many Requests") },
+                MockResponse { status: 500, ..MockResponse::json("Internal Server Error") },
+            ],
+            |builder| builder.with_retry_policy(RetryPolicy::new(1, Duration::from_millis(1))),
+        )
+        .await;
+
+        let address = "0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap();
+        server.client().get_ether_balance_single(&address, None).await.unwrap_err();
+
+        let labels = "{module=account,action=balance}";
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                format!("etherscan_requests_total{labels} +1"),
+                format!("etherscan_request_duration_seconds{labels}"),
+                format!("etherscan_retries_total{labels} +1"),
+                format!("etherscan_requests_total{labels} +1"),
+                format!("etherscan_request_duration_seconds{labels}"),
+                format!("etherscan_errors_total{labels} +1"),
+            ]
+        );
+    }
+}
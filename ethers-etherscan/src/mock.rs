@@ -0,0 +1,167 @@
+//! A minimal HTTP server that replays canned responses, for testing the client without network
+//! access.
+
+use crate::{Client, ClientBuilder};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// A canned HTTP response
+#[derive(Clone, Debug)]
+pub(crate) struct MockResponse {
+    pub(crate) status: u16,
+    pub(crate) content_type: &'static str,
+    pub(crate) body: Vec<u8>,
+    /// The `Content-Encoding` of the body, only served to requests accepting it
+    pub(crate) content_encoding: Option<&'static str>,
+    /// How long to wait before responding
+    pub(crate) delay: Duration,
+}
+
+impl MockResponse {
+    pub(crate) fn json(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json",
+            body: body.into().into_bytes(),
+            content_encoding: None,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// A gzip compressed JSON response
+    pub(crate) fn gzip(body: &str) -> Self {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+        Self { body: encoder.finish().unwrap(), content_encoding: Some("gzip"), ..Self::json("") }
+    }
+
+    /// A brotli compressed JSON response
+    pub(crate) fn brotli(body: &str) -> Self {
+        let mut compressed = Vec::new();
+        let params = brotli::enc::BrotliEncoderParams::default();
+        brotli::BrotliCompress(&mut body.as_bytes(), &mut compressed, &params).unwrap();
+        Self { body: compressed, content_encoding: Some("br"), ..Self::json("") }
+    }
+}
+
+/// Serves the given responses in order, repeating the last one, and records the request lines.
+pub(crate) struct MockServer {
+    client: Client,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    pub(crate) async fn start(responses: Vec<MockResponse>) -> Self {
+        Self::start_with(responses, |builder| builder).await
+    }
+
+    /// Like [MockServer::start], with additional configuration of the client
+    pub(crate) async fn start_with(
+        responses: Vec<MockResponse>,
+        configure: impl FnOnce(ClientBuilder) -> ClientBuilder,
+    ) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/api", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            let mut responses = responses.into_iter();
+            let mut last = None;
+            loop {
+                let (mut stream, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                let request = read_request_head(&mut stream).await;
+                recorded.lock().unwrap().push(request.lines().next().unwrap_or_default().into());
+
+                let response: MockResponse = match responses.next() {
+                    Some(response) => {
+                        last = Some(response.clone());
+                        response
+                    }
+                    None => last.clone().expect("at least one response"),
+                };
+                tokio::time::sleep(response.delay).await;
+                let mut response = response;
+                if let Some(encoding) = response.content_encoding {
+                    if !accepts_encoding(&request, encoding) {
+                        response = MockResponse { status: 406, ..MockResponse::json("") };
+                    }
+                }
+                let encoding = response
+                    .content_encoding
+                    .map(|encoding| format!("Content-Encoding: {encoding}\r\n"))
+                    .unwrap_or_default();
+                let head = format!(
+                    "HTTP/1.1 {} OK\r\nContent-Type: {}\r\nContent-Length: {}\r\n{encoding}Connection: close\r\n\r\n",
+                    response.status,
+                    response.content_type,
+                    response.body.len()
+                );
+                let _ = stream.write_all(head.as_bytes()).await;
+                let _ = stream.write_all(&response.body).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        let builder = Client::builder()
+            .with_api_key("MOCK_KEY")
+            .with_api_url(url.as_str())
+            .unwrap()
+            .with_url("http://localhost")
+            .unwrap()
+            .with_rate_limit(0);
+        let client = configure(builder).build().unwrap();
+        Self { client, requests }
+    }
+
+    /// A client that sends its requests to this server
+    pub(crate) fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// The request lines received so far, e.g. `GET /api?module=... HTTP/1.1`
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn read_request_head(stream: &mut tokio::net::TcpStream) -> String {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => head.extend_from_slice(&buf[..n]),
+        }
+    }
+    String::from_utf8_lossy(&head).into_owned()
+}
+
+/// Returns whether the `Accept-Encoding` header of a request head lists `encoding`.
+fn accepts_encoding(request: &str, encoding: &str) -> bool {
+    request
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(name, _)| name.eq_ignore_ascii_case("accept-encoding"))
+        .flat_map(|(_, value)| value.split(','))
+        .any(|accepted| accepted.split(';').next().unwrap_or_default().trim() == encoding)
+}
+
+/// Returns the value of a query parameter of a recorded request line.
+pub(crate) fn query_param<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    let target = request.split(' ').nth(1)?;
+    let query = target.split_once('?')?.1;
+    query.split('&').find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+}
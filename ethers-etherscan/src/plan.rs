@@ -0,0 +1,130 @@
+//! The paid plans of the Etherscan API and the endpoints they unlock
+
+use std::fmt;
+
+/// An Etherscan API subscription, ordered from the free tier up.
+///
+/// A client configured with [ClientBuilder::with_plan](crate::ClientBuilder::with_plan) refuses
+/// requests to endpoints its plan doesn't include with
+/// [EtherscanError::PlanRequired](crate::errors::EtherscanError::PlanRequired), instead of
+/// spending a request on the explorer's rejection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ApiPlan {
+    #[default]
+    Free,
+    Standard,
+    Pro,
+    ProPlus,
+}
+
+impl ApiPlan {
+    /// Returns the rate limit of the plan, in requests per second.
+    pub fn rate_limit(self) -> u32 {
+        match self {
+            ApiPlan::Free => 5,
+            ApiPlan::Standard => 10,
+            ApiPlan::Pro | ApiPlan::ProPlus => 30,
+        }
+    }
+
+    /// Returns the cheapest plan including the endpoint of `module` and `action`.
+    pub fn required_for(module: &str, action: &str) -> ApiPlan {
+        PAID_ENDPOINTS
+            .iter()
+            .find(|(m, a, _)| *m == module && *a == action)
+            .map_or(ApiPlan::Free, |(_, _, plan)| *plan)
+    }
+}
+
+impl fmt::Display for ApiPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiPlan::Free => write!(f, "Free"),
+            ApiPlan::Standard => write!(f, "Standard"),
+            ApiPlan::Pro => write!(f, "Pro"),
+            ApiPlan::ProPlus => write!(f, "Pro Plus"),
+        }
+    }
+}
+
+/// The endpoints that need a paid plan by module and action, all others are free
+const PAID_ENDPOINTS: &[(&str, &str, ApiPlan)] = &[
+    ("account", "balancehistory", ApiPlan::Pro),
+    ("account", "tokenbalancehistory", ApiPlan::Pro),
+    ("account", "addresstokenbalance", ApiPlan::Pro),
+    ("account", "addresstokennftbalance", ApiPlan::Pro),
+    ("account", "addresstokennftinventory", ApiPlan::Pro),
+    ("stats", "tokensupplyhistory", ApiPlan::Pro),
+    ("stats", "dailyavgblocksize", ApiPlan::Pro),
+    ("stats", "dailyblkcount", ApiPlan::Pro),
+    ("stats", "dailyblockrewards", ApiPlan::Pro),
+    ("stats", "dailyavgblocktime", ApiPlan::Pro),
+    ("stats", "dailyuncleblkcount", ApiPlan::Pro),
+    ("stats", "dailyavggaslimit", ApiPlan::Pro),
+    ("stats", "dailygasused", ApiPlan::Pro),
+    ("stats", "dailyavggasprice", ApiPlan::Pro),
+    ("stats", "dailytxnfee", ApiPlan::Pro),
+    ("stats", "dailynewaddress", ApiPlan::Pro),
+    ("stats", "dailynetutilization", ApiPlan::Pro),
+    ("stats", "dailyavghashrate", ApiPlan::Pro),
+    ("stats", "dailytx", ApiPlan::Pro),
+    ("stats", "dailyavgnetdifficulty", ApiPlan::Pro),
+    ("stats", "ethdailymarketcap", ApiPlan::Pro),
+    ("stats", "ethdailyprice", ApiPlan::Pro),
+    ("token", "tokeninfo", ApiPlan::Pro),
+    ("token", "tokenholderlist", ApiPlan::Pro),
+    ("token", "topholders", ApiPlan::ProPlus),
+    ("nametag", "getaddresstag", ApiPlan::ProPlus),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        errors::EtherscanError,
+        mock::{MockResponse, MockServer},
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn looks_up_required_plan() {
+        assert_eq!(ApiPlan::required_for("account", "txlist"), ApiPlan::Free);
+        assert_eq!(ApiPlan::required_for("account", "balancehistory"), ApiPlan::Pro);
+        assert_eq!(ApiPlan::required_for("token", "topholders"), ApiPlan::ProPlus);
+        assert!(ApiPlan::Standard < ApiPlan::Pro);
+    }
+
+    #[tokio::test]
+    async fn refuses_endpoints_above_plan() {
+        let server = MockServer::start_with(
+            vec![MockResponse::json(r#"{"status":"1","message":"OK","result":"1"}"#)],
+            |builder| builder.with_plan(ApiPlan::Standard),
+        )
+        .await;
+        let client = server.client();
+        assert_eq!(client.plan(), Some(ApiPlan::Standard));
+
+        let query = client.create_query("account", "balancehistory", HashMap::<&str, &str>::new());
+        let err = client.get_json::<String, _>(&query).await.unwrap_err();
+        assert!(matches!(
+            err,
+            EtherscanError::PlanRequired { needed: ApiPlan::Pro, current: ApiPlan::Standard }
+        ));
+        assert!(server.requests().is_empty());
+
+        let query = client.create_query("account", "balance", HashMap::<&str, &str>::new());
+        client.get_json::<String, _>(&query).await.unwrap();
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn plan_sets_rate_limit() {
+        let client = crate::Client::builder()
+            .chain(ethers_core::types::Chain::Mainnet)
+            .unwrap()
+            .with_plan(ApiPlan::Pro)
+            .build()
+            .unwrap();
+        assert_eq!(client.rate_limit(), 30);
+    }
+}
@@ -0,0 +1,152 @@
+//! Client side rate limiting of requests
+
+use ethers_core::types::Chain;
+use std::{sync::Mutex, time::Duration};
+
+#[cfg(not(target_arch = "wasm32"))]
+use futures_timer::Delay;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use wasm_timer::{Delay, Instant};
+
+/// The free tier rate limit of the Etherscan family of explorers, in requests per second
+const ETHERSCAN_RATE_LIMIT: u32 = 5;
+
+/// The default rate limit of Blockscout instances, in requests per second
+const BLOCKSCOUT_RATE_LIMIT: u32 = 10;
+
+/// Returns the free tier rate limit of the explorer of `chain`, in requests per second.
+///
+/// Unknown explorers, e.g. of a client configured only with urls, get the Etherscan limit.
+pub(crate) fn default_rate_limit(chain: Option<Chain>) -> u32 {
+    use Chain::*;
+
+    match chain {
+        Some(
+            XDai | Chiado | Sokol | Poa | Rsk | Emerald | EmeraldTestnet | Evmos | EvmosTestnet |
+            Celo,
+        ) => BLOCKSCOUT_RATE_LIMIT,
+        _ => ETHERSCAN_RATE_LIMIT,
+    }
+}
+
+/// Spaces requests evenly to stay below a number of requests per second.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    requests_per_second: u32,
+    /// The earliest time the next request may be sent
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter for `requests_per_second`, `0` disables limiting.
+    pub(crate) fn new(requests_per_second: u32) -> Self {
+        Self { requests_per_second, next_slot: Mutex::new(None) }
+    }
+
+    pub(crate) fn requests_per_second(&self) -> u32 {
+        self.requests_per_second
+    }
+
+    /// Waits until the next request may be sent, returning how long it waited.
+    ///
+    /// Cancellation safe: if the returned future is dropped while waiting, its slot is handed
+    /// back unless later requests already queued behind it.
+    pub(crate) async fn acquire(&self) -> Duration {
+        if self.requests_per_second == 0 {
+            return Duration::ZERO
+        }
+        let interval = Duration::from_secs(1) / self.requests_per_second;
+        let (slot, wait) = {
+            let mut next_slot = self.next_slot.lock().unwrap();
+            let now = Instant::now();
+            let slot = next_slot.map_or(now, |slot| slot.max(now));
+            *next_slot = Some(slot + interval);
+            (slot, slot - now)
+        };
+        if !wait.is_zero() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(target: "etherscan", ?wait, "Waiting for the rate limit");
+            let reservation = Reservation { limiter: self, slot };
+            let _ = Delay::new(wait).await;
+            std::mem::forget(reservation);
+        }
+        wait
+    }
+}
+
+/// A slot taken by a request still waiting for it, handed back if the wait is cancelled
+struct Reservation<'a> {
+    limiter: &'a RateLimiter,
+    /// The start of the reserved slot
+    slot: Instant,
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        let interval = Duration::from_secs(1) / self.limiter.requests_per_second;
+        let mut next_slot = self.limiter.next_slot.lock().unwrap_or_else(|err| err.into_inner());
+        // only the latest reservation can be handed back without reordering the queue
+        if *next_slot == Some(self.slot + interval) {
+            *next_slot = Some(self.slot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
+
+    #[test]
+    fn defaults_to_explorer_limit() {
+        assert_eq!(default_rate_limit(Some(Chain::Mainnet)), 5);
+        assert_eq!(default_rate_limit(Some(Chain::Polygon)), 5);
+        assert_eq!(default_rate_limit(Some(Chain::XDai)), 10);
+        assert_eq!(default_rate_limit(None), 5);
+    }
+
+    #[test]
+    fn client_uses_chain_limit() {
+        let client = crate::Client::new(Chain::Celo, "API_KEY").unwrap();
+        assert_eq!(client.rate_limit(), 10);
+
+        let client = crate::Client::builder()
+            .chain(Chain::Mainnet)
+            .unwrap()
+            .with_rate_limit(30)
+            .build()
+            .unwrap();
+        assert_eq!(client.rate_limit(), 30);
+    }
+
+    #[tokio::test]
+    async fn spaces_requests() {
+        let server = MockServer::start_with(
+            vec![MockResponse::json(r#"{"status":"1","message":"OK","result":"1"}"#)],
+            |builder| builder.with_rate_limit(20),
+        )
+        .await;
+
+        let start = Instant::now();
+        for _ in 0..4 {
+            server.client().gas_estimate(1.into()).await.unwrap();
+        }
+        // the first request is sent immediately, then one every 50ms
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn cancelled_wait_releases_slot() {
+        let limiter = RateLimiter::new(10);
+        limiter.acquire().await;
+        let start = Instant::now();
+        let cancelled = tokio::time::timeout(Duration::from_millis(10), limiter.acquire()).await;
+        assert!(cancelled.is_err());
+
+        // waits for the slot the cancelled request gave up, not the one after it
+        limiter.acquire().await;
+        assert!(start.elapsed() < Duration::from_millis(150));
+    }
+}
@@ -0,0 +1,145 @@
+//! Retrying of failed requests
+
+use crate::errors::EtherscanError;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use futures_timer::Delay;
+#[cfg(target_arch = "wasm32")]
+use wasm_timer::Delay;
+
+/// Decides which failed requests are sent again, with exponential backoff
+///
+/// Requests that hit the rate limit, or fail with HTTP status `429`, are retried. Server errors
+/// and HTML error pages only if enabled with [RetryPolicy::retry_on_html]. The default policy never
+/// retries. Set with [ClientBuilder::with_retry_policy](crate::ClientBuilder::with_retry_policy).
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    retry_on_html: bool,
+}
+
+impl Default for RetryPolicy {
+    /// Does not retry
+    fn default() -> Self {
+        Self::new(0, Duration::ZERO)
+    }
+}
+
+impl RetryPolicy {
+    /// Retries up to `max_retries` times, waiting `initial_backoff` before the first retry and
+    /// doubling the wait for every following one.
+    pub fn new(max_retries: u32, initial_backoff: Duration) -> Self {
+        Self { max_retries, initial_backoff, retry_on_html: false }
+    }
+
+    /// Also retries responses with a `5xx` HTTP status, and responses that fail to deserialize
+    /// because they are an HTML page instead of JSON, which Etherscan serves during incidents.
+    ///
+    /// JSON responses that don't match the expected schema are never retried.
+    #[must_use]
+    pub fn retry_on_html(mut self, retry_on_html: bool) -> Self {
+        self.retry_on_html = retry_on_html;
+        self
+    }
+
+    /// Returns whether the request that failed with `err` after `attempt` retries should be sent
+    /// again. `body` is the response body, if one was received.
+    pub(crate) fn should_retry(
+        &self,
+        attempt: u32,
+        err: &EtherscanError,
+        body: Option<&str>,
+    ) -> bool {
+        if attempt >= self.max_retries {
+            return false
+        }
+        match err {
+            EtherscanError::RateLimitExceeded | EtherscanError::HttpStatus(429) => true,
+            EtherscanError::HttpStatus(status) => self.retry_on_html && *status >= 500,
+            EtherscanError::Serde(_) => self.retry_on_html && body.map_or(false, is_html),
+            _ => false,
+        }
+    }
+
+    /// Waits before the retry after `attempt` retries.
+    pub(crate) async fn backoff(&self, attempt: u32) {
+        let backoff = self.initial_backoff.saturating_mul(2u32.saturating_pow(attempt));
+        let _ = Delay::new(backoff).await;
+    }
+}
+
+/// Returns whether the response body is an HTML page.
+fn is_html(body: &str) -> bool {
+    body.trim_start().starts_with('<')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
+
+    fn html_response() -> MockResponse {
+        MockResponse {
+            status: 502,
+            content_type: "text/html",
+            body: b"<html><body><h1>502 Bad Gateway</h1></body></html>".to_vec(),
+            content_encoding: None,
+            delay: Duration::ZERO,
+        }
+    }
+
+    const GAS_ORACLE: &str = r#"{"status":"1","message":"OK","result":{"LastBlock":"1","SafeGasPrice":"1","ProposeGasPrice":"2","FastGasPrice":"3","suggestBaseFee":"0.5","gasUsedRatio":"0.5"}}"#;
+
+    #[tokio::test]
+    async fn retries_html_responses() {
+        let policy = RetryPolicy::new(2, Duration::from_millis(1)).retry_on_html(true);
+        let server = MockServer::start_with(
+            vec![html_response(), MockResponse::json(GAS_ORACLE)],
+            |builder| builder.with_retry_policy(policy),
+        )
+        .await;
+
+        server.client().gas_oracle().await.unwrap();
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_schema_mismatch() {
+        let policy = RetryPolicy::new(2, Duration::from_millis(1)).retry_on_html(true);
+        let server = MockServer::start_with(
+            vec![MockResponse::json(r#"{"status":"1","message":"OK","result":{"LastBlock":"1"}}"#)],
+            |builder| builder.with_retry_policy(policy),
+        )
+        .await;
+
+        let err = server.client().gas_oracle().await.unwrap_err();
+        assert!(matches!(err, EtherscanError::Serde(_)));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn html_is_not_retried_by_default() {
+        let server = MockServer::start(vec![html_response()]).await;
+
+        let err = server.client().gas_oracle().await.unwrap_err();
+        assert!(matches!(err, EtherscanError::HttpStatus(502)));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn stops_after_max_retries() {
+        let policy = RetryPolicy::new(1, Duration::ZERO);
+        assert!(policy.should_retry(0, &EtherscanError::RateLimitExceeded, None));
+        assert!(!policy.should_retry(1, &EtherscanError::RateLimitExceeded, None));
+        assert!(!policy.should_retry(0, &EtherscanError::InvalidApiKey, None));
+        assert!(policy.should_retry(0, &EtherscanError::HttpStatus(429), None));
+        assert!(!policy.should_retry(0, &EtherscanError::HttpStatus(502), None));
+        assert!(policy.clone().retry_on_html(true).should_retry(
+            0,
+            &EtherscanError::HttpStatus(502),
+            None
+        ));
+    }
+}
@@ -0,0 +1,194 @@
+//! Daily network statistics of the Etherscan API Pro plan
+
+use crate::{account::Sort, Client, Response, Result};
+use chrono::NaiveDate;
+use serde::{de, Deserialize, Deserializer};
+use std::collections::HashMap;
+
+/// A daily value of a network statistic
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DailyStat {
+    /// The UTC day
+    pub date: NaiveDate,
+    pub value: f64,
+}
+
+/// A row of a daily statistic response, e.g.
+/// `{"UTCDate":"2019-02-01","unixTimeStamp":"1548979200","networkUtilization":"0.8606"}`
+#[derive(Deserialize)]
+struct DailyStatRow {
+    #[serde(rename = "UTCDate", deserialize_with = "deserialize_date")]
+    date: NaiveDate,
+    #[serde(flatten)]
+    fields: HashMap<String, serde_json::Value>,
+}
+
+impl DailyStatRow {
+    /// Converts the row, taking the value from `field`, which holds a number or a numeric string.
+    fn into_stat(self, field: &'static str) -> Result<DailyStat> {
+        let value = match self.fields.get(field) {
+            Some(serde_json::Value::Number(value)) => value.as_f64(),
+            Some(serde_json::Value::String(value)) => value.parse().ok(),
+            Some(_) => None,
+            None => return Err(<serde_json::Error as de::Error>::missing_field(field).into()),
+        };
+        let value = value.ok_or_else(|| {
+            <serde_json::Error as de::Error>::custom(format!("invalid number in `{field}`"))
+        })?;
+        Ok(DailyStat { date: self.date, value })
+    }
+}
+
+fn deserialize_date<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<NaiveDate, D::Error> {
+    let date = String::deserialize(deserializer)?;
+    date.parse().map_err(de::Error::custom)
+}
+
+impl Client {
+    /// Returns the daily average gas used over gas limit, in percent
+    pub async fn daily_network_utilization(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        sort: Sort,
+    ) -> Result<Vec<DailyStat>> {
+        self.daily_stat("dailynetutilization", "networkUtilization", start_date, end_date, sort)
+            .await
+    }
+
+    /// Returns the daily average block size, in bytes
+    pub async fn daily_avg_block_size(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        sort: Sort,
+    ) -> Result<Vec<DailyStat>> {
+        self.daily_stat("dailyavgblocksize", "blockSize_bytes", start_date, end_date, sort).await
+    }
+
+    /// Returns the daily average time to mine a block, in seconds
+    pub async fn daily_avg_block_time(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        sort: Sort,
+    ) -> Result<Vec<DailyStat>> {
+        self.daily_stat("dailyavgblocktime", "blockTime_sec", start_date, end_date, sort).await
+    }
+
+    /// Returns the daily amount of block rewards paid to miners, in ether
+    pub async fn daily_block_rewards(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        sort: Sort,
+    ) -> Result<Vec<DailyStat>> {
+        self.daily_stat("dailyblockrewards", "blockRewards_Eth", start_date, end_date, sort).await
+    }
+
+    /// Returns the daily number of uncle blocks mined
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::{account::Sort, Client};
+    /// # use ethers_core::types::Chain;
+    /// use chrono::NaiveDate;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let uncles = client
+    ///         .daily_uncle_block_count(
+    ///             NaiveDate::from_ymd_opt(2019, 2, 1).unwrap(),
+    ///             NaiveDate::from_ymd_opt(2019, 2, 28).unwrap(),
+    ///             Sort::Asc,
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    /// # }
+    /// ```
+    pub async fn daily_uncle_block_count(
+        &self,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        sort: Sort,
+    ) -> Result<Vec<DailyStat>> {
+        self.daily_stat("dailyuncleblkcount", "uncleBlockCount", start_date, end_date, sort).await
+    }
+
+    /// Requests the daily statistic `action` between two days, inclusive, and reads the values
+    /// from `field`.
+    async fn daily_stat(
+        &self,
+        action: &'static str,
+        field: &'static str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        sort: Sort,
+    ) -> Result<Vec<DailyStat>> {
+        let query = self.create_query(
+            "stats",
+            action,
+            HashMap::from([
+                ("startdate", start_date.to_string()),
+                ("enddate", end_date.to_string()),
+                ("sort", sort.to_string()),
+            ]),
+        );
+        let response: Response<Vec<DailyStatRow>> = self.get_json(&query).await?;
+        response.result.into_iter().map(|row| row.into_stat(field)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        errors::EtherscanError,
+        mock::{query_param, MockResponse, MockServer},
+    };
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2019, 2, day).unwrap()
+    }
+
+    #[tokio::test]
+    async fn can_get_daily_stats() {
+        let server = MockServer::start(vec![
+            MockResponse::json(
+                r#"{"status":"1","message":"OK","result":[{"UTCDate":"2019-02-01","unixTimeStamp":"1548979200","networkUtilization":"0.8606"},{"UTCDate":"2019-02-02","unixTimeStamp":"1549065600","networkUtilization":"0.7714"}]}"#,
+            ),
+            MockResponse::json(
+                r#"{"status":"1","message":"OK","result":[{"UTCDate":"2019-02-01","unixTimeStamp":"1548979200","blockSize_bytes":20373}]}"#,
+            ),
+        ])
+        .await;
+
+        let utilization =
+            server.client().daily_network_utilization(date(1), date(2), Sort::Asc).await.unwrap();
+        assert_eq!(
+            utilization,
+            [
+                DailyStat { date: date(1), value: 0.8606 },
+                DailyStat { date: date(2), value: 0.7714 }
+            ]
+        );
+        let size =
+            server.client().daily_avg_block_size(date(1), date(1), Sort::Desc).await.unwrap();
+        assert_eq!(size, [DailyStat { date: date(1), value: 20373.0 }]);
+
+        let requests = server.requests();
+        assert_eq!(query_param(&requests[0], "action"), Some("dailynetutilization"));
+        assert_eq!(query_param(&requests[0], "startdate"), Some("2019-02-01"));
+        assert_eq!(query_param(&requests[0], "enddate"), Some("2019-02-02"));
+        assert_eq!(query_param(&requests[1], "sort"), Some("desc"));
+    }
+
+    #[test]
+    fn rejects_missing_field() {
+        let row: DailyStatRow =
+            serde_json::from_str(r#"{"UTCDate":"2019-02-01","blockTime_sec":"17.67"}"#).unwrap();
+        assert!(matches!(row.into_stat("uncleBlockCount"), Err(EtherscanError::Serde(_))));
+    }
+}
@@ -0,0 +1,122 @@
+//! Token metadata of the Etherscan API Pro plan
+
+use crate::{
+    check_ok, utils::deserialize_numeric_or_zero, Client, EtherscanError, Response, Result,
+};
+use ethers_core::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+use serde_aux::prelude::*;
+use std::collections::HashMap;
+
+/// The project information of a token, as returned by the `tokeninfo` endpoint
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenInfo {
+    pub contract_address: Address,
+    pub token_name: String,
+    pub symbol: String,
+    /// The number of decimals
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub divisor: u32,
+    /// E.g. `ERC20`
+    pub token_type: String,
+    #[serde(deserialize_with = "deserialize_numeric_or_zero")]
+    pub total_supply: U256,
+    /// Whether the token passed the explorer's verification of its project
+    #[serde(deserialize_with = "deserialize_bool_from_anything")]
+    pub blue_checkmark: bool,
+    pub description: String,
+    pub website: String,
+    /// The price in US dollars, may be empty
+    #[serde(rename = "tokenPriceUSD")]
+    pub token_price_usd: String,
+}
+
+impl Client {
+    /// Returns the project information of a token and stores it in the token metadata cache of
+    /// [Client::token_metadata].
+    ///
+    /// Fails with [EtherscanError::UnknownToken] if the explorer has no information on
+    /// `contract`.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let info = client
+    ///         .get_token_info(&"0xdAC17F958D2ee523a2206206994597C13D831ec7".parse().unwrap())
+    ///         .await
+    ///         .unwrap();
+    ///     assert_eq!(info.symbol, "USDT");
+    /// # }
+    /// ```
+    pub async fn get_token_info(&self, contract: &Address) -> Result<TokenInfo> {
+        let query = self.create_query(
+            "token",
+            "tokeninfo",
+            HashMap::from([("contractaddress", self.format_address(contract))]),
+        );
+        let response: Response<Vec<TokenInfo>> = self.get_json(&query).await?;
+        let infos = check_ok(&response.status, &response.message, response.result)?;
+        let info = infos.into_iter().next().ok_or(EtherscanError::UnknownToken(*contract))?;
+        self.token_metadata
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(*contract, info.clone());
+        Ok(info)
+    }
+
+    /// Returns the project information of a token from the token metadata cache, fetching it
+    /// with [Client::get_token_info] on a miss.
+    ///
+    /// The cache is kept in memory for the lifetime of the client and shared between its clones,
+    /// so formatting a long list of transfers requests each token once. Cached entries are never
+    /// refreshed, so the price and total supply may be stale.
+    pub async fn token_metadata(&self, contract: &Address) -> Result<TokenInfo> {
+        let cached = self
+            .token_metadata
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(contract)
+            .cloned();
+        match cached {
+            Some(info) => Ok(info),
+            None => self.get_token_info(contract).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockResponse, MockServer};
+
+    const TOKEN_INFO: &str = r#"{"status":"1","message":"OK","result":[{"contractAddress":"0xdac17f958d2ee523a2206206994597c13d831ec7","tokenName":"Tether USD","symbol":"USDT","divisor":"6","tokenType":"ERC20","totalSupply":"39823315228842796","blueCheckmark":"true","description":"Tether gives you the joint benefits of open blockchain technology and traditional currency.","website":"https://tether.to/","email":"","blog":"","reddit":"","slack":"","facebook":"","twitter":"https://twitter.com/Tether_to","bitcointalk":"","github":"","telegram":"","wechat":"","linkedin":"","discord":"","whitepaper":"https://tether.to/wp-content/uploads/2016/06/TetherWhitePaper.pdf","tokenPriceUSD":"1.000000000000000000"}]}"#;
+
+    #[tokio::test]
+    async fn caches_token_metadata() {
+        let server = MockServer::start(vec![
+            MockResponse::json(TOKEN_INFO),
+            MockResponse::json(r#"{"status":"1","message":"OK","result":[]}"#),
+        ])
+        .await;
+        let client = server.client().clone();
+        let usdt = "0xdac17f958d2ee523a2206206994597c13d831ec7".parse().unwrap();
+
+        let info = client.token_metadata(&usdt).await.unwrap();
+        assert_eq!(info.symbol, "USDT");
+        assert_eq!(info.divisor, 6);
+        assert!(info.blue_checkmark);
+        // served from the cache shared with the server's client
+        let info = server.client().token_metadata(&usdt).await.unwrap();
+        assert_eq!(info.total_supply, U256::from(39823315228842796u64));
+        assert_eq!(server.requests().len(), 1);
+
+        let unknown = Address::repeat_byte(1);
+        let err = client.token_metadata(&unknown).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::UnknownToken(contract) if contract == unknown));
+    }
+}
@@ -1,8 +1,20 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
+use ethers_core::types::{Address, Log, TransactionReceipt, H256, U256, U64};
 use serde::Deserialize;
 
-use crate::{Client, EtherscanError, Response, Result};
+use crate::{
+    account::{InternalTransaction, NormalTransaction, Tag, TxListParams},
+    logs::TokenTransfer,
+    Client, EtherscanError, ProxyResponse, Response, Result,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use futures_timer::Delay;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use wasm_timer::{Delay, Instant};
 
 #[derive(Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -16,6 +28,47 @@ struct TransactionReceiptStatus {
     status: String,
 }
 
+/// The part of a `proxy` transaction that is needed to follow it until it's mined
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProxyTransaction {
+    from: Address,
+    nonce: U256,
+    block_number: Option<U64>,
+    block_hash: Option<H256>,
+}
+
+/// A mined transaction with everything it caused, as shown on its explorer page
+#[derive(Clone, Debug)]
+pub struct TransactionDetail {
+    pub transaction: NormalTransaction,
+    pub internal_transactions: Vec<InternalTransaction>,
+    /// The ERC-20, ERC-721 and ERC-1155 transfers of the transaction, ordered by their log index
+    pub token_transfers: Vec<TokenTransfer>,
+}
+
+/// A listed transaction with the fields only its receipt has, see [Client::reconcile]
+#[derive(Clone, Debug)]
+pub struct ReconciledTransaction {
+    pub transaction: NormalTransaction,
+    /// The gas price actually paid, in wei
+    pub effective_gas_price: Option<U256>,
+    pub logs: Vec<Log>,
+    /// `1` if the transaction succeeded and `0` if it reverted, `None` before byzantium
+    pub status: Option<U64>,
+}
+
+impl ReconciledTransaction {
+    /// Combines a listed transaction with its receipt.
+    ///
+    /// Receipts from before the london fork have no effective gas price, the gas price of the
+    /// transaction is used instead since that's what was paid.
+    pub fn new(transaction: NormalTransaction, receipt: TransactionReceipt) -> Self {
+        let effective_gas_price = receipt.effective_gas_price.or(transaction.gas_price);
+        Self { transaction, effective_gas_price, logs: receipt.logs, status: receipt.status }
+    }
+}
+
 impl Client {
     /// Returns the status of a contract execution
     pub async fn check_contract_execution_status(&self, tx_hash: impl AsRef<str>) -> Result<()> {
@@ -48,4 +101,479 @@ impl Client {
             err => Err(EtherscanError::BadStatusCode(err.to_string())),
         }
     }
+
+    /// Waits until a transaction is mined with at least `confirmations` confirmations and is
+    /// listed by [Client::get_transactions], polling every `poll_interval`.
+    ///
+    /// The transaction is looked up with the `eth_getTransactionByHash` proxy, a transaction
+    /// mined in the latest block has one confirmation. Unknown transactions are polled until
+    /// `timeout`, since the explorer may not have seen them yet.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [EtherscanError::Timeout] if the transaction isn't confirmed within `timeout`,
+    /// and with [EtherscanError::TransactionReplaced] if another transaction of the sender with
+    /// the same nonce was mined instead.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    /// # use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let tx = client
+    ///         .wait_for_transaction(
+    ///             &"0x1f5c2f3a2e2fa86333e1ad0ab4a2ae2dfe9201c3ac7bc1d0d9a8c0f0b9c3ae5e".parse().unwrap(),
+    ///             3,
+    ///             Duration::from_secs(12),
+    ///             Duration::from_secs(300),
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    /// # }
+    /// ```
+    pub async fn wait_for_transaction(
+        &self,
+        hash: &H256,
+        confirmations: u64,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<NormalTransaction> {
+        let deadline = Instant::now() + timeout;
+        // the sender and nonce, once the transaction was seen
+        let mut sent = None;
+        loop {
+            match self.get_proxy_transaction(hash).await? {
+                Some(tx) => {
+                    sent = Some((tx.from, tx.nonce));
+                    match tx.block_number {
+                        Some(block) => {
+                            let block = block.as_u64();
+                            let head = self.get_block_number().await?;
+                            if head.saturating_add(1) >= block.saturating_add(confirmations) {
+                                if let Some(tx) =
+                                    self.find_transaction(hash, &tx.from, block).await?
+                                {
+                                    return Ok(tx)
+                                }
+                            }
+                        }
+                        None => self.ensure_not_replaced(hash, &tx.from, tx.nonce).await?,
+                    }
+                }
+                // dropped from the mempool, or not seen yet
+                None => {
+                    if let Some((from, nonce)) = sent {
+                        self.ensure_not_replaced(hash, &from, nonce).await?;
+                    }
+                }
+            }
+
+            if Instant::now() + poll_interval > deadline {
+                return Err(EtherscanError::Timeout)
+            }
+            let _ = Delay::new(poll_interval).await;
+        }
+    }
+
+    /// Returns whether a transaction listed earlier, e.g. by [Client::get_transactions], is still
+    /// included in the same block, using the `eth_getTransactionByHash` proxy.
+    ///
+    /// Returns `false` if a reorg dropped the transaction, put it back into the mempool, or
+    /// included it in a different block. The block is compared by hash, or by number for rows
+    /// without a block hash. Genesis allocations are always present.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let address = "0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap();
+    ///     for tx in client.get_transactions(&address, None).await.unwrap() {
+    ///         assert!(client.check_transaction_still_present(&tx).await.unwrap());
+    ///     }
+    /// # }
+    /// ```
+    pub async fn check_transaction_still_present(&self, tx: &NormalTransaction) -> Result<bool> {
+        let hash = match tx.hash.value() {
+            Some(hash) => hash,
+            None => return Ok(true),
+        };
+        let current = match self.get_proxy_transaction(hash).await? {
+            Some(current) => current,
+            None => return Ok(false),
+        };
+        Ok(match (tx.block_hash, current.block_hash) {
+            (Some(listed), Some(current)) => listed == U256::from_big_endian(current.as_bytes()),
+            (None, Some(_)) => current.block_number == tx.block_number.as_number(),
+            (_, None) => false,
+        })
+    }
+
+    /// Returns a mined transaction together with its internal transactions and token transfers.
+    ///
+    /// The sender and block of the transaction are looked up with the `eth_getTransactionByHash`
+    /// proxy, then the transaction is taken from the list of the sender in that block. The token
+    /// transfers are decoded from the logs of its receipt, like
+    /// [Client::get_token_transfers_for_tx]. This sends four requests.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [EtherscanError::TransactionNotFound] if the transaction is unknown, pending,
+    /// or not yet in the transaction list of its sender.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let detail = client
+    ///         .get_transaction_detail(
+    ///             &"0x40eb908387324f2b575b4879cd9d7188f69c8fc9d87c901b9e2daaea4b442170".parse().unwrap(),
+    ///         )
+    ///         .await
+    ///         .unwrap();
+    ///     println!("{} internal transactions", detail.internal_transactions.len());
+    /// # }
+    /// ```
+    pub async fn get_transaction_detail(&self, hash: &H256) -> Result<TransactionDetail> {
+        let not_found = || EtherscanError::TransactionNotFound(*hash);
+        let (from, block) = match self.get_proxy_transaction(hash).await? {
+            Some(ProxyTransaction { from, block_number: Some(block), .. }) => (from, block),
+            _ => return Err(not_found()),
+        };
+        let block = block.as_u64();
+        let transaction = self.find_transaction(hash, &from, block).await?.ok_or_else(not_found)?;
+        let internal_transactions = self.get_internal_transactions_for_tx(hash).await?;
+
+        let receipt = self.get_transaction_receipt(hash).await?.ok_or_else(not_found)?;
+        let mut token_transfers: Vec<_> =
+            receipt.logs.iter().flat_map(TokenTransfer::from_log).collect();
+        token_transfers.sort_by_key(|transfer| transfer.log_index);
+
+        Ok(TransactionDetail { transaction, internal_transactions, token_transfers })
+    }
+
+    /// Returns the receipt of a listed transaction, using the `eth_getTransactionReceipt` proxy.
+    ///
+    /// Returns `None` if the transaction is pending or no longer known, e.g. after a reorg, and
+    /// for the genesis rows, which have no receipt.
+    ///
+    /// ```no_run
+    /// # use ethers_etherscan::Client;
+    /// # use ethers_core::types::Chain;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///     let client = Client::new(Chain::Mainnet, "API_KEY").unwrap();
+    ///     let address = "0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap();
+    ///     for tx in client.get_transactions(&address, None).await.unwrap() {
+    ///         let receipt = client.fetch_receipt(&tx).await.unwrap();
+    ///         println!("{:?}", receipt.map(|receipt| receipt.logs.len()));
+    ///     }
+    /// # }
+    /// ```
+    pub async fn fetch_receipt(
+        &self,
+        tx: &NormalTransaction,
+    ) -> Result<Option<TransactionReceipt>> {
+        match tx.hash.value() {
+            Some(hash) => self.get_transaction_receipt(hash).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the receipt of a transaction using the `eth_getTransactionReceipt` proxy, `None`
+    /// if it is pending or unknown.
+    pub(crate) async fn get_transaction_receipt(
+        &self,
+        hash: &H256,
+    ) -> Result<Option<TransactionReceipt>> {
+        let query = self.create_query(
+            "proxy",
+            "eth_getTransactionReceipt",
+            HashMap::from([("txhash", format!("{hash:?}"))]),
+        );
+        let response: ProxyResponse<Option<TransactionReceipt>> = self.get_json_rpc(&query).await?;
+        Ok(response.result)
+    }
+
+    /// Adds the effective gas price, logs and status from its receipt to a listed transaction,
+    /// see [Self::fetch_receipt] and [ReconciledTransaction::new].
+    ///
+    /// Returns `None` if the transaction has no receipt.
+    pub async fn reconcile(&self, tx: NormalTransaction) -> Result<Option<ReconciledTransaction>> {
+        let receipt = self.fetch_receipt(&tx).await?;
+        Ok(receipt.map(|receipt| ReconciledTransaction::new(tx, receipt)))
+    }
+
+    async fn get_proxy_transaction(&self, hash: &H256) -> Result<Option<ProxyTransaction>> {
+        let query = self.create_query(
+            "proxy",
+            "eth_getTransactionByHash",
+            HashMap::from([("txhash", format!("{hash:?}"))]),
+        );
+        let response: ProxyResponse<Option<ProxyTransaction>> = self.get_json_rpc(&query).await?;
+        Ok(response.result)
+    }
+
+    /// Fails with [EtherscanError::TransactionReplaced] if the nonce of the pending transaction
+    /// `hash` was used by a mined transaction other than it.
+    async fn ensure_not_replaced(&self, hash: &H256, from: &Address, nonce: U256) -> Result<()> {
+        if self.get_transaction_count(from, Some(Tag::Latest)).await? <= nonce {
+            return Ok(())
+        }
+        // the transaction itself may have been mined since it was looked up
+        match self.get_proxy_transaction(hash).await? {
+            Some(ProxyTransaction { block_number: Some(_), .. }) => Ok(()),
+            _ => Err(EtherscanError::TransactionReplaced(*hash)),
+        }
+    }
+
+    /// Returns the transaction `hash` of `from` in `block` from the transaction list, which may
+    /// lag behind the proxy.
+    async fn find_transaction(
+        &self,
+        hash: &H256,
+        from: &Address,
+        block: u64,
+    ) -> Result<Option<NormalTransaction>> {
+        let params = TxListParams::default().start_block(block).end_block(block);
+        let txs = self.get_transactions(from, Some(params)).await?;
+        Ok(txs.into_iter().find(|tx| tx.hash.value() == Some(hash)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fixtures::{empty_list, list_response, with_fields, NORMAL_TRANSACTION},
+        logs::TokenStandard,
+        mock::{query_param, MockResponse, MockServer},
+    };
+
+    const HASH: &str = "0xc276cc8d0b5e9b5ef5a9e3e41e7dd2c4e5cb34af571b9fd4e9a4bf77d1d1a6b8";
+
+    fn proxy_tx(block_number: Option<&str>) -> MockResponse {
+        MockResponse::json(
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "hash": HASH,
+                    "from": "0x9aa99c23f67c81701c772b106b4f83f6e858dd2e",
+                    "nonce": "0x5",
+                    "blockNumber": block_number,
+                }
+            })
+            .to_string(),
+        )
+    }
+
+    fn proxy_result(result: &str) -> MockResponse {
+        MockResponse::json(format!(r#"{{"jsonrpc":"2.0","id":1,"result":{result}}}"#))
+    }
+
+    /// The fixture transaction of `HASH`, listed in block 16
+    fn tx_list() -> MockResponse {
+        let tx = with_fields(
+            NORMAL_TRANSACTION,
+            serde_json::json!({ "blockNumber": "16", "hash": HASH, "nonce": "5" }),
+        );
+        MockResponse::json(list_response(&[&tx.to_string()]))
+    }
+
+    #[tokio::test]
+    async fn detects_reorged_transactions() {
+        let in_block = |block_hash: Option<&str>| {
+            proxy_result(
+                &serde_json::json!({
+                    "hash": HASH,
+                    "from": "0x9aa99c23f67c81701c772b106b4f83f6e858dd2e",
+                    "nonce": "0x5",
+                    "blockNumber": block_hash.map(|_| "0x10"),
+                    "blockHash": block_hash,
+                })
+                .to_string(),
+            )
+        };
+        let server = MockServer::start(vec![
+            tx_list(),
+            in_block(Some("0xb9367ac1df1a7c1e2cd93e4c2b1bcf2b1a6c25a2c1b74d7f58e1bbe3c10f8e2e")),
+            in_block(Some("0x1111111111111111111111111111111111111111111111111111111111111111")),
+            in_block(None),
+            proxy_result("null"),
+        ])
+        .await;
+        let client = server.client();
+        let address = "0x9aa99c23f67c81701c772b106b4f83f6e858dd2e".parse().unwrap();
+        let tx = client.get_transactions(&address, None).await.unwrap().remove(0);
+
+        let mut present = Vec::new();
+        for _ in 0..4 {
+            present.push(client.check_transaction_still_present(&tx).await.unwrap());
+        }
+        assert_eq!(present, [true, false, false, false]);
+        assert_eq!(query_param(&server.requests()[1], "txhash"), Some(HASH));
+    }
+
+    async fn wait(server: &MockServer, timeout: Duration) -> Result<NormalTransaction> {
+        let hash = HASH.parse().unwrap();
+        server.client().wait_for_transaction(&hash, 2, Duration::from_millis(1), timeout).await
+    }
+
+    #[tokio::test]
+    async fn waits_for_confirmations() {
+        let server = MockServer::start(vec![
+            proxy_tx(None),
+            // the nonce is not used yet
+            proxy_result("\"0x5\""),
+            proxy_tx(Some("0x10")),
+            // one confirmation
+            proxy_result("\"0x10\""),
+            proxy_tx(Some("0x10")),
+            proxy_result("\"0x11\""),
+            tx_list(),
+        ])
+        .await;
+
+        let tx = wait(&server, Duration::from_secs(10)).await.unwrap();
+        assert_eq!(tx.hash.value(), Some(&HASH.parse().unwrap()));
+        let requests = server.requests();
+        assert_eq!(requests.len(), 7);
+        assert_eq!(query_param(&requests[6], "startBlock"), Some("16"));
+    }
+
+    #[tokio::test]
+    async fn detects_replaced_transaction() {
+        let server = MockServer::start(vec![
+            proxy_tx(None),
+            proxy_result("\"0x6\""),
+            // still pending after the nonce was used
+            proxy_tx(None),
+        ])
+        .await;
+
+        let err = wait(&server, Duration::from_secs(10)).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::TransactionReplaced(_)));
+    }
+
+    /// A receipt of [HASH] with the given logs
+    fn receipt(logs: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "transactionHash": HASH,
+            "transactionIndex": "0x0",
+            "blockHash": "0xb9367ac1df1a7c1e2cd93e4c2b1bcf2b1a6c25a2c1b74d7f58e1bbe3c10f8e2e",
+            "blockNumber": "0x10",
+            "from": "0x9aa99c23f67c81701c772b106b4f83f6e858dd2e",
+            "to": "0xdac17f958d2ee523a2206206994597c13d831ec7",
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "contractAddress": null,
+            "logs": logs,
+            "status": "0x1",
+            "logsBloom": format!("0x{}", "0".repeat(512)),
+            "effectiveGasPrice": "0x2",
+        })
+    }
+
+    #[tokio::test]
+    async fn can_get_transaction_detail() {
+        let topic = |address: &str| format!("0x{address:0>64}");
+        let (pool, router) = ("11".repeat(20), "22".repeat(20));
+        let transfer = |index: &str, event: &str, topics: Vec<String>, data: String| {
+            let mut topics = topics;
+            topics.insert(0, format!("{:?}", H256(ethers_core::utils::keccak256(event))));
+            serde_json::json!({
+                "address": "0xdac17f958d2ee523a2206206994597c13d831ec7",
+                "topics": topics,
+                "data": data,
+                "logIndex": index,
+            })
+        };
+        let logs = serde_json::json!([
+            // a swap hop between a pool and a router, neither of them the sender
+            transfer(
+                "0x3",
+                "Transfer(address,address,uint256)",
+                vec![topic(&pool), topic(&router)],
+                format!("0x{:0>64}", "3e8"),
+            ),
+            transfer(
+                "0x1",
+                "TransferSingle(address,address,address,uint256,uint256)",
+                vec![topic(&pool), topic(&pool), topic(&router)],
+                format!("0x{:0>64}{:0>64}", "7", "2"),
+            ),
+        ]);
+        let server = MockServer::start(vec![
+            proxy_tx(Some("0x10")),
+            tx_list(),
+            MockResponse::json(empty_list()),
+            proxy_result(&receipt(logs).to_string()),
+        ])
+        .await;
+
+        let hash = HASH.parse().unwrap();
+        let detail = server.client().get_transaction_detail(&hash).await.unwrap();
+        assert_eq!(detail.transaction.hash.value(), Some(&hash));
+        assert!(detail.internal_transactions.is_empty());
+        let standards: Vec<_> =
+            detail.token_transfers.iter().map(|transfer| transfer.standard).collect();
+        assert_eq!(standards, [TokenStandard::Erc1155, TokenStandard::Erc20]);
+        assert_eq!(detail.token_transfers[1].from, pool.parse().unwrap());
+        assert_eq!(detail.token_transfers[1].value, 1000.into());
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(query_param(&requests[3], "action"), Some("eth_getTransactionReceipt"));
+    }
+
+    #[tokio::test]
+    async fn can_reconcile_with_receipt() {
+        let receipt = receipt(serde_json::json!([{
+            "address": "0xdac17f958d2ee523a2206206994597c13d831ec7",
+            "topics": [],
+            "data": "0x",
+        }]));
+        let server =
+            MockServer::start(vec![proxy_result(&receipt.to_string()), proxy_result("null")]).await;
+
+        let tx = crate::fixtures::normal_transaction();
+        let reconciled = server.client().reconcile(tx.clone()).await.unwrap().unwrap();
+        assert_eq!(reconciled.effective_gas_price, Some(2.into()));
+        assert_eq!(reconciled.status, Some(1.into()));
+        assert_eq!(reconciled.logs.len(), 1);
+        let request = &server.requests()[0];
+        assert_eq!(query_param(request, "action"), Some("eth_getTransactionReceipt"));
+
+        // pending
+        assert!(server.client().reconcile(tx).await.unwrap().is_none());
+        let genesis = crate::fixtures::genesis_transaction();
+        assert!(server.client().fetch_receipt(&genesis).await.unwrap().is_none());
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn pending_transaction_has_no_detail() {
+        let server = MockServer::start(vec![proxy_tx(None)]).await;
+
+        let err = server.client().get_transaction_detail(&HASH.parse().unwrap()).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::TransactionNotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn times_out_on_unknown_transaction() {
+        let server = MockServer::start(vec![proxy_result("null")]).await;
+
+        let err = wait(&server, Duration::from_millis(20)).await.unwrap_err();
+        assert!(matches!(err, EtherscanError::Timeout));
+    }
 }
@@ -1,7 +1,7 @@
 use crate::{contract::SourceCodeMetadata, EtherscanError, Result};
-use ethers_core::types::Address;
+use ethers_core::types::{Address, U256};
 use semver::Version;
-use serde::{Deserialize, Deserializer};
+use serde::{de::Error as _, Deserialize, Deserializer};
 
 static SOLC_BIN_LIST_URL: &str = "https://binaries.soliditylang.org/bin/list.txt";
 
@@ -33,6 +33,78 @@ pub fn deserialize_address_opt<'de, D: Deserializer<'de>>(
     }
 }
 
+/// A number as returned by explorers, either a JSON number or a numeric string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Numeric {
+    String(String),
+    Num(u64),
+}
+
+/// Parses a decimal or `0x` prefixed hexadecimal number, as returned by explorers using the
+/// Etherscan API shape. Leading zeros and surrounding whitespace are accepted.
+fn parse_numeric(s: &str) -> std::result::Result<U256, String> {
+    let s = s.trim();
+    let hex = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X"));
+    match hex {
+        Some(hex) => {
+            let digits = hex.trim_start_matches('0');
+            if digits.len() > 64 {
+                return Err(format!("hex number {s} does not fit into 256 bits"))
+            }
+            if digits.is_empty() {
+                // `0x` is zero, as in the proxy responses
+                return match hex.chars().all(|c| c == '0') {
+                    true => Ok(U256::zero()),
+                    false => Err(format!("invalid hex number {s}")),
+                }
+            }
+            U256::from_str_radix(digits, 16).map_err(|err| format!("invalid hex number {s}: {err}"))
+        }
+        None if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) => {
+            U256::from_dec_str(s).map_err(|err| format!("invalid number {s}: {err}"))
+        }
+        None => Err(format!("invalid number {s}")),
+    }
+}
+
+/// Parses a number or numeric string, returning `None` for `null` and the `-` or empty string
+/// placeholders that some rows of the genesis and other early blocks hold.
+pub fn deserialize_numeric_or_none<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<U256>, D::Error> {
+    match Option::<Numeric>::deserialize(deserializer)? {
+        Some(Numeric::String(s)) if s.trim().is_empty() || s.trim() == "-" => Ok(None),
+        Some(Numeric::String(s)) => parse_numeric(&s).map(Some).map_err(D::Error::custom),
+        Some(Numeric::Num(num)) => Ok(Some(num.into())),
+        None => Ok(None),
+    }
+}
+
+/// Like [deserialize_numeric_or_none], returning zero for the placeholders.
+pub fn deserialize_numeric_or_zero<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<U256, D::Error> {
+    Ok(deserialize_numeric_or_none(deserializer)?.unwrap_or_default())
+}
+
+/// Like [deserialize_numeric_or_none], for numbers that fit into a `u64`.
+pub fn deserialize_u64_or_none<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<u64>, D::Error> {
+    match deserialize_numeric_or_none(deserializer)? {
+        Some(num) => num.try_into().map(Some).map_err(D::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// Like [deserialize_numeric_or_zero], for numbers that fit into a `u64`.
+pub fn deserialize_u64_or_zero<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<u64, D::Error> {
+    Ok(deserialize_u64_or_none(deserializer)?.unwrap_or_default())
+}
+
 /// Deserializes as JSON:
 ///
 /// Object: `{ "SourceCode": { language: "Solidity", .. }, ..}`
@@ -72,6 +144,48 @@ mod tests {
     use super::*;
     use crate::contract::SourceCodeLanguage;
 
+    #[test]
+    fn can_deserialize_numeric_placeholders() {
+        #[derive(Deserialize)]
+        struct Test {
+            #[serde(deserialize_with = "deserialize_numeric_or_none")]
+            opt: Option<U256>,
+            #[serde(deserialize_with = "deserialize_u64_or_zero")]
+            zero: u64,
+        }
+
+        for placeholder in ["\"-\"", "\"\"", "\" - \""] {
+            let json = format!(r#"{{"opt":{placeholder},"zero":{placeholder}}}"#);
+            let test: Test = serde_json::from_str(&json).unwrap();
+            assert_eq!((test.opt, test.zero), (None, 0));
+        }
+
+        let test: Test = serde_json::from_str(r#"{"opt":"0x10","zero":"21000"}"#).unwrap();
+        assert_eq!((test.opt, test.zero), (Some(16.into()), 21000));
+        let test: Test = serde_json::from_str(r#"{"opt":null,"zero":7}"#).unwrap();
+        assert_eq!((test.opt, test.zero), (None, 7));
+        assert!(serde_json::from_str::<Test>(r#"{"opt":"--","zero":"0"}"#).is_err());
+    }
+
+    #[test]
+    fn can_parse_numeric() {
+        let max = U256::MAX;
+        assert_eq!(parse_numeric("21000"), Ok(21000.into()));
+        assert_eq!(parse_numeric(&max.to_string()), Ok(max));
+        assert_eq!(parse_numeric("0x5208"), Ok(21000.into()));
+        assert_eq!(parse_numeric("0X5208"), Ok(21000.into()));
+        assert_eq!(parse_numeric(&format!("0x{}{max:x}", "0".repeat(10))), Ok(max));
+        assert_eq!(parse_numeric("0x"), Ok(U256::zero()));
+        assert_eq!(parse_numeric(" 7 "), Ok(7.into()));
+
+        assert!(parse_numeric(&format!("0x1{max:x}")).is_err());
+        assert!(parse_numeric(&format!("{max}0")).is_err());
+        assert!(parse_numeric("0xzz").is_err());
+        assert!(parse_numeric("1e3").is_err());
+        assert!(parse_numeric("-1").is_err());
+        assert!(parse_numeric("").is_err());
+    }
+
     #[test]
     fn can_deserialize_address_opt() {
         #[derive(serde::Serialize, Deserialize)]
@@ -116,7 +230,7 @@ mod tests {
         assert_eq!(de.source_code.sources().len(), 1);
         assert_eq!(de.source_code.sources().get("Contract").unwrap().content, src);
         #[cfg(feature = "ethers-solc")]
-        assert!(matches!(de.source_code.settings().unwrap(), None));
+        assert!(de.source_code.settings().unwrap().is_none());
 
         // Stringified JSON
         let json = r#"{
@@ -127,7 +241,7 @@ mod tests {
         assert_eq!(de.source_code.sources().len(), 1);
         assert_eq!(de.source_code.sources().get("Contract").unwrap().content, src);
         #[cfg(feature = "ethers-solc")]
-        assert!(matches!(de.source_code.settings().unwrap(), None));
+        assert!(de.source_code.settings().unwrap().is_none());
 
         let json = r#"{"source_code": "source code text"}"#;
         let de: Test = serde_json::from_str(json).unwrap();
@@ -135,7 +135,9 @@ impl Client {
         &self,
         contract: &VerifyContract,
     ) -> Result<Response<String>> {
-        let body = self.create_query("contract", "verifysourcecode", contract);
+        let mut form = serde_json::to_value(contract)?;
+        form["contractaddress"] = self.format_address(&contract.address).into();
+        let body = self.create_query("contract", "verifysourcecode", form);
         self.post_form(&body).await
     }
 
@@ -1,6 +1,10 @@
 use crate::*;
-use ethers_etherscan::account::{InternalTxQueryOption, TokenQueryOption};
+use ethers_etherscan::account::{
+    Direction, InternalTxQueryOption, LedgerSource, Tag, TokenQueryOption, TxListParams,
+};
+use futures_util::{StreamExt, TryStreamExt};
 use serial_test::serial;
+use std::collections::HashSet;
 
 #[tokio::test]
 #[serial]
@@ -17,6 +21,68 @@ async fn get_ether_balance_single_success() {
     .await
 }
 
+#[tokio::test]
+#[serial]
+async fn get_ether_balance_all_tags_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let balances = client
+            .get_ether_balance_all_tags(
+                &"0x58eB28A67731c570Ef827C365c89B5751F9E6b0a".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(balances.len(), 3);
+        assert!(balances.contains_key(&Tag::Earliest));
+        assert!(balances.contains_key(&Tag::Latest));
+        assert!(balances.contains_key(&Tag::Pending));
+    })
+    .await
+}
+
+#[tokio::test]
+#[serial]
+async fn get_first_transaction_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let tx = client
+            .get_first_transaction(&"0xa1e4380a3b1f749673e270229993ee55f35663b4".parse().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        // the first transaction of mainnet
+        assert_eq!(tx.block_number, 46147.into());
+    })
+    .await
+}
+
+#[tokio::test]
+#[serial]
+async fn get_last_transaction_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let address = "0x4F26FfBe5F04ED43630fdC30A87638d53D0b0876".parse().unwrap();
+        let last = client.get_last_transaction(&address).await.unwrap().unwrap();
+        let first = client.get_first_transaction(&address).await.unwrap().unwrap();
+        assert!(last.block_number.as_number() >= first.block_number.as_number());
+    })
+    .await
+}
+
+#[tokio::test]
+#[serial]
+async fn get_transaction_count_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        // the sender of the first transaction of block 46147
+        let count = client
+            .get_transaction_count(
+                &"0xa1e4380a3b1f749673e270229993ee55f35663b4".parse().unwrap(),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(count >= 1.into());
+    })
+    .await
+}
+
 #[tokio::test]
 #[serial]
 async fn get_ether_balance_multi_success() {
@@ -46,6 +112,42 @@ async fn get_transactions_success() {
     .await
 }
 
+#[tokio::test]
+#[serial]
+async fn get_transactions_stream_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let params = TxListParams::default().page_size(2);
+        let txs: Vec<_> = client
+            .get_transactions_stream(
+                &"0x4F26FfBe5F04ED43630fdC30A87638d53D0b0876".parse().unwrap(),
+                Some(params),
+            )
+            .take(5)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(txs.len(), 5);
+
+        let hashes: HashSet<_> = txs.iter().map(|tx| tx.hash.value().copied()).collect();
+        assert_eq!(hashes.len(), txs.len());
+    })
+    .await
+}
+
+#[tokio::test]
+#[serial]
+async fn get_transactions_between_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let a = "0x4F26FfBe5F04ED43630fdC30A87638d53D0b0876".parse().unwrap();
+        let b = "0xdAC17F958D2ee523a2206206994597C13D831ec7".parse().unwrap();
+        let txs = client.get_transactions_between(&a, &b, None).await.unwrap();
+        for tx in txs {
+            assert!(tx.to == Some(b) || tx.from.value() == Some(&b));
+        }
+    })
+    .await
+}
+
 #[tokio::test]
 #[serial]
 async fn get_internal_transactions_success() {
@@ -82,6 +184,23 @@ async fn get_internal_transactions_by_tx_hash_success() {
     .await
 }
 
+#[tokio::test]
+#[serial]
+async fn get_internal_transactions_for_tx_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let txs = client
+            .get_internal_transactions_for_tx(
+                &"0x40eb908387324f2b575b4879cd9d7188f69c8fc9d87c901b9e2daaea4b442170"
+                    .parse()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(!txs.is_empty());
+    })
+    .await
+}
+
 #[tokio::test]
 #[serial]
 async fn get_erc20_transfer_events_success() {
@@ -95,7 +214,7 @@ async fn get_erc20_transfer_events_success() {
             )
             .await
             .unwrap();
-        let tx = txs.get(0).unwrap();
+        let tx = txs.first().unwrap();
         assert_eq!(tx.gas_used, 93657u64.into());
         assert_eq!(tx.nonce, 10u64.into());
         assert_eq!(tx.block_number, 2228258u64.into());
@@ -103,6 +222,21 @@ async fn get_erc20_transfer_events_success() {
     .await
 }
 
+#[tokio::test]
+#[serial]
+async fn get_erc20_transfer_events_by_direction_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let address = "0x4e83362442b8d1bec281594cea3050c8eb01311c".parse().unwrap();
+        let txs = client
+            .get_erc20_token_transfer_events_by_direction(&address, None, Direction::Out, None)
+            .await
+            .unwrap();
+        assert!(!txs.is_empty());
+        assert!(txs.iter().all(|tx| tx.from == address));
+    })
+    .await
+}
+
 #[tokio::test]
 #[serial]
 async fn get_erc721_transfer_events_success() {
@@ -166,3 +300,46 @@ async fn get_avalanche_transactions() {
     })
     .await
 }
+
+#[tokio::test]
+#[serial]
+async fn get_validated_blocks_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let blocks = client
+            .get_validated_blocks(
+                &"0x95222290DD7278Aa3Ddd389Cc1E1d165CC4BAfe5".parse().unwrap(),
+                Some((1, 10)),
+            )
+            .await
+            .unwrap();
+        assert!(!blocks.is_empty());
+    })
+    .await
+}
+
+#[tokio::test]
+#[serial]
+async fn get_full_ledger_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let ledger = client
+            .get_full_ledger(&"0x2c1ba59d6f58433fb1eaee7d20b26ed83bda51a3".parse().unwrap(), None)
+            .await
+            .unwrap();
+        assert!(ledger.iter().any(|entry| entry.source == LedgerSource::Normal));
+        assert!(ledger.iter().any(|entry| entry.source == LedgerSource::Internal));
+    })
+    .await
+}
+
+#[tokio::test]
+#[serial]
+async fn get_deployed_contracts_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        // the Uniswap V2 factory deploys pairs with create2
+        let factory = "0x5c69bee701ef814a2b6a3edd4b1652cb9cc5aa6f".parse().unwrap();
+        let params = TxListParams::default().start_block(10008355).end_block(10100000);
+        let contracts = client.get_deployed_contracts(&factory, Some(params)).await.unwrap();
+        assert!(!contracts.is_empty());
+    })
+    .await
+}
@@ -97,3 +97,23 @@ async fn can_fetch_contract_source_tree_for_multi_entry_contract() {
     })
     .await
 }
+
+#[tokio::test]
+#[serial]
+async fn can_get_contract_bytecode() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let code = client
+            .get_contract_bytecode(&"0xBB9bc244D798123fDe783fCc1C72d3Bb8C189413".parse().unwrap())
+            .await
+            .unwrap();
+        assert!(!code.is_empty());
+
+        // an externally owned account
+        let code = client
+            .get_contract_bytecode(&"0x4e83362442b8d1bec281594cea3050c8eb01311c".parse().unwrap())
+            .await
+            .unwrap();
+        assert!(code.is_empty());
+    })
+    .await
+}
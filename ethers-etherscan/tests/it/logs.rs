@@ -0,0 +1,39 @@
+use crate::*;
+use ethers_core::types::Chain;
+use ethers_etherscan::logs::LogQueryParams;
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn get_logs_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let params = LogQueryParams {
+            address: Some("0xbd3531da5cf5857e7cfaa92426877b022e612cf8".parse().unwrap()),
+            from_block: Some(12878196.into()),
+            to_block: Some(12878196.into()),
+            ..Default::default()
+        };
+        let logs = client.get_logs(params).await.unwrap();
+
+        assert!(!logs.is_empty());
+        assert!(logs.iter().all(|log| log.block_number == Some(12878196.into())));
+    })
+    .await
+}
+
+#[tokio::test]
+#[serial]
+async fn get_token_transfers_for_tx_success() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        let transfers = client
+            .get_token_transfers_for_tx(
+                &"0x40eb908387324f2b575b4879cd9d7188f69c8fc9d87c901b9e2daaea4b442170"
+                    .parse()
+                    .unwrap(),
+            )
+            .await;
+
+        transfers.unwrap();
+    })
+    .await
+}
@@ -12,6 +12,7 @@ use std::{
 mod account;
 mod contract;
 mod gas;
+mod logs;
 mod transaction;
 mod verify;
 mod version;
@@ -27,6 +28,44 @@ async fn check_wrong_etherscan_api_key() {
     assert!(matches!(resp, EtherscanError::InvalidApiKey));
 }
 
+#[tokio::test]
+async fn can_verify_chain() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        assert_eq!(client.detect_chain().await.unwrap(), Chain::Mainnet);
+        client.verify_chain().await.unwrap();
+    })
+    .await
+}
+
+#[tokio::test]
+async fn can_get_block_number() {
+    run_with_client(Chain::Mainnet, |client| async move {
+        // any block after the Merge
+        assert!(client.get_block_number().await.unwrap() > 15_537_393);
+    })
+    .await
+}
+
+#[tokio::test]
+async fn check_chain_mismatch() {
+    run_with_client(Chain::Mainnet, |_| async move {
+        let client = Client::builder()
+            .with_api_key(std::env::var("ETHERSCAN_API_KEY").unwrap_or_default())
+            .chain(Chain::Mainnet)
+            .unwrap()
+            .with_api_url("https://api-goerli.etherscan.io/api")
+            .unwrap()
+            .build()
+            .unwrap();
+        let err = client.verify_chain().await.unwrap_err();
+        assert!(matches!(
+            err,
+            EtherscanError::ChainMismatch { expected: Chain::Mainnet, actual: 5 }
+        ));
+    })
+    .await
+}
+
 /// Calls the function with a new Etherscan Client.
 pub async fn run_with_client<F, Fut, T>(chain: Chain, f: F) -> T
 where